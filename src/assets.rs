@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::Path;
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
 use crate::software_renderer::bitmap::Bitmap;
 use crate::software_renderer::blit::blit_bitmap_to_surface;
@@ -28,17 +29,17 @@ pub struct Assets {
 }
 
 impl Assets {
-    pub fn new(fs: &FileSystem) -> Assets {
+    pub fn new(fs: &FileSystem) -> FileSystemResult<Assets> {
 
-        Assets {
+        Ok(Assets {
             sprite_info: HashMap::new(),
             assembly_frames: HashMap::new(),
             assemblies: HashMap::new(),
             palettes: HashMap::new(),
             bitmaps: HashMap::new(),
 
-            anim_sets: fs.read_sprite_animations(),
-        }
+            anim_sets: fs.read_sprite_animations()?,
+        })
     }
 
     pub fn get_sprite_info(&self, sprite_info_key: u64) -> &SpriteInfo {
@@ -85,67 +86,67 @@ impl Assets {
         self.palettes.get_mut(&palette_key).unwrap()
     }
 
-    pub fn load_sprite_assembly(&mut self, fs: &FileSystem, assembly_index: usize, size_flags: u32) -> u64 {
+    pub fn load_sprite_assembly(&mut self, fs: &FileSystem, assembly_index: usize, size_flags: u32) -> FileSystemResult<u64> {
         let assembly_key = Assets::asset_key_sprite_assembly(assembly_index);
         if self.assemblies.contains_key(&assembly_key) {
-            return assembly_key;
+            return Ok(assembly_key);
         }
 
-        let (assembly, frames) = fs.read_sprite_assembly(assembly_index, size_flags);
+        let (assembly, frames) = fs.read_sprite_assembly(assembly_index, size_flags)?;
         self.assembly_frames.extend(frames);
         self.assemblies.insert(assembly_key, assembly);
         println!("Loaded sprite assembly {}", assembly_index);
 
-        assembly_key
+        Ok(assembly_key)
     }
 
-    pub fn load_sprite_palette(&mut self, fs: &FileSystem, palette_index: usize) -> u64 {
+    pub fn load_sprite_palette(&mut self, fs: &FileSystem, palette_index: usize) -> FileSystemResult<u64> {
         let palette_key = Assets::asset_key_palette_sprite_scene(palette_index);
         if self.palettes.contains_key(&palette_key) {
-            return palette_key;
+            return Ok(palette_key);
         }
 
-        let palette = fs.read_sprite_palette(palette_index, 0).unwrap();
+        let palette = fs.read_sprite_palette(palette_index, 0)?.ok_or(FileSystemError::InvalidData(format!("Sprite palette {} is empty", palette_index)))?;
         self.palettes.insert(palette_key, palette);
         println!("Loaded sprite palette {}", palette_index);
 
-        palette_key
+        Ok(palette_key)
     }
 
-    pub fn load_sprite_tiles(&mut self, fs: &FileSystem, sprite_index: usize, max_chip_count: usize) -> u64 {
+    pub fn load_sprite_tiles(&mut self, fs: &FileSystem, sprite_index: usize, max_chip_count: usize) -> FileSystemResult<u64> {
         let bitmap_key = Assets::asset_key_bitmap_sprite_tiles(sprite_index);
         if self.bitmaps.contains_key(&bitmap_key) {
-            return bitmap_key;
+            return Ok(bitmap_key);
         }
 
-        let bitmap = fs.read_sprite_tiles(sprite_index, max_chip_count);
+        let bitmap = fs.read_sprite_tiles(sprite_index, max_chip_count)?;
         self.bitmaps.insert(bitmap_key, bitmap);
         println!("Loaded sprite tiles for sprite {}", sprite_index);
 
-        bitmap_key
+        Ok(bitmap_key)
     }
 
-    pub fn load_sprite_info(&mut self, fs: &FileSystem, sprite_index: usize) -> u64 {
+    pub fn load_sprite_info(&mut self, fs: &FileSystem, sprite_index: usize) -> FileSystemResult<u64> {
         let sprite_info_key = Assets::asset_key_sprite_info(sprite_index);
         if self.sprite_info.contains_key(&sprite_info_key) {
-            return sprite_info_key;
+            return Ok(sprite_info_key);
         }
 
-        let info = fs.read_sprite_header(sprite_index);
-        let assembly_key = self.load_sprite_assembly(&fs, info.assembly_index, info.size_flags);
+        let info = fs.read_sprite_header(sprite_index)?;
+        let assembly_key = self.load_sprite_assembly(&fs, info.assembly_index, info.size_flags)?;
         let assembly = self.get_assembly(assembly_key);
 
         let sprite = SpriteInfo {
             index: sprite_index,
-            tiles_bitmap_key: self.load_sprite_tiles(&fs, info.bitmap_index, assembly.chip_max),
+            tiles_bitmap_key: self.load_sprite_tiles(&fs, info.bitmap_index, assembly.chip_max)?,
             assembly_key,
-            palette_key: self.load_sprite_palette(&fs, info.palette_index),
+            palette_key: self.load_sprite_palette(&fs, info.palette_index)?,
             anim_set_index: info.anim_index,
         };
         self.sprite_info.insert(sprite_info_key, sprite);
         println!("Loaded sprite info for sprite {}", sprite_index);
 
-        sprite_info_key
+        Ok(sprite_info_key)
     }
 
     pub fn dump(&self) {
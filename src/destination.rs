@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::{Context, GameMode};
 use crate::facing::Facing;
@@ -77,25 +77,25 @@ impl Destination {
         }
     }
 
-    pub fn from_cursor(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Destination {
+    pub fn from_cursor(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Destination, Error> {
         match mode {
             GameMode::Snes => {
-                let index_facing = data.read_u16::<LittleEndian>().unwrap() as usize;
+                let index_facing = data.read_u16::<LittleEndian>()? as usize;
                 let index = index_facing & 0x01FF;
                 let last_facing_byte = (index_facing >> 8) as u8;
                 let facing = ((last_facing_byte >> 1) & 0x0F) | (last_facing_byte & 0x80);
-                let tile_x = data.read_u8().unwrap() as i32;
-                let tile_y = data.read_u8().unwrap() as i32;
+                let tile_x = data.read_u8()? as i32;
+                let tile_y = data.read_u8()? as i32;
 
-                Destination::from_data(index, Facing::from_data(facing), tile_x, tile_y, 0, 0, facing)
+                Ok(Destination::from_data(index, Facing::from_data(facing), tile_x, tile_y, 0, 0, facing))
             },
             GameMode::Pc => {
-                let index = data.read_u16::<LittleEndian>().unwrap() as usize;
-                let facing = data.read_u8().unwrap();
-                let tile_x = data.read_u8().unwrap() as i32;
-                let tile_y = data.read_u8().unwrap() as i32;
+                let index = data.read_u16::<LittleEndian>()? as usize;
+                let facing = data.read_u8()?;
+                let tile_x = data.read_u8()? as i32;
+                let tile_y = data.read_u8()? as i32;
 
-                Destination::from_data(index, Facing::from_data(facing), tile_x, tile_y, 0, 0, facing)
+                Ok(Destination::from_data(index, Facing::from_data(facing), tile_x, tile_y, 0, 0, facing))
             }
         }
     }
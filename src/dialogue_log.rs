@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::renderer::{Renderer, TextFlags, TextFont, TextRenderable};
+use crate::software_renderer::blit::SurfaceBlendOps;
+use crate::software_renderer::text::TextDrawFlags;
+use crate::text_processor::{page_lines, TextPage};
+use crate::util::rect::Rect;
+
+// Oldest entries are dropped beyond this many.
+const DIALOGUE_LOG_MAX_ENTRIES: usize = 2000;
+
+const DIALOGUE_LOG_TEXT_COLOR: [u8; 4] = [231, 231, 231, 255];
+const DIALOGUE_LOG_HEADER_COLOR: [u8; 4] = [160, 200, 255, 255];
+
+/// A single textbox page that was shown.
+pub struct DialogueLogEntry {
+
+    /// Game time in seconds at which the page was shown.
+    pub time: f64,
+
+    /// Title of the scene or world it was shown in.
+    pub location: String,
+
+    /// The actor that the textbox was shown for.
+    pub actor_index: Option<usize>,
+
+    pub lines: Vec<String>,
+}
+
+impl DialogueLogEntry {
+    fn header(&self) -> String {
+        match self.actor_index {
+            Some(actor_index) => format!("[{}] {}, actor {}", format_time(self.time), self.location, actor_index),
+            None => format!("[{}] {}", format_time(self.time), self.location),
+        }
+    }
+}
+
+/// Records every textbox page that is shown, and renders it as a scrollable overlay.
+pub struct DialogueLog {
+    entries: VecDeque<DialogueLogEntry>,
+    time: f64,
+    location: String,
+
+    visible: bool,
+
+    // Number of lines scrolled up from the end of the log.
+    scroll: usize,
+
+    // Rendered text of the visible lines, recreated when the log or scroll position changes.
+    renderables: Vec<TextRenderable>,
+    dirty: bool,
+}
+
+impl Default for DialogueLog {
+    fn default() -> DialogueLog {
+        DialogueLog {
+            entries: VecDeque::new(),
+            time: 0.0,
+            location: String::new(),
+
+            visible: false,
+            scroll: 0,
+
+            renderables: Vec::new(),
+            dirty: true,
+        }
+    }
+}
+
+impl DialogueLog {
+    pub fn new() -> DialogueLog {
+        Self::default()
+    }
+
+    pub fn tick(&mut self, delta: f64) {
+        self.time += delta;
+    }
+
+    /// Sets the location that new entries are recorded for.
+    pub fn set_location(&mut self, location: String) {
+        self.location = location;
+    }
+
+    pub fn add_page(&mut self, actor_index: Option<usize>, page: &TextPage) {
+        if self.entries.len() >= DIALOGUE_LOG_MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(DialogueLogEntry {
+            time: self.time,
+            location: self.location.clone(),
+            actor_index,
+            lines: page_lines(page),
+        });
+        self.dirty = true;
+    }
+
+    pub fn get_entries(&self) -> &VecDeque<DialogueLogEntry> {
+        &self.entries
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visible(&mut self) {
+        self.visible = !self.visible;
+        self.scroll = 0;
+        self.dirty = true;
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.line_count().saturating_sub(1));
+        self.dirty = true;
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+        self.dirty = true;
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        for entry in self.entries.iter() {
+            writeln!(out, "{}", entry.header())?;
+            for line in entry.lines.iter() {
+                writeln!(out, "    {}", line)?;
+            }
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn export(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(&mut out)
+    }
+
+    // All lines of the log with a flag for header lines, as they are displayed in the overlay.
+    fn lines(&self) -> Vec<(String, bool)> {
+        let mut lines = Vec::new();
+        for entry in self.entries.iter() {
+            lines.push((entry.header(), true));
+            lines.extend(entry.lines.iter().map(|line| (format!("  {}", line), false)));
+        }
+
+        lines
+    }
+
+    fn line_count(&self) -> usize {
+        self.entries.iter().map(|entry| entry.lines.len() + 1).sum()
+    }
+
+    pub fn render(&mut self, render: &mut Renderer) {
+        if !self.visible {
+            return;
+        }
+
+        let width = render.target.width as i32;
+        let height = render.target.height as i32;
+        render.render_box_filled(Rect::new(0, 0, width, height), [0, 0, 0, 215], SurfaceBlendOps::Blend);
+
+        let (_, line_height) = render.measure_text("A", TextFont::Small);
+        let line_height = line_height.max(1) as i32;
+        let visible_lines = ((height - 4) / line_height).max(1) as usize;
+
+        // Fill the screen from the bottom, ending at the scroll position.
+        if self.dirty {
+            let lines = self.lines();
+            let end = lines.len() - self.scroll.min(lines.len());
+            let start = end.saturating_sub(visible_lines);
+            self.renderables = lines[start..end].iter()
+                .map(|(text, is_header)| {
+                    let color = if *is_header { DIALOGUE_LOG_HEADER_COLOR } else { DIALOGUE_LOG_TEXT_COLOR };
+                    TextRenderable::new(text.clone(), TextFont::Small, color, TextDrawFlags::SHADOW, 0)
+                })
+                .collect();
+            self.dirty = false;
+        }
+
+        if self.renderables.is_empty() {
+            let mut empty = TextRenderable::new("No dialogue yet.".to_string(), TextFont::Small, DIALOGUE_LOG_TEXT_COLOR, TextDrawFlags::SHADOW, 0);
+            render.render_text(&mut empty, width / 2, height / 2, TextFlags::AlignHCenter);
+            return;
+        }
+
+        let mut y = height - 2 - self.renderables.len() as i32 * line_height;
+        for renderable in self.renderables.iter_mut() {
+            render.render_text(renderable, 4, y, TextFlags::empty());
+            y += line_height;
+        }
+    }
+}
+
+// Formats seconds as minutes, seconds and tenths.
+fn format_time(time: f64) -> String {
+    let tenths = (time * 10.0) as u64;
+    format!("{:02}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_processor::TextPart;
+
+    #[test]
+    fn dialogue_log_export() {
+        let mut log = DialogueLog::new();
+        log.set_location("0 - Crono's Room".to_string());
+        log.tick(61.25);
+        log.add_page(Some(3), &vec![
+            TextPart::Text { text: "Crono!".to_string() },
+            TextPart::LineBreak,
+            TextPart::Text { text: "Wake up!".to_string() },
+        ]);
+        log.add_page(None, &vec![TextPart::Text { text: "...".to_string() }]);
+
+        let mut out = Vec::new();
+        log.write(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[01:01.2] 0 - Crono's Room, actor 3\n    Crono!\n    Wake up!\n\n[01:01.2] 0 - Crono's Room\n    ...\n\n",
+        );
+
+        // Scrolling stops at the first line.
+        log.scroll_up(100);
+        assert_eq!(log.scroll, 4);
+        log.scroll_down(1);
+        assert_eq!(log.scroll, 3);
+    }
+}
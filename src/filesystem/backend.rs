@@ -1,10 +1,12 @@
 use std::io::{BufRead, Cursor};
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
+use crate::party::items::ItemId;
 use crate::software_renderer::bitmap::Bitmap;
 use crate::software_renderer::palette::{Color, Palette};
 
 pub trait FileSystemBackendTrait {
-    fn read_text_string_list(&self, data: Cursor<Vec<u8>>, start: Option<usize>, end: Option<usize>) -> Vec<String> {
+    fn read_text_string_list(&self, data: Cursor<Vec<u8>>, start: Option<usize>, end: Option<usize>) -> FileSystemResult<Vec<String>> {
         let mut strings = Vec::<String>::new();
         for line in data.lines().map_while(Result::ok) {
             strings.push(line);
@@ -12,11 +14,14 @@ pub trait FileSystemBackendTrait {
 
         let real_start = if start.is_some() { start.unwrap() } else { 0 };
         let real_end = if end.is_some() { end.unwrap() } else { strings.len() };
+        if real_start > real_end || real_end > strings.len() {
+            return Err(FileSystemError::InvalidData(format!("Expected at least {} lines of text, found {}", real_end, strings.len())));
+        }
 
-        strings[real_start..real_end].to_vec()
+        Ok(strings[real_start..real_end].to_vec())
     }
 
-    fn read_palette(&self, mut data: Cursor<Vec<u8>>, skip: usize, set_size: usize, set_count: usize, set_start: usize, set_pad: usize) -> Palette {
+    fn read_palette(&self, mut data: Cursor<Vec<u8>>, skip: usize, set_size: usize, set_count: usize, set_start: usize, set_pad: usize) -> FileSystemResult<Palette> {
         let mut colors = Vec::<Color>::new();
         for _ in 0..skip {
             colors.push([0, 0, 0, 0xFF]);
@@ -28,7 +33,7 @@ pub trait FileSystemBackendTrait {
             }
 
             for _ in 0..set_size {
-                colors.push(FileSystem::read_color(&mut data));
+                colors.push(FileSystem::read_color(&mut data)?);
             }
 
             for _ in 0..set_pad {
@@ -36,7 +41,7 @@ pub trait FileSystemBackendTrait {
             }
         }
 
-        Palette::from_colors(&colors)
+        Ok(Palette::from_colors(&colors))
     }
 
     fn convert_planar_chips_to_linear(&self, data: Vec<u8>, width: usize, bitplanes: usize) -> Vec<u8> {
@@ -96,53 +101,62 @@ pub trait FileSystemBackendTrait {
         pixels
     }
 
-    fn get_world_header_data(&self, world_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_map_tile_data(&self, world_map_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_tileset12_graphics(&self, chips_index: usize) -> Option<Vec<u8>>;
-    fn get_world_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>>;
-    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_music_data(&self, music_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_exits_triggers_data(&self, exits_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_exit_names(&self, language: &str) -> Vec<String>;
-    fn get_world_names(&self, language: &str) -> Vec<String>;
-    fn get_world_sprite_data(&self) -> Vec<u8>;
-    fn get_world_sprite_graphics(&self, world_index: usize, tiles_index: usize) -> Option<Vec<u8>>;
-    fn get_world_player_sprite_graphics(&self) -> Option<Vec<u8>>;
-    fn get_world_epoch_sprite_graphics(&self) -> Option<Vec<u8>>;
-    fn get_world_palette(&self, world_palette_index: usize) -> Palette;
-    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> Cursor<Vec<u8>>;
-    fn get_world_player_palettes(&self) -> Palette;
-    fn get_world_script_data(&self, script_index: usize) -> Vec<u8>;
-
-    fn get_scene_palette_anim_data(&self) -> (Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>);
-    fn get_scene_palette(&self, scene_palette_index: usize) -> Palette;
-    fn get_scene_header_data(&self, scene_index: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_map_data(&self, scene_map_index: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_layer_priorities(&self, scene_map_index: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_tileset_data(&self, tileset_index: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>>;
-    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> Option<Cursor<Vec<u8>>>;
-    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> Vec<u8>;
-    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> Option<Cursor<Vec<u8>>>;
-    fn get_scene_exit_data(&self, scene_index: usize) -> Cursor<Vec<u8>>;
-    fn get_scene_names(&self, language: &str) -> Vec<String>;
-    fn get_scene_treasure_data(&self) -> (Vec<u32>, Cursor<Vec<u8>>);
-    fn get_scene_script_data(&self, scene_script_index: usize) -> Cursor<Vec<u8>>;
-
-    fn get_sprite_header_data(&self, sprite_index: usize) -> Cursor<Vec<u8>>;
-    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> Cursor<Vec<u8>>;
-    fn get_sprite_animation_data(&self) -> (Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>);
-    fn get_sprite_palette(&self, sprite_index: usize) -> Option<Palette>;
-    fn get_sprite_graphics(&self, sprite_tiles_index: usize, chip_count: usize, compressed: bool) -> Vec<u8>;
-
-    fn get_item_names(&self, language: &str) -> Vec<String>;
-    fn get_player_names(&self, language: &str) -> Vec<String>;
-
-    fn get_textbox_string_table(&self, address: usize, language: &str) -> Vec<String>;
-
-    fn get_ui_theme_cursor_graphics(&self) -> (Bitmap, Palette);
-    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> (Bitmap, Palette);
+    fn get_world_header_data(&self, world_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_map_tile_data(&self, world_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_world_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_music_data(&self, music_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_exits_triggers_data(&self, exits_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_exit_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+    fn get_world_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+    fn get_world_sprite_data(&self) -> FileSystemResult<Vec<u8>>;
+    fn get_world_sprite_graphics(&self, world_index: usize, tiles_index: usize) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_world_player_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_world_epoch_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_world_palette(&self, world_palette_index: usize) -> FileSystemResult<Palette>;
+    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_world_player_palettes(&self) -> FileSystemResult<Palette>;
+    fn get_world_script_data(&self, script_index: usize) -> FileSystemResult<Vec<u8>>;
+
+    fn get_scene_palette_anim_data(&self) -> FileSystemResult<(Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>)>;
+    fn get_scene_palette(&self, scene_palette_index: usize) -> FileSystemResult<Palette>;
+    fn get_scene_header_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_map_data(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_layer_priorities(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_tileset_data(&self, tileset_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>>;
+    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Vec<u8>>;
+    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>>;
+    fn get_scene_exit_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_scene_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+    fn get_scene_treasure_data(&self) -> FileSystemResult<(Vec<u32>, Cursor<Vec<u8>>)>;
+    fn get_scene_script_data(&self, scene_script_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+
+    fn get_sprite_header_data(&self, sprite_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>>;
+    fn get_sprite_animation_data(&self) -> FileSystemResult<(Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>)>;
+    fn get_sprite_palette(&self, sprite_index: usize) -> FileSystemResult<Option<Palette>>;
+    fn get_sprite_graphics(&self, sprite_tiles_index: usize, chip_count: usize, compressed: bool) -> FileSystemResult<Vec<u8>>;
+
+    fn get_languages(&self) -> FileSystemResult<Vec<String>>;
+    fn get_keyed_strings(&self, language: &str) -> FileSystemResult<Vec<(String, String, String)>>;
+    fn get_item_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+    fn get_tech_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+    fn get_player_names(&self, language: &str) -> FileSystemResult<Vec<String>>;
+
+    fn get_shop_items(&self, shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>>;
+    fn get_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>>;
+
+    fn get_textbox_string_table(&self, address: usize, language: &str) -> FileSystemResult<Vec<String>>;
+
+    fn get_font_8_graphics(&self) -> FileSystemResult<Option<Vec<u8>>>;
+    fn get_font_16_graphics(&self) -> FileSystemResult<Option<(Vec<u8>, Vec<u8>)>>;
+
+    fn get_ui_theme_cursor_graphics(&self) -> FileSystemResult<(Bitmap, Palette)>;
+    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> FileSystemResult<(Bitmap, Palette)>;
 }
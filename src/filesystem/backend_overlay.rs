@@ -0,0 +1,620 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use crate::filesystem::backend::FileSystemBackendTrait;
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
+use crate::party::items::ItemId;
+use crate::software_renderer::bitmap::Bitmap;
+use crate::software_renderer::palette::Palette;
+
+// Width in pixels of linear tileset graphics, as returned by the other backends.
+const TILESET_WIDTH: u32 = 128;
+
+/// Reads individual assets from a directory of loose files and falls through to another backend
+/// for everything else. This allows previewing modified assets without rebuilding a ROM or
+/// resources.bin file.
+///
+/// Files are named after the index of the asset they replace, inside a directory per asset type:
+/// - `scene_maps/<index>.bin`: uncompressed scene map data.
+/// - `scene_scripts/<index>.bin`: uncompressed scene script data.
+/// - `scene_exits/<index>.bin`: scene exit data.
+/// - `scene_tileset12_assemblies/<index>.bin`: uncompressed layer 1 and 2 tileset assembly data.
+/// - `scene_tileset3_assemblies/<index>.bin`: uncompressed layer 3 tileset assembly data.
+/// - `scene_palettes/<index>.png`: the PNG palette replaces the first colors of a scene palette.
+/// - `scene_tilesets12/<index>.png`, `scene_tilesets3/<index>.png`: scene tileset graphics.
+/// - `world_maps/<index>.bin`: world map tile data.
+/// - `world_scripts/<index>.bin`: world script data.
+/// - `world_palettes/<index>.png`: the PNG palette replaces the first colors of a world palette.
+/// - `world_tilesets12/<index>.png`, `world_tilesets3/<index>.png`: world tileset graphics.
+///
+/// Tileset images must be indexed PNG images 128 pixels wide. Only the low 4 bits (2 bits for
+/// layer 3) of each pixel are used, so images saved with a full 256 color palette work as well.
+pub struct FileSystemBackendOverlay {
+    base: Box<dyn FileSystemBackendTrait>,
+    path: PathBuf,
+
+    // Files that were already reported as used, so that each is only reported once.
+    logged: RefCell<HashSet<PathBuf>>,
+}
+
+impl FileSystemBackendOverlay {
+    pub fn new(base: Box<dyn FileSystemBackendTrait>, path: &Path) -> FileSystemBackendOverlay {
+        println!("Using overlay directory {}.", path.display());
+
+        FileSystemBackendOverlay {
+            base,
+            path: path.to_path_buf(),
+            logged: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn find_file(&self, kind: &str, index: usize, extension: &str) -> Option<PathBuf> {
+        let path = self.path.join(kind).join(format!("{}.{}", index, extension));
+        if !path.is_file() {
+            return None;
+        }
+
+        if self.logged.borrow_mut().insert(path.clone()) {
+            println!("Overlay: using {}.", path.display());
+        }
+        Some(path)
+    }
+
+    fn read_data(&self, kind: &str, index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        let Some(path) = self.find_file(kind, index, "bin") else {
+            return Ok(None);
+        };
+
+        Ok(Some(fs::read(path)?))
+    }
+
+    fn read_png(&self, kind: &str, index: usize) -> FileSystemResult<Option<(Bitmap, Palette)>> {
+        let Some(path) = self.find_file(kind, index, "png") else {
+            return Ok(None);
+        };
+
+        let png = Bitmap::read_png(&path)
+            .map_err(|error| FileSystemError::InvalidData(format!("{}: {}", path.display(), error)))?;
+        Ok(Some(png))
+    }
+
+    fn read_palette_override(&self, kind: &str, index: usize, mut palette: Palette) -> FileSystemResult<Palette> {
+        let Some((_, png_palette)) = self.read_png(kind, index)? else {
+            return Ok(palette);
+        };
+
+        for (color, png_color) in palette.colors.iter_mut().zip(png_palette.colors) {
+            *color = png_color;
+        }
+
+        Ok(palette)
+    }
+
+    fn read_tileset_graphics(&self, kind: &str, index: usize, bitplanes: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        let Some((bitmap, _)) = self.read_png(kind, index)? else {
+            return Ok(None);
+        };
+        if bitmap.width != TILESET_WIDTH {
+            return Err(FileSystemError::InvalidData(format!("{} {} is {} pixels wide, expected {}.", kind, index, bitmap.width, TILESET_WIDTH)));
+        }
+
+        let mask = ((1u16 << bitplanes) - 1) as u8;
+        Ok(Some(bitmap.data.iter().map(|pixel| pixel & mask).collect()))
+    }
+}
+
+impl FileSystemBackendTrait for FileSystemBackendOverlay {
+    fn get_world_header_data(&self, world_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_header_data(world_index)
+    }
+
+    fn get_world_map_tile_data(&self, world_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        if let Some(data) = self.read_data("world_maps", world_map_index)? {
+            return Ok(Cursor::new(data));
+        }
+        self.base.get_world_map_tile_data(world_map_index)
+    }
+
+    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_map_tile_props_data(world_map_props_index)
+    }
+
+    fn get_world_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if let Some(data) = self.read_tileset_graphics("world_tilesets12", chips_index, 4)? {
+            return Ok(Some(data));
+        }
+        self.base.get_world_tileset12_graphics(chips_index)
+    }
+
+    fn get_world_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if let Some(data) = self.read_tileset_graphics("world_tilesets3", chips_index, 2)? {
+            return Ok(Some(data));
+        }
+        self.base.get_world_tileset3_graphics(chips_index)
+    }
+
+    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_tileset12_assembly_data(assembly_index)
+    }
+
+    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_tileset3_assembly_data(assembly_index)
+    }
+
+    fn get_world_music_data(&self, music_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_music_data(music_index)
+    }
+
+    fn get_world_exits_triggers_data(&self, exits_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_exits_triggers_data(exits_index)
+    }
+
+    fn get_world_exit_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_world_exit_names(language)
+    }
+
+    fn get_world_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_world_names(language)
+    }
+
+    fn get_world_sprite_data(&self) -> FileSystemResult<Vec<u8>> {
+        self.base.get_world_sprite_data()
+    }
+
+    fn get_world_sprite_graphics(&self, world_index: usize, tiles_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        self.base.get_world_sprite_graphics(world_index, tiles_index)
+    }
+
+    fn get_world_player_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+        self.base.get_world_player_sprite_graphics()
+    }
+
+    fn get_world_epoch_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+        self.base.get_world_epoch_sprite_graphics()
+    }
+
+    fn get_world_palette(&self, world_palette_index: usize) -> FileSystemResult<Palette> {
+        let palette = self.base.get_world_palette(world_palette_index)?;
+        self.read_palette_override("world_palettes", world_palette_index, palette)
+    }
+
+    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_world_palette_anim_data(world_palette_index)
+    }
+
+    fn get_world_player_palettes(&self) -> FileSystemResult<Palette> {
+        self.base.get_world_player_palettes()
+    }
+
+    fn get_world_script_data(&self, script_index: usize) -> FileSystemResult<Vec<u8>> {
+        if let Some(data) = self.read_data("world_scripts", script_index)? {
+            return Ok(data);
+        }
+        self.base.get_world_script_data(script_index)
+    }
+
+    fn get_scene_palette_anim_data(&self) -> FileSystemResult<(Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>)> {
+        self.base.get_scene_palette_anim_data()
+    }
+
+    fn get_scene_palette(&self, scene_palette_index: usize) -> FileSystemResult<Palette> {
+        let palette = self.base.get_scene_palette(scene_palette_index)?;
+        self.read_palette_override("scene_palettes", scene_palette_index, palette)
+    }
+
+    fn get_scene_header_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_scene_header_data(scene_index)
+    }
+
+    fn get_scene_map_data(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        if let Some(data) = self.read_data("scene_maps", scene_map_index)? {
+            return Ok(Cursor::new(data));
+        }
+        self.base.get_scene_map_data(scene_map_index)
+    }
+
+    fn get_scene_layer_priorities(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_scene_layer_priorities(scene_map_index)
+    }
+
+    fn get_scene_tileset_data(&self, tileset_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_scene_tileset_data(tileset_index)
+    }
+
+    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if let Some(data) = self.read_tileset_graphics("scene_tilesets3", chips_index, 2)? {
+            return Ok(Some(data));
+        }
+        self.base.get_scene_tileset3_graphics(chips_index)
+    }
+
+    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+        if let Some(data) = self.read_data("scene_tileset3_assemblies", assembly_index)? {
+            return Ok(Some(Cursor::new(data)));
+        }
+        self.base.get_scene_tileset3_assembly_data(assembly_index)
+    }
+
+    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Vec<u8>> {
+        if let Some(data) = self.read_tileset_graphics("scene_tilesets12", chips_index, 4)? {
+            return Ok(data);
+        }
+        self.base.get_scene_tileset12_graphics(chips_index)
+    }
+
+    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        if let Some(data) = self.read_data("scene_tileset12_assemblies", index_assembly)? {
+            return Ok(Cursor::new(data));
+        }
+        self.base.get_scene_tileset12_assembly_data(index_assembly)
+    }
+
+    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+        self.base.get_scene_tileset12_animation_data(chip_anims_index)
+    }
+
+    fn get_scene_exit_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        if let Some(data) = self.read_data("scene_exits", scene_index)? {
+            return Ok(Cursor::new(data));
+        }
+        self.base.get_scene_exit_data(scene_index)
+    }
+
+    fn get_scene_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_scene_names(language)
+    }
+
+    fn get_scene_treasure_data(&self) -> FileSystemResult<(Vec<u32>, Cursor<Vec<u8>>)> {
+        self.base.get_scene_treasure_data()
+    }
+
+    fn get_scene_script_data(&self, scene_script_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        if let Some(data) = self.read_data("scene_scripts", scene_script_index)? {
+            return Ok(Cursor::new(data));
+        }
+        self.base.get_scene_script_data(scene_script_index)
+    }
+
+    fn get_sprite_header_data(&self, sprite_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_sprite_header_data(sprite_index)
+    }
+
+    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.base.get_sprite_assembly_data(sprite_assembly_index)
+    }
+
+    fn get_sprite_animation_data(&self) -> FileSystemResult<(Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>)> {
+        self.base.get_sprite_animation_data()
+    }
+
+    fn get_sprite_palette(&self, sprite_index: usize) -> FileSystemResult<Option<Palette>> {
+        self.base.get_sprite_palette(sprite_index)
+    }
+
+    fn get_sprite_graphics(&self, sprite_tiles_index: usize, chip_count: usize, compressed: bool) -> FileSystemResult<Vec<u8>> {
+        self.base.get_sprite_graphics(sprite_tiles_index, chip_count, compressed)
+    }
+
+    fn get_languages(&self) -> FileSystemResult<Vec<String>> {
+        self.base.get_languages()
+    }
+
+    fn get_keyed_strings(&self, language: &str) -> FileSystemResult<Vec<(String, String, String)>> {
+        self.base.get_keyed_strings(language)
+    }
+
+    fn get_item_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_item_names(language)
+    }
+
+    fn get_tech_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_tech_names(language)
+    }
+
+    fn get_player_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_player_names(language)
+    }
+
+    fn get_shop_items(&self, shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>> {
+        self.base.get_shop_items(shop_index)
+    }
+
+    fn get_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>> {
+        self.base.get_item_prices()
+    }
+
+    fn get_textbox_string_table(&self, address: usize, language: &str) -> FileSystemResult<Vec<String>> {
+        self.base.get_textbox_string_table(address, language)
+    }
+
+    fn get_font_8_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+        self.base.get_font_8_graphics()
+    }
+
+    fn get_font_16_graphics(&self) -> FileSystemResult<Option<(Vec<u8>, Vec<u8>)>> {
+        self.base.get_font_16_graphics()
+    }
+
+    fn get_ui_theme_cursor_graphics(&self) -> FileSystemResult<(Bitmap, Palette)> {
+        self.base.get_ui_theme_cursor_graphics()
+    }
+
+    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> FileSystemResult<(Bitmap, Palette)> {
+        self.base.get_ui_theme_window_graphics(ui_theme_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{create_dir_all, remove_dir_all, File};
+    use std::io::BufWriter;
+    use png::{BitDepth, ColorType, Encoder};
+    use super::*;
+
+    fn write_png_4bit(path: &Path, width: u32, height: u32, pixels: &[u8], colors: &[u8]) {
+        let file = File::create(path).unwrap();
+        let mut encoder = Encoder::new(BufWriter::new(file), width, height);
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(BitDepth::Four);
+        encoder.set_palette(colors.to_vec());
+
+        let packed: Vec<u8> = pixels.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect();
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&packed).unwrap();
+    }
+
+    // Returns recognizable data for the scene maps, palettes and tileset graphics the tests
+    // override, and nothing else.
+    struct StubBackend;
+
+    fn missing<T>() -> FileSystemResult<T> {
+        Err(FileSystemError::FileNotFound("stub".to_string()))
+    }
+
+    impl FileSystemBackendTrait for StubBackend {
+        fn get_world_header_data(&self, _world_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_map_tile_data(&self, _world_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_map_tile_props_data(&self, _world_map_props_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_tileset12_graphics(&self, _chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_tileset3_graphics(&self, _chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_tileset12_assembly_data(&self, _assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_tileset3_assembly_data(&self, _assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_music_data(&self, _music_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_exits_triggers_data(&self, _exits_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_exit_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_world_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_world_sprite_data(&self) -> FileSystemResult<Vec<u8>> {
+            missing()
+        }
+
+        fn get_world_sprite_graphics(&self, _world_index: usize, _tiles_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_player_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_epoch_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_palette(&self, _world_palette_index: usize) -> FileSystemResult<Palette> {
+            missing()
+        }
+
+        fn get_world_palette_anim_data(&self, _world_palette_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_world_player_palettes(&self) -> FileSystemResult<Palette> {
+            missing()
+        }
+
+        fn get_world_script_data(&self, _script_index: usize) -> FileSystemResult<Vec<u8>> {
+            missing()
+        }
+
+        fn get_scene_palette_anim_data(&self) -> FileSystemResult<(Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>)> {
+            missing()
+        }
+
+        fn get_scene_palette(&self, _scene_palette_index: usize) -> FileSystemResult<Palette> {
+            Ok(Palette::from_colors(&vec![[1, 2, 3, 0xFF]; 4]))
+        }
+
+        fn get_scene_header_data(&self, _scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_map_data(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            Ok(Cursor::new(vec![0xBA, scene_map_index as u8]))
+        }
+
+        fn get_scene_layer_priorities(&self, _scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_tileset_data(&self, _tileset_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_tileset3_graphics(&self, _chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_tileset3_assembly_data(&self, _assembly_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+            missing()
+        }
+
+        fn get_scene_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Vec<u8>> {
+            Ok(vec![0xBA; chips_index])
+        }
+
+        fn get_scene_tileset12_assembly_data(&self, _index_assembly: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_tileset12_animation_data(&self, _chip_anims_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+            missing()
+        }
+
+        fn get_scene_exit_data(&self, _scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_scene_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_scene_treasure_data(&self) -> FileSystemResult<(Vec<u32>, Cursor<Vec<u8>>)> {
+            missing()
+        }
+
+        fn get_scene_script_data(&self, _scene_script_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_sprite_header_data(&self, _sprite_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_sprite_assembly_data(&self, _sprite_assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_sprite_animation_data(&self) -> FileSystemResult<(Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>)> {
+            missing()
+        }
+
+        fn get_sprite_palette(&self, _sprite_index: usize) -> FileSystemResult<Option<Palette>> {
+            missing()
+        }
+
+        fn get_sprite_graphics(&self, _sprite_tiles_index: usize, _chip_count: usize, _compressed: bool) -> FileSystemResult<Vec<u8>> {
+            missing()
+        }
+
+        fn get_languages(&self) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_keyed_strings(&self, _language: &str) -> FileSystemResult<Vec<(String, String, String)>> {
+            missing()
+        }
+
+        fn get_item_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_tech_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_player_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_shop_items(&self, _shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>> {
+            missing()
+        }
+
+        fn get_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>> {
+            missing()
+        }
+
+        fn get_textbox_string_table(&self, _address: usize, _language: &str) -> FileSystemResult<Vec<String>> {
+            missing()
+        }
+
+        fn get_font_8_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+            missing()
+        }
+
+        fn get_font_16_graphics(&self) -> FileSystemResult<Option<(Vec<u8>, Vec<u8>)>> {
+            missing()
+        }
+
+        fn get_ui_theme_cursor_graphics(&self) -> FileSystemResult<(Bitmap, Palette)> {
+            missing()
+        }
+
+        fn get_ui_theme_window_graphics(&self, _ui_theme_index: usize) -> FileSystemResult<(Bitmap, Palette)> {
+            missing()
+        }
+    }
+
+    #[test]
+    fn overrides_and_falls_through() {
+        let path = env::temp_dir().join(format!("ctviewer_overlay_{}", std::process::id()));
+        if path.exists() {
+            remove_dir_all(&path).unwrap();
+        }
+        create_dir_all(path.join("scene_maps")).unwrap();
+        create_dir_all(path.join("scene_tilesets12")).unwrap();
+        create_dir_all(path.join("scene_palettes")).unwrap();
+
+        fs::write(path.join("scene_maps/1.bin"), [1, 2, 3, 4]).unwrap();
+        let pixels: Vec<u8> = (0..128 * 8).map(|i| (i % 16) as u8).collect();
+        write_png_4bit(&path.join("scene_tilesets12/2.png"), 128, 8, &pixels, &[255, 0, 0, 0, 255, 0]);
+        write_png_4bit(&path.join("scene_palettes/3.png"), 2, 1, &[0, 1], &[255, 0, 0, 0, 255, 0]);
+        write_png_4bit(&path.join("scene_tilesets12/4.png"), 64, 8, &pixels[..64 * 8], &[255, 0, 0, 0, 255, 0]);
+
+        let overlay = FileSystemBackendOverlay::new(Box::new(StubBackend), &path);
+
+        assert_eq!(overlay.get_scene_map_data(1).unwrap().into_inner(), vec![1, 2, 3, 4]);
+        assert_eq!(overlay.get_scene_map_data(0).unwrap().into_inner(), vec![0xBA, 0]);
+        assert_eq!(overlay.get_scene_tileset12_graphics(2).unwrap(), pixels);
+        assert_eq!(overlay.get_scene_tileset12_graphics(3).unwrap(), vec![0xBA; 3]);
+        assert!(matches!(overlay.get_scene_tileset12_graphics(4), Err(FileSystemError::InvalidData(_))));
+        assert!(matches!(overlay.get_scene_script_data(1), Err(FileSystemError::FileNotFound(_))));
+
+        let palette = overlay.get_scene_palette(3).unwrap();
+        assert_eq!(palette.colors.len(), 4);
+        assert_eq!(palette.colors[0], [255, 0, 0, 0xFF]);
+        assert_eq!(palette.colors[1], [0, 255, 0, 0xFF]);
+        assert_eq!(palette.colors[2..], [[1, 2, 3, 0xFF], [1, 2, 3, 0xFF]]);
+
+        // Each file is only logged the first time it is used.
+        overlay.get_scene_map_data(1).unwrap();
+        assert_eq!(overlay.logged.borrow().len(), 4);
+
+        remove_dir_all(&path).unwrap();
+    }
+}
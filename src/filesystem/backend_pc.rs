@@ -1,10 +1,12 @@
-use std::fs::File;
-use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::fs;
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
 use std::path::Path;
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::filesystem::backend::FileSystemBackendTrait;
+use crate::filesystem::error::{table_get, FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::{FileSystem};
 use crate::filesystem::resourcesbin::ResourcesBin;
+use crate::party::items::ItemId;
 use crate::software_renderer::bitmap::Bitmap;
 use crate::software_renderer::palette::{Color, Palette};
 use crate::util::bmp_reader::Bmp;
@@ -39,18 +41,22 @@ impl FileSystemBackendPc {
         }
     }
 
-    fn file_get(&self, filename: &str) -> Cursor<Vec<u8>> {
+    pub(crate) fn file_get(&self, filename: &str) -> FileSystemResult<Cursor<Vec<u8>>> {
         match self.resources {
             None => {
                 let path = self.base_path.join(Path::new(&filename));
-                let mut file = File::open(&path).expect(&format!("Could not open file {}.", filename));
-                let mut data = vec![0; file.metadata().unwrap().len() as usize];
-                file.read_exact(&mut data).unwrap();
+                let data = fs::read(&path).map_err(|e| match e.kind() {
+                    ErrorKind::NotFound => FileSystemError::FileNotFound(filename.to_string()),
+                    _ => FileSystemError::Io(e),
+                })?;
 
-                Cursor::new(data)
+                Ok(Cursor::new(data))
             },
             Some(ref res) => {
-                res.file_get(filename)
+                if !res.file_exists(filename) {
+                    return Err(FileSystemError::FileNotFound(filename.to_string()));
+                }
+                Ok(res.file_get(filename))
             }
         }
     }
@@ -67,37 +73,37 @@ impl FileSystemBackendPc {
         }
     }
 
-    fn get_bytes(&self, reader: &mut Cursor<Vec<u8>>, len: Option<usize>, start: Option<usize>) -> Vec<u8> {
+    fn get_bytes(&self, reader: &mut Cursor<Vec<u8>>, len: Option<usize>, start: Option<usize>) -> FileSystemResult<Vec<u8>> {
         let mut buffer;
 
         let mut offset = 0;
-        if start.is_some() {
-            reader.seek(SeekFrom::Start(start.unwrap() as u64)).unwrap();
-            offset = start.unwrap();
+        if let Some(start) = start {
+            reader.seek(SeekFrom::Start(start as u64))?;
+            offset = start;
         }
 
-        if len.is_none() {
-            buffer = vec![0u8; reader.get_mut().len() - offset];
+        if let Some(len) = len {
+            buffer = vec![0u8; len];
         } else {
-            buffer = vec![0u8; len.unwrap()];
+            buffer = vec![0u8; reader.get_mut().len().saturating_sub(offset)];
         }
-        reader.read_exact(&mut buffer).unwrap();
+        reader.read_exact(&mut buffer)?;
 
-        buffer
+        Ok(buffer)
     }
 
-    fn get_bytes_cursor(&self, reader: &mut Cursor<Vec<u8>>, len: Option<usize>, start: Option<usize>) -> Cursor<Vec<u8>> {
-        let buffer = self.get_bytes(reader, len, start);
-        Cursor::new(buffer)
+    fn get_bytes_cursor(&self, reader: &mut Cursor<Vec<u8>>, len: Option<usize>, start: Option<usize>) -> FileSystemResult<Cursor<Vec<u8>>> {
+        let buffer = self.get_bytes(reader, len, start)?;
+        Ok(Cursor::new(buffer))
     }
 
-    fn get_file_cursor(&self, filename: &String, len: Option<usize>, start: Option<usize>) -> Cursor<Vec<u8>> {
-        let mut reader = self.file_get(filename);
+    fn get_file_cursor(&self, filename: &String, len: Option<usize>, start: Option<usize>) -> FileSystemResult<Cursor<Vec<u8>>> {
+        let mut reader = self.file_get(filename)?;
         self.get_bytes_cursor(&mut reader, len, start)
     }
 
-    fn get_file_bytes(&self, filename: &String, len: Option<usize>, start: Option<usize>) -> Vec<u8> {
-        let mut reader = self.file_get(filename);
+    fn get_file_bytes(&self, filename: &String, len: Option<usize>, start: Option<usize>) -> FileSystemResult<Vec<u8>> {
+        let mut reader = self.file_get(filename)?;
         self.get_bytes(&mut reader, len, start)
     }
 
@@ -115,95 +121,126 @@ impl FileSystemBackendPc {
         pixels
     }
 
-    fn remove_string_list_keys(&self, strings: Vec<String>) -> Vec<String> {
-        strings.iter().map(|x|x.split_once(",").unwrap().1.to_string()).collect()
+    fn split_string_list_keys(&self, strings: Vec<String>) -> FileSystemResult<Vec<(String, String)>> {
+        strings.iter()
+            .map(|x| x.split_once(",")
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| FileSystemError::InvalidData(format!("String list entry without a key: {}", x))))
+            .collect()
+    }
+
+    fn remove_string_list_keys(&self, strings: Vec<String>) -> FileSystemResult<Vec<String>> {
+        Ok(self.split_string_list_keys(strings)?.into_iter().map(|(_, value)| value).collect())
+    }
+
+    fn message_file_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let mut names: Vec<String> = match self.resources {
+            None => {
+                let mut names = Vec::new();
+                for entry in fs::read_dir(self.base_path.join(format!("Localize/{}/msg", language)))? {
+                    names.push(entry?.file_name().to_string_lossy().to_string());
+                }
+                names
+            },
+            Some(ref res) => {
+                let prefix = format!("Localize/{}/msg/", language);
+                res.file_paths().iter()
+                    .filter_map(|path| path.strip_prefix(&prefix))
+                    .map(|name| name.to_string())
+                    .collect()
+            },
+        };
+        names.retain(|name| name.ends_with(".txt"));
+        names.sort();
+
+        Ok(names)
     }
 }
 
 impl FileSystemBackendTrait for FileSystemBackendPc {
-    fn get_world_header_data(&self, world_index: usize) -> Cursor<Vec<u8>> {
-        let mut reader = self.file_get(&"Game/common/bankc6.bin".to_string());
-        reader.seek(SeekFrom::Start(0xFD10 + world_index as u64 * 23)).unwrap();
+    fn get_world_header_data(&self, world_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        let mut reader = self.file_get(&"Game/common/bankc6.bin".to_string())?;
+        reader.seek(SeekFrom::Start(0xFD10 + world_index as u64 * 23))?;
         self.get_bytes_cursor(&mut reader, Some(23), None)
     }
 
-    fn get_world_map_tile_data(&self, world_map_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_map_tile_data(&self, world_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/Map/Map_{:0>4}.dat", world_map_index), None, None)
     }
 
-    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/Id/Id_{:0>4}.dat", world_map_props_index), None, None)
     }
 
-    fn get_world_tileset12_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
+    fn get_world_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
         let filename = &format!("Game/world/map_bin/cg{}.bin", chips_index);
         if !self.file_exists(filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut reader = self.file_get(&filename);
-        let data = self.get_bytes(&mut reader, None, Some(4));
+        let mut reader = self.file_get(&filename)?;
+        let data = self.get_bytes(&mut reader, None, Some(4))?;
 
-        Some(self.unpack_4bpp_graphics(&data))
+        Ok(Some(self.unpack_4bpp_graphics(&data)))
     }
 
-    fn get_world_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
+    fn get_world_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
         let filename = &format!("Game/world/map_bin/cg{}.bin", chips_index);
         if !self.file_exists(filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut reader = self.file_get(&filename);
-        let data = self.get_bytes(&mut reader, None, Some(4));
+        let mut reader = self.file_get(&filename)?;
+        let data = self.get_bytes(&mut reader, None, Some(4))?;
 
-        Some(self.unpack_4bpp_graphics(&data))
+        Ok(Some(self.unpack_4bpp_graphics(&data)))
     }
 
-    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/Chip/Chip_{:04}.dat", assembly_index), None, None)
     }
 
-    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
 
         // Load tile assembly from bankc6.bin. Why isn't this extracted out into files?
-        let mut reader = self.file_get(&"Game/common/bankc6.bin".to_string());
-        reader.seek(SeekFrom::Start(0xFF40 + assembly_index as u64 * 3)).unwrap();
-        let offset = reader.read_u16::<LittleEndian>().unwrap() as u64;
-        reader.seek(SeekFrom::Start(offset)).unwrap();
+        let mut reader = self.file_get(&"Game/common/bankc6.bin".to_string())?;
+        reader.seek(SeekFrom::Start(0xFF40 + assembly_index as u64 * 3))?;
+        let offset = reader.read_u16::<LittleEndian>()? as u64;
+        reader.seek(SeekFrom::Start(offset))?;
 
         // Decompress it.
         let mut assembly_data = vec![0u8; 0x1000];
         let mut assembly = vec![0u8; 0x1000];
-        reader.seek(SeekFrom::Start(offset)).unwrap();
-        reader.read_exact(&mut assembly_data).unwrap();
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut assembly_data)?;
         lz_decompress(&assembly_data, &mut assembly, 0);
 
-        Cursor::new(assembly)
+        Ok(Cursor::new(assembly))
     }
 
-    fn get_world_music_data(&self, music_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_music_data(&self, music_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/SeId/SeId_{:0>4}.dat", music_index), None, None)
     }
 
-    fn get_world_exits_triggers_data(&self, exits_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_exits_triggers_data(&self, exits_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/EventTable/EventTable_{:04}.dat", exits_index), None, None)
     }
 
-    fn get_world_exit_names(&self, language: &str) -> Vec<String> {
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/w_map.txt", language), None, None);
+    fn get_world_exit_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/w_map.txt", language), None, None)?;
         self.read_text_string_list(data, None, Some(106))
     }
 
-    fn get_world_names(&self, language: &str) -> Vec<String> {
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/w_map.txt", language), None, None);
+    fn get_world_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/w_map.txt", language), None, None)?;
         self.read_text_string_list(data, Some(106), Some(112))
     }
 
-    fn get_world_sprite_data(&self) -> Vec<u8> {
+    fn get_world_sprite_data(&self) -> FileSystemResult<Vec<u8>> {
         self.get_file_bytes(&String::from("Game/common/shapeSeqTbl.bin"), None, None)
     }
 
-    fn get_world_sprite_graphics(&self, world_index: usize, tiles_index: usize) -> Option<Vec<u8>> {
+    fn get_world_sprite_graphics(&self, world_index: usize, tiles_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
 
         // Map world bitmap indices to Steam BMP files.
         //
@@ -243,232 +280,229 @@ impl FileSystemBackendTrait for FileSystemBackendPc {
             11 => "Game/world/gif/4_wobj0.bmp",
             12 => "Game/world/gif/4_wobj1.bmp",
             13 => "Game/world/gif/4_kodai_break.bmp",
-            _ => return None,
+            _ => return Ok(None),
         };
 
-        let mut bitmap = Bmp::from_cursor(&mut self.file_get(&name.to_string()));
+        let mut bitmap = Bmp::from_cursor(&mut self.file_get(&name.to_string())?);
         bitmap.map_down();
-        Some(bitmap.pixels)
+        Ok(Some(bitmap.pixels))
     }
 
-    fn get_world_player_sprite_graphics(&self) -> Option<Vec<u8>> {
+    fn get_world_player_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
         let bmp = Bmp::from_path(Path::new("data/pc_sprites_empty.bmp"));
 
-        Some(bmp.pixels)
+        Ok(Some(bmp.pixels))
     }
 
-    fn get_world_epoch_sprite_graphics(&self) -> Option<Vec<u8>> {
+    fn get_world_epoch_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
         let bmp = Bmp::from_path(Path::new("data/epoch_sprites_empty.bmp"));
 
-        Some(bmp.pixels)
+        Ok(Some(bmp.pixels))
     }
 
-    fn get_world_palette(&self, world_palette_index: usize) -> Palette {
-        let mut data = self.get_file_cursor(&format!("Game/world/plt_bin/plt{}.bin", world_palette_index), None, None);
-        data.seek(SeekFrom::Start(2)).unwrap();
+    fn get_world_palette(&self, world_palette_index: usize) -> FileSystemResult<Palette> {
+        let mut data = self.get_file_cursor(&format!("Game/world/plt_bin/plt{}.bin", world_palette_index), None, None)?;
+        data.seek(SeekFrom::Start(2))?;
 
         let mut colors = Vec::<Color>::new();
         for _ in 0..256 {
-            colors.push(FileSystem::read_color(&mut data));
+            colors.push(FileSystem::read_color(&mut data)?);
         }
 
-        Palette::from_colors(&colors)
+        Ok(Palette::from_colors(&colors))
     }
 
-    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> Cursor<Vec<u8>> {
+    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/world/colanim_bin/{}_colanim.bin", world_palette_index), None, None)
     }
 
-    fn get_world_player_palettes(&self) -> Palette {
+    fn get_world_player_palettes(&self) -> FileSystemResult<Palette> {
         // TODO: PC version does not have this, since there are no original SNES sprites...
-        let mut data = self.get_file_cursor(&"Game/world/plt_bin/plt4.bin".to_string(), None, None);
-        data.seek(SeekFrom::Start(2)).unwrap();
+        let mut data = self.get_file_cursor(&"Game/world/plt_bin/plt4.bin".to_string(), None, None)?;
+        data.seek(SeekFrom::Start(2))?;
 
         let mut colors = Vec::<Color>::new();
         for _ in 0..128 {
-            colors.push(FileSystem::read_color(&mut data));
+            colors.push(FileSystem::read_color(&mut data)?);
         }
 
-        Palette::from_colors(&colors)
+        Ok(Palette::from_colors(&colors))
     }
 
-    fn get_world_script_data(&self, script_index: usize) -> Vec<u8> {
+    fn get_world_script_data(&self, script_index: usize) -> FileSystemResult<Vec<u8>> {
         self.get_file_bytes(&format!("Game/world/esl/Event_{:04}.dat", script_index), None, None)
     }
 
-    fn get_scene_palette_anim_data(&self) -> (Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>) {
-        (
-            self.get_file_cursor(&"Game/common/PalAnimaAdrs.dat".to_string(), None, Some(4)),
-            self.get_file_cursor(&"Game/common/PalAnimaData.dat".to_string(), None, Some(4)),
-            self.get_file_cursor(&"Game/common/PaletteAnimeColor.bin".to_string(), None, None),
-        )
+    fn get_scene_palette_anim_data(&self) -> FileSystemResult<(Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>)> {
+        Ok((
+            self.get_file_cursor(&"Game/common/PalAnimaAdrs.dat".to_string(), None, Some(4))?,
+            self.get_file_cursor(&"Game/common/PalAnimaData.dat".to_string(), None, Some(4))?,
+            self.get_file_cursor(&"Game/common/PaletteAnimeColor.bin".to_string(), None, None)?,
+        ))
     }
 
-    fn get_scene_palette(&self, scene_palette_index: usize) -> Palette {
-        let mut data = self.get_file_cursor(&format!("Game/field/palette_bin/plt{}.bin", scene_palette_index), None, None);
-        data.seek(SeekFrom::Start(2)).unwrap();
+    fn get_scene_palette(&self, scene_palette_index: usize) -> FileSystemResult<Palette> {
+        let mut data = self.get_file_cursor(&format!("Game/field/palette_bin/plt{}.bin", scene_palette_index), None, None)?;
+        data.seek(SeekFrom::Start(2))?;
 
         let mut colors = Vec::<Color>::new();
         for _ in 0..256 {
-            colors.push(FileSystem::read_color(&mut data));
+            colors.push(FileSystem::read_color(&mut data)?);
         }
 
-        Palette::from_colors(&colors)
+        Ok(Palette::from_colors(&colors))
     }
 
-    fn get_scene_header_data(&self, scene_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_header_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/Mapinfo/mapinfo_{}.dat", scene_index), None, None)
     }
 
-    fn get_scene_map_data(&self, scene_map_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_map_data(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/MapTable/MapTable_{:0>4}.dat", scene_map_index), None, None)
     }
 
-    fn get_scene_layer_priorities(&self, scene_map_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_layer_priorities(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/PrioMap/PrioMap{:}.dat", scene_map_index), None, None)
     }
 
-    fn get_scene_tileset_data(&self, tileset_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_tileset_data(&self, tileset_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/BGSetTable/bgsettable_{}.dat", tileset_index), None, None)
     }
 
-    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
+    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
         let filename = &format!("Game/field/weather_bin/cg{}.bin", chips_index);
         if !self.file_exists(filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut reader = self.file_get(&filename);
-        let data = self.get_bytes(&mut reader, None, Some(4));
+        let mut reader = self.file_get(&filename)?;
+        let data = self.get_bytes(&mut reader, None, Some(4))?;
 
-        Some(self.unpack_4bpp_graphics(&data))
+        Ok(Some(self.unpack_4bpp_graphics(&data)))
     }
 
-    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> Option<Cursor<Vec<u8>>> {
+    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
         let filename = &format!("Game/field/ChipTable/ChipTableBg3_{:0>4}.dat", assembly_index);
         if !self.file_exists(filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut reader = self.file_get(&filename);
-        Some(self.get_bytes_cursor(&mut reader, None, None))
+        let mut reader = self.file_get(&filename)?;
+        Ok(Some(self.get_bytes_cursor(&mut reader, None, None)?))
     }
 
-    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> Vec<u8> {
-        let data = self.get_file_bytes(&format!("Game/field/map_bin/cg{}.bin", chips_index), None, Some(4));
-        self.unpack_4bpp_graphics(&data)
+    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Vec<u8>> {
+        let data = self.get_file_bytes(&format!("Game/field/map_bin/cg{}.bin", chips_index), None, Some(4))?;
+        Ok(self.unpack_4bpp_graphics(&data))
     }
 
-    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/ChipTable/ChipTable_{:0>4}.dat", index_assembly), None, None)
     }
 
-    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> Option<Cursor<Vec<u8>>> {
+    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
         let filename = &format!("Game/field/BGAnime/bganimeinfo_{}.dat", chip_anims_index);
         if !self.file_exists(filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut reader = self.file_get(&filename);
-        Some(self.get_bytes_cursor(&mut reader, None, Some(1)))
+        let mut reader = self.file_get(&filename)?;
+        Ok(Some(self.get_bytes_cursor(&mut reader, None, Some(1))?))
     }
 
-    fn get_scene_exit_data(&self, scene_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_exit_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
 
         // Read offsets to the exits of each scene.
-        let mut offsets_data = self.get_file_cursor(&String::from("Game/common/MapJumpOffsetTbl.dat"), None, None);
-        let ptr_count = offsets_data.read_u32::<LittleEndian>().unwrap() as usize;
+        let mut offsets_data = self.get_file_cursor(&String::from("Game/common/MapJumpOffsetTbl.dat"), None, None)?;
+        let ptr_count = offsets_data.read_u32::<LittleEndian>()? as usize;
         let mut offsets = vec![0; ptr_count];
         for i in 0..ptr_count {
-            offsets[i] = offsets_data.read_u16::<LittleEndian>().unwrap() as usize * 8 + 4;
+            offsets[i] = offsets_data.read_u16::<LittleEndian>()? as usize * 8 + 4;
         }
 
-        let data = self.get_file_cursor(&String::from("Game/common/MapJumpDataTbl.dat"), None, None);
+        let data = self.get_file_cursor(&String::from("Game/common/MapJumpDataTbl.dat"), None, None)?;
         let len = data.get_ref().len();
-        let offset = offsets[scene_index];
+        let offset = *table_get(&offsets, scene_index, "scene exits")?;
 
         // Determine how many exits there are based on the current and next offset.
-        let size;
-        if scene_index == offsets.len() - 1 {
-            size = len - offset;
-        } else {
-            size = offsets[scene_index + 1] - offset;
-        }
+        let end = offsets.get(scene_index + 1).copied().unwrap_or(len);
+        let exit_data = data.get_ref().get(offset..end)
+            .ok_or_else(|| FileSystemError::InvalidData(format!("Exits of scene {} are outside of the exit data", scene_index)))?;
 
-        Cursor::new(data.get_ref()[offset..offset + size].to_vec())
+        Ok(Cursor::new(exit_data.to_vec()))
     }
 
-    fn get_scene_names(&self, language: &str) -> Vec<String> {
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/debug_map.txt", language), None, None);
+    fn get_scene_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/debug_map.txt", language), None, None)?;
         let mut strings = Vec::<String>::new();
         strings.push("".to_string());
-        strings.append(&mut self.remove_string_list_keys(self.read_text_string_list(data, None, None)));
+        strings.append(&mut self.remove_string_list_keys(self.read_text_string_list(data, None, None)?)?);
 
-        strings
+        Ok(strings)
     }
 
-    fn get_scene_treasure_data(&self) -> (Vec<u32>, Cursor<Vec<u8>>) {
-        let mut data = self.file_get(&"Game/common/TakaraDataTbl.dat".to_string());
-        let mut offsets_data = self.file_get(&"Game/common/TakaraOffsetTbl.dat".to_string());
+    fn get_scene_treasure_data(&self) -> FileSystemResult<(Vec<u32>, Cursor<Vec<u8>>)> {
+        let mut data = self.file_get(&"Game/common/TakaraDataTbl.dat".to_string())?;
+        let mut offsets_data = self.file_get(&"Game/common/TakaraOffsetTbl.dat".to_string())?;
 
-        let ptr_count = offsets_data.read_u32::<LittleEndian>().unwrap() as usize;
+        let ptr_count = offsets_data.read_u32::<LittleEndian>()? as usize;
         let mut offsets = vec![0u32; ptr_count];
         for offset in offsets.iter_mut() {
-            *offset = offsets_data.read_u16::<LittleEndian>().unwrap() as u32 * 6 + 4;
+            *offset = offsets_data.read_u16::<LittleEndian>()? as u32 * 6 + 4;
         }
 
         // Add a last entry so that the number of chests can be calculated like the SNES version.
-        offsets.push(offsets[ptr_count - 1]);
+        offsets.push(offsets.last().copied().unwrap_or(0));
 
-        (
+        Ok((
             offsets,
-            self.get_bytes_cursor(&mut data, None, None),
-        )
+            self.get_bytes_cursor(&mut data, None, None)?,
+        ))
     }
 
-    fn get_scene_script_data(&self, scene_script_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_script_data(&self, scene_script_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/field/atel/Atel_{:0>4}.dat", scene_script_index), None, None)
     }
 
-    fn get_sprite_header_data(&self, sprite_index: usize) -> Cursor<Vec<u8>> {
+    fn get_sprite_header_data(&self, sprite_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/chara/dat/c{:0>3}.dat", sprite_index), None, None)
     }
 
-    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> Cursor<Vec<u8>> {
+    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_file_cursor(&format!("Game/chara/cell/c{:0>3}.cel", sprite_assembly_index), None, None)
     }
 
-    fn get_sprite_animation_data(&self) -> (Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>) {
+    fn get_sprite_animation_data(&self) -> FileSystemResult<(Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>)> {
         let mut pointers_slots = Vec::new();
-        let mut data_slot_pointers = self.get_file_cursor(&String::from("Game/common/SlotAddressTable.dat"), None, Some(4));
+        let mut data_slot_pointers = self.get_file_cursor(&String::from("Game/common/SlotAddressTable.dat"), None, Some(4))?;
         for _ in 0..201 {
-            pointers_slots.push(data_slot_pointers.read_u16::<LittleEndian>().unwrap() as usize);
+            pointers_slots.push(data_slot_pointers.read_u16::<LittleEndian>()? as usize);
         }
 
         let mut pointers_intervals = Vec::new();
-        let mut data_interval_pointers = self.get_file_cursor(&String::from("Game/common/IntervalAddressTable.dat"), None, Some(4));
+        let mut data_interval_pointers = self.get_file_cursor(&String::from("Game/common/IntervalAddressTable.dat"), None, Some(4))?;
         for _ in 0..201 {
-            pointers_intervals.push(data_interval_pointers.read_u16::<LittleEndian>().unwrap() as usize);
+            pointers_intervals.push(data_interval_pointers.read_u16::<LittleEndian>()? as usize);
         }
 
-        (
+        Ok((
             pointers_slots,
-            self.get_file_cursor(&String::from("Game/common/SlotAddress.bin"), None, None),
+            self.get_file_cursor(&String::from("Game/common/SlotAddress.bin"), None, None)?,
             pointers_intervals,
-            self.get_file_cursor(&String::from("Game/common/IntervalAddress.bin"), None, None),
-        )
+            self.get_file_cursor(&String::from("Game/common/IntervalAddress.bin"), None, None)?,
+        ))
     }
 
-    fn get_sprite_palette(&self, sprite_index: usize) -> Option<Palette> {
+    fn get_sprite_palette(&self, sprite_index: usize) -> FileSystemResult<Option<Palette>> {
 
         // The PC version stores these in the sprite BMP files, so we get it from the
         // first BMP file instead.
         let filename = format!("Game/chara/bmp/c{:0>3}_0.bmp", sprite_index);
         if !self.file_exists(&filename) {
-            return None;
+            return Ok(None);
         }
 
-        let mut bmp_reader = self.file_get(&filename);
+        let mut bmp_reader = self.file_get(&filename)?;
         let bmp = Bmp::from_cursor(&mut bmp_reader);
 
         // Normalize the BMP palette into raw RGBA color bytes.
@@ -480,10 +514,10 @@ impl FileSystemBackendTrait for FileSystemBackendPc {
             colors[index][3] = 0xFF;
         }
 
-        Some(Palette::from_colors(&colors))
+        Ok(Some(Palette::from_colors(&colors)))
     }
 
-    fn get_sprite_graphics(&self, sprite_index: usize, chip_count: usize, _compressed: bool) -> Vec<u8> {
+    fn get_sprite_graphics(&self, sprite_index: usize, chip_count: usize, _compressed: bool) -> FileSystemResult<Vec<u8>> {
         let bitmap_count = (chip_count as f64 / 512.0).ceil() as usize;
         let mut tile_data = vec![0u8; 256 * bitmap_count * 256];
 
@@ -493,26 +527,85 @@ impl FileSystemBackendTrait for FileSystemBackendPc {
                 continue;
             }
 
-            let mut reader = self.file_get(&filename);
+            let mut reader = self.file_get(&filename)?;
             let bmp = Bmp::from_cursor(&mut reader);
             let offset = bitmap_index * 0x10000;
             tile_data[offset..offset + bmp.pixels.len()].copy_from_slice(&bmp.pixels);
         }
 
-        tile_data
+        Ok(tile_data)
+    }
+
+    fn get_languages(&self) -> FileSystemResult<Vec<String>> {
+
+        // Any directory in Localize with message files is a language.
+        let mut languages: Vec<String> = match self.resources {
+            None => {
+                let mut languages = Vec::new();
+                for entry in fs::read_dir(self.base_path.join("Localize"))? {
+                    let name = entry?.file_name().to_string_lossy().to_string();
+                    if self.file_exists(&format!("Localize/{}/msg/w_map.txt", name)) {
+                        languages.push(name);
+                    }
+                }
+                languages
+            },
+            Some(ref res) => {
+                res.file_paths().iter()
+                    .filter_map(|path| path.strip_prefix("Localize/")?.strip_suffix("/msg/w_map.txt"))
+                    .map(|language| language.to_string())
+                    .collect()
+            },
+        };
+        languages.sort();
+
+        Ok(languages)
     }
 
-    fn get_item_names(&self, language: &str) -> Vec<String> {
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/item.txt", language), None, None);
+    fn get_keyed_strings(&self, language: &str) -> FileSystemResult<Vec<(String, String, String)>> {
+        let mut strings = Vec::new();
+        for name in self.message_file_names(language)? {
+            let data = self.get_file_cursor(&format!("Localize/{}/msg/{}", language, name), None, None)?;
+            let table = name.strip_suffix(".txt").unwrap_or(&name).to_string();
+
+            // Lines without a key are not part of a keyed table.
+            let lines = self.read_text_string_list(data, None, None)?
+                .into_iter()
+                .filter(|line| line.contains(','))
+                .collect();
+            for (key, value) in self.split_string_list_keys(lines)? {
+                strings.push((table.clone(), key, value));
+            }
+        }
+
+        Ok(strings)
+    }
+
+    fn get_item_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/item.txt", language), None, None)?;
         self.read_text_string_list(data, None, None)
     }
 
-    fn get_player_names(&self, language: &str) -> Vec<String> {
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/player.txt", language), None, None);
+    // The message file that holds tech names has not been identified yet.
+    fn get_tech_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_player_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/player.txt", language), None, None)?;
         self.read_text_string_list(data, None, Some(8))
     }
 
-    fn get_textbox_string_table(&self, address: usize, language: &str) -> Vec<String> {
+    // The location of shop and item data in the PC version's resources is not known yet.
+    fn get_shop_items(&self, _shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>> {
+        Ok(None)
+    }
+
+    fn get_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>> {
+        Ok(None)
+    }
+
+    fn get_textbox_string_table(&self, address: usize, language: &str) -> FileSystemResult<Vec<String>> {
         let message_file = match address {
             0 => "cmes0.txt",
             1 => "cmes1.txt",
@@ -546,19 +639,28 @@ impl FileSystemBackendTrait for FileSystemBackendPc {
             29 => "exms3.txt",
             30 => "wireless1.txt",
             31 => "wireless2.txt",
-            _ => panic!("Unknown PC string table index {}", address),
+            _ => return Err(FileSystemError::IndexOutOfRange { table: "PC string tables", index: address, count: 32 }),
         };
 
-        let data = self.get_file_cursor(&format!("Localize/{}/msg/{}", language, message_file), None, None);
-        self.remove_string_list_keys(self.read_text_string_list(data, None, None))
+        let data = self.get_file_cursor(&format!("Localize/{}/msg/{}", language, message_file), None, None)?;
+        self.remove_string_list_keys(self.read_text_string_list(data, None, None)?)
+    }
+
+    // The PC version fonts are not supported, it uses the TTF fonts instead.
+    fn get_font_8_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    fn get_font_16_graphics(&self) -> FileSystemResult<Option<(Vec<u8>, Vec<u8>)>> {
+        Ok(None)
     }
 
-    fn get_ui_theme_cursor_graphics(&self) -> (Bitmap, Palette) {
-        let mut file = self.get_file_cursor(&"Game/common/K_NEW3.BIN".to_string(), None, None);
+    fn get_ui_theme_cursor_graphics(&self) -> FileSystemResult<(Bitmap, Palette)> {
+        let mut file = self.get_file_cursor(&"Game/common/K_NEW3.BIN".to_string(), None, None)?;
 
-        file.seek(SeekFrom::Start(0xDD86)).unwrap();
+        file.seek(SeekFrom::Start(0xDD86))?;
         let mut data = vec![0u8; 0x100];
-        file.read_exact(&mut data).unwrap();
+        file.read_exact(&mut data)?;
         let raw = self.convert_planar_chips_to_linear(data, 128, 4);
         let cursor_bitmap = Bitmap::from_raw_data(128, 64, raw);
 
@@ -584,25 +686,57 @@ impl FileSystemBackendTrait for FileSystemBackendPc {
             [0x29, 0x29, 0x29, 0xFF],
         ].to_vec());
 
-        (cursor_bitmap, cursor_palette)
+        Ok((cursor_bitmap, cursor_palette))
     }
 
-    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> (Bitmap, Palette) {
-        let mut file = self.get_file_cursor(&"Game/common/K_NEW3.BIN".to_string(), None, None);
+    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> FileSystemResult<(Bitmap, Palette)> {
+        let mut file = self.get_file_cursor(&"Game/common/K_NEW3.BIN".to_string(), None, None)?;
 
         // Read chip graphics.
         let start = 0xDE86 + ui_theme_index as u64 * 0x280;
-        file.seek(SeekFrom::Start(start)).unwrap();
+        file.seek(SeekFrom::Start(start))?;
         let mut data = vec![0u8; 0x1400];
-        file.read_exact(&mut data).unwrap();
+        file.read_exact(&mut data)?;
         let raw = self.convert_planar_chips_to_linear(data, 64, 4);
         let window_bitmap = Bitmap::from_raw_data(64, 24, raw);
 
         // Read palette data.
         let start = 0xF286 + ui_theme_index as u64 * 16;
-        file.seek(SeekFrom::Start(start)).unwrap();
-        let window_palette = self.read_palette(file, 0, 8, 1, 0, 8);
+        file.seek(SeekFrom::Start(start))?;
+        let window_palette = self.read_palette(file, 0, 8, 1, 0, 8)?;
 
-        (window_bitmap, window_palette)
+        Ok((window_bitmap, window_palette))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use super::*;
+
+    #[test]
+    fn keyed_strings_and_languages() {
+        let path = env::temp_dir().join(format!("ctviewer_pc_strings_{}", std::process::id()));
+        if path.exists() {
+            remove_dir_all(&path).unwrap();
+        }
+        create_dir_all(path.join("Localize/en/msg")).unwrap();
+        create_dir_all(path.join("Localize/de/msg")).unwrap();
+        create_dir_all(path.join("Localize/empty")).unwrap();
+        fs::write(path.join("Localize/en/msg/w_map.txt"), "0,Zeal\r\n").unwrap();
+        fs::write(path.join("Localize/de/msg/w_map.txt"), "0,Zeal\r\n").unwrap();
+        fs::write(path.join("Localize/en/msg/cmes0.txt"), "A,Hello, world\r\nB,Bye\r\n").unwrap();
+        fs::write(path.join("Localize/en/msg/readme.md"), "C,Ignored\r\n").unwrap();
+
+        let backend = FileSystemBackendPc::new(&path.clone().into(), FileSystemBackendPcMode::FileSystem);
+        assert_eq!(backend.get_languages().unwrap(), vec!["de", "en"]);
+        assert_eq!(backend.get_keyed_strings("en").unwrap(), vec![
+            ("cmes0".to_string(), "A".to_string(), "Hello, world".to_string()),
+            ("cmes0".to_string(), "B".to_string(), "Bye".to_string()),
+            ("w_map".to_string(), "0".to_string(), "Zeal".to_string()),
+        ]);
+
+        remove_dir_all(&path).unwrap();
     }
 }
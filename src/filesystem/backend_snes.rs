@@ -1,13 +1,118 @@
+use std::fs;
 use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::filesystem::backend::FileSystemBackendTrait;
+use crate::filesystem::error::{table_get, FileSystemError, FileSystemResult};
+use crate::filesystem::rom_identify::{identify_rom, print_rom_info};
 use crate::filesystem::text_decoder::TextDecoder;
+use crate::filesystem::text_encoder::TextEncoder;
+use crate::party::items::ItemId;
 use crate::software_renderer::bitmap::Bitmap;
 use crate::software_renderer::palette::Palette;
 use crate::util::lz_decompress::lz_decompress;
 
+const ROM_TITLE: &[u8] = b"CHRONO TRIGGER       ";
+const UNEXPANDED_ROM_SIZE: usize = 4 * 1024 * 1024;
+
+const SCENE_HEADERS_ADDRESS: usize = 0x360000;
+const SCENE_HEADER_SIZE: usize = 14;
+const SCENE_COUNT: usize = 512;
+
+// Start addresses of the scene headers and the scene pointer tables. An enlarged table cannot
+// extend past the start of the next one.
+const SCENE_POINTER_TABLES: [usize; 6] = [SCENE_HEADERS_ADDRESS, 0x361E00, 0x362100, 0x3621C0, 0x362220, 0x3CF9F0];
+const ITEM_COUNT: usize = 0xA71 / 11;
+const MAX_TEXTBOX_STRING_LEN: usize = 512;
+
+/// Locations of the text of a release. Text that has not been located in a release is None.
+struct SnesRomOffsets {
+    // Substring pointers and their count. The substrings are in the same bank.
+    substrings: Option<(usize, usize)>,
+
+    // Fixed length item names, and their length.
+    item_names: Option<(usize, usize)>,
+
+    // Fixed length tech names, their length and count.
+    tech_names: Option<(usize, usize, usize)>,
+
+    world_exit_names: Option<usize>,
+    world_names: Option<usize>,
+
+    player_names: [&'static str; 8],
+
+    // 2bpp 8x8 font with 256 characters.
+    font_8: Option<usize>,
+
+    // 2bpp 8x16 dialogue font with 96 characters, and its character width table.
+    font_16: Option<(usize, usize)>,
+
+    // Language code of the text in this ROM.
+    language: &'static str,
+}
+
+const SNES_NA_OFFSETS: SnesRomOffsets = SnesRomOffsets {
+    substrings: Some((0x1EFA00, 128)),
+    item_names: Some((0xC0B5E, 11)),
+    tech_names: None,
+    world_exit_names: Some(0x6F400),
+    world_names: Some(0x6F4D4),
+    player_names: ["Crono", "Marle", "Lucca", "Robo", "Frog", "Ayla", "Magus", "Epoch"],
+    font_8: Some(0x3F8000),
+    font_16: Some((0x3F8C60, 0x026046)),
+    language: "en",
+};
+
+/// How SNES addresses in ROM map to offsets in the ROM data.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RomMapping {
+    HiRom,
+
+    // Expanded ROMs larger than 4 MiB, with the upper part in banks 0x40 to 0x7D.
+    ExHiRom,
+}
+
+impl RomMapping {
+    pub fn detect(data: &[u8]) -> RomMapping {
+        if data.len() > UNEXPANDED_ROM_SIZE {
+            RomMapping::ExHiRom
+        } else {
+            RomMapping::HiRom
+        }
+    }
+
+    pub fn to_offset(&self, address: usize, rom_size: usize) -> Option<usize> {
+        let offset = match (self, address >> 16) {
+            (_, 0xC0..=0xFF) => address - 0xC00000,
+            (RomMapping::ExHiRom, 0x40..=0x7D) => address,
+            _ => return None,
+        };
+
+        if offset < rom_size {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// Address of the internal ROM header, expanded ROMs have it in the upper half.
+    pub fn header_address(&self) -> usize {
+        match self {
+            RomMapping::HiRom => 0xFFC0,
+            RomMapping::ExHiRom => 0x40FFC0,
+        }
+    }
+
+    pub fn to_address(&self, offset: usize) -> usize {
+        if offset >= UNEXPANDED_ROM_SIZE {
+            offset
+        } else {
+            0xC00000 + offset
+        }
+    }
+}
+
 #[derive(Default, Clone)]
 struct Entry {
     address: usize,
@@ -16,6 +121,7 @@ struct Entry {
 
 pub struct FileSystemBackendSnes {
     data: Vec<u8>,
+    offsets: &'static SnesRomOffsets,
     text_decoder: TextDecoder,
 
     world_header_entries: Vec<Entry>,
@@ -48,62 +154,69 @@ pub struct FileSystemBackendSnes {
 }
 
 impl FileSystemBackendSnes {
-    pub fn new(rom_path: &Path) -> Self {
-        let mut file = File::open(&rom_path).expect(&format!("Could not open ROM '{}'.", rom_path.display()));
-        if file.metadata().unwrap().len() > 6 * 1024 * 1024 {
-            panic!("ROM is too large.");
-        }
-
-        let mut data = vec![0u8; file.metadata().unwrap().len() as usize];
-        file.read_exact(&mut data).unwrap();
-
-        // Find ROM header and strip file format header if present.
-        let mut header = 0;
-        let id = str::from_utf8(&data[0x101C0..0x101D5]);
-        if id.is_ok() {
-            if id.unwrap() == "CHRONO TRIGGER       " {
-                header = 0x101C0;
-                data = data[0x200..].to_vec();
-            }
-        } else {
-            let id = str::from_utf8(&data[0xFFC0..0xFFD5]);
-            if id.is_ok() {
-                if id.unwrap() == "CHRONO TRIGGER       " {
-                    header = 0xFFC0;
-                }
-            }
-        }
-        if header == 0 {
-            panic!("ROM is not a Chrono Trigger ROM.");
-        }
+    pub fn new(rom_path: &Path) -> Result<Self, String> {
+        let (_, data) = read_rom(rom_path)?;
 
         println!("Using SNES data backend from {}.", rom_path.display());
 
-        let text_decoder = TextDecoder::from_cursor(&mut Cursor::new(data[0x1EFA00..0x1EFF00].to_vec()), 128, 0xFA00);
+        Self::from_data(data)
+    }
+
+    /// Uses ROM data that was already read and had its file format header removed.
+    pub fn from_data(data: Vec<u8>) -> Result<Self, String> {
+        let info = identify_rom(&data)?;
+        print_rom_info(&info, data.len());
+
+        let mapping = info.mapping;
+        let offsets = &SNES_NA_OFFSETS;
+
+        let text_decoder = match offsets.substrings {
+            Some((address, count)) => {
+                let bank = address & 0xFF0000;
+                let mut data = Cursor::new(data[bank..bank + 0x10000].to_vec());
+                data.seek(SeekFrom::Start((address - bank) as u64)).unwrap();
+                TextDecoder::from_cursor(&mut data, count, 0)
+            },
+            None => TextDecoder::new(),
+        };
 
         let world_header_entries = get_local_entries(&data, 0x6FD00, 8, 0x6FDF0, false);
-        let world_map_tile_entries = get_entries(&data, 0x6FF20, 8, 0x6C7F7);
-        let world_map_props_entries = get_entries(&data, 0x6FF80, 8, 0x6DA76);
-        let world_tileset_entries = get_entries(&data, 0x6FE20, 42, 0x59A56);
-        let world_tileset12_assembly_entries = get_entries(&data, 0x6FF00, 6, 0x66A39);
-        let world_tileset3_assembly_entries = get_entries(&data, 0x6FF40, 6, 0x6D410);
-        let world_music_entries = get_entries(&data, 0x6FFA0, 3, 0x6DD06);
-        let world_exit_entries = get_entries(&data, 0x6FFC0, 8, 0x629EC);
-        let world_sprite_entries = get_entries(&data, 0x6FDF0, 16, 0x61E73);
-        let world_palette_entries = get_entries(&data, 0x6FEA0, 32, 0x3E000);
-        let world_script_entries = get_entries(&data, 0x6FFE0, 8, 0x3CE43);
-
-        let scene_map_entries = get_entries(&data, 0x361E00, 202, 0x35EE02);
-        let scene_tileset_entries = get_entries(&data, 0x362220, 204, 0x3D8E64);
-        let scene_tileset12_assembly_entries = get_entries(&data, 0x362100, 64, 0x2F7F41);
+        let world_map_tile_entries = get_entries(&data, mapping, 0x6FF20, 8, 0x6C7F7);
+        let world_map_props_entries = get_entries(&data, mapping, 0x6FF80, 8, 0x6DA76);
+        let world_tileset_entries = get_entries(&data, mapping, 0x6FE20, 42, 0x59A56);
+        let world_tileset12_assembly_entries = get_entries(&data, mapping, 0x6FF00, 6, 0x66A39);
+        let world_tileset3_assembly_entries = get_entries(&data, mapping, 0x6FF40, 6, 0x6D410);
+        let world_music_entries = get_entries(&data, mapping, 0x6FFA0, 3, 0x6DD06);
+        let world_exit_entries = get_entries(&data, mapping, 0x6FFC0, 8, 0x629EC);
+        let world_sprite_entries = get_entries(&data, mapping, 0x6FDF0, 16, 0x61E73);
+        let world_palette_entries = get_entries(&data, mapping, 0x6FEA0, 32, 0x3E000);
+        let world_script_entries = get_entries(&data, mapping, 0x6FFE0, 8, 0x3CE43);
+
+        // Tables that scene headers index into can be enlarged by editors, so they are sized by
+        // the largest index that is used.
+        let scene_headers = &data[SCENE_HEADERS_ADDRESS..SCENE_HEADERS_ADDRESS + SCENE_COUNT * SCENE_HEADER_SIZE];
+        let max_header_value = |offset: usize, word: bool| {
+            scene_headers.chunks_exact(SCENE_HEADER_SIZE)
+                .map(|header| if word { header[offset] as usize | (header[offset + 1] as usize) << 8 } else { header[offset] as usize })
+                .max()
+                .unwrap_or(0)
+        };
+        let scene_map_count = table_count(&data, 0x361E00, 202, max_header_value(4, true) + 1, &SCENE_POINTER_TABLES);
+        let scene_tileset12_assembly_count = table_count(&data, 0x362100, 64, max_header_value(1, false) + 1, &SCENE_POINTER_TABLES);
+        let scene_tileset3_assembly_count = table_count(&data, 0x3621C0, 19, max_header_value(2, false) + 1, &SCENE_POINTER_TABLES);
+        let scene_script_count = table_count(&data, 0x3CF9F0, 513, max_header_value(8, true) + 1, &SCENE_POINTER_TABLES);
+
+        let scene_map_entries = get_entries(&data, mapping, 0x361E00, scene_map_count, 0x35EE02);
+        let scene_tileset_entries = get_entries(&data, mapping, 0x362220, 204, 0x3D8E64);
+        let scene_tileset12_assembly_entries = get_entries(&data, mapping, 0x362100, scene_tileset12_assembly_count, 0x2F7F41);
         let scene_tileset12_animation_entries = get_relative_entries(&data, 0x3DF290, 64, 0x3DF310, 0x3DF9CC);
-        let scene_tileset3_assembly_entries = get_entries(&data, 0x3621C0, 19, 0x2FB168);
+        let scene_tileset3_assembly_entries = get_entries(&data, mapping, 0x3621C0, scene_tileset3_assembly_count, 0x2FB168);
         let scene_exit_entries = get_local_entries(&data, 0x250000, 512, 0x251A44, true);
-        let scene_script_entries = get_entries(&data, 0x3CF9F0, 513, 0x3D9FEA);
+        let scene_script_entries = get_entries(&data, mapping, 0x3CF9F0, scene_script_count, 0x3D9FEA);
 
         let sprite_pointer_entries = get_local_entries(&data, 0x24FFE0, 7, 0x24FFEE, false);
-        let sprite_entries = get_entries(&data, sprite_pointer_entries[0].address, 248, 0x21DDB2);
-        let sprite_assembly_entries = get_entries(&data, sprite_pointer_entries[1].address, 231, 0x23F8C0);
+        let sprite_entries = get_entries(&data, mapping, sprite_pointer_entries[0].address, 248, 0x21DDB2);
+        let sprite_assembly_entries = get_entries(&data, mapping, sprite_pointer_entries[1].address, 231, 0x23F8C0);
         let sprite_anim_frame_entries = get_local_entries(&data, sprite_pointer_entries[2].address, 194, 0x24A800, false);
         let sprite_anim_duration_entries = get_local_entries(&data, sprite_pointer_entries[3].address, 194, 0x24F000, false);
         let sprite_palette_entries = get_local_entries(&data, sprite_pointer_entries[4].address, 253, 0x243000, false);
@@ -116,8 +229,21 @@ impl FileSystemBackendSnes {
             length: 9312,
         };
 
-        FileSystemBackendSnes {
+        print_layout(&data, mapping, &[
+            ("World maps", &world_map_tile_entries),
+            ("World tilesets", &world_tileset_entries),
+            ("World scripts", &world_script_entries),
+            ("Scene maps", &scene_map_entries),
+            ("Scene tilesets", &scene_tileset_entries),
+            ("Scene tileset assemblies", &scene_tileset12_assembly_entries),
+            ("Scene layer 3 tileset assemblies", &scene_tileset3_assembly_entries),
+            ("Scene scripts", &scene_script_entries),
+            ("Sprites", &sprite_entries),
+        ]);
+
+        Ok(FileSystemBackendSnes {
             data,
+            offsets,
 
             text_decoder,
 
@@ -148,235 +274,285 @@ impl FileSystemBackendSnes {
             sprite_palette_entries,
             sprite_anim_frame_data_entry,
             sprite_anim_duration_data_entry,
+        })
+    }
+
+    /// Encodes tagged text as a string in a textbox string table, using this ROM's dictionary.
+    pub fn encode_string(&self, text: &str) -> Result<Vec<u8>, String> {
+        TextEncoder::new(&self.text_decoder).encode(text)
+    }
+
+    /// Returns the ROM offset and encoded length of each string in a textbox string table.
+    pub fn textbox_string_ranges(&self, address: usize) -> FileSystemResult<Vec<(usize, usize)>> {
+        let mut ranges = Vec::new();
+        for offset in self.textbox_string_offsets(address)? {
+            let mut data = self.get_bytes_cursor(offset, MAX_TEXTBOX_STRING_LEN)?;
+            self.text_decoder.decode_huffman_string(&mut data);
+            ranges.push((offset, data.position() as usize));
         }
+
+        Ok(ranges)
     }
 
-    fn get_bytes(&self, offset: usize, len: usize) -> Vec<u8> {
-        self.data[offset..offset + len].to_vec()
+    // Returns the ROM offsets of the strings in a textbox string table.
+    fn textbox_string_offsets(&self, address: usize) -> FileSystemResult<Vec<usize>> {
+        let page_start = address & 0xFF0000;
+
+        let mut offsets = Vec::new();
+        for index in 0..256 {
+
+            // Cludgy way to read a 16 bit pointer, the next one, then verify if this is still
+            // likely a valid one based on their difference.
+            let pointers = self.get_bytes(address + index * 2, 4)?;
+            let ptr = pointers[0] as usize | ((pointers[1] as usize) << 8);
+            let ptr_next = pointers[2] as usize | ((pointers[3] as usize) << 8);
+            if ptr_next < ptr || ptr_next - ptr > MAX_TEXTBOX_STRING_LEN {
+                break;
+            }
+            offsets.push(page_start + ptr);
+        }
+
+        Ok(offsets)
     }
 
-    fn get_bytes_cursor(&self, offset: usize, len: usize) -> Cursor<Vec<u8>> {
-        Cursor::new(self.data[offset..offset + len].to_vec())
+    fn get_bytes(&self, offset: usize, len: usize) -> FileSystemResult<Vec<u8>> {
+        match self.data.get(offset..offset + len) {
+            Some(data) => Ok(data.to_vec()),
+            None => Err(FileSystemError::InvalidData(format!("Data at 0x{:06X} is outside of the ROM", offset))),
+        }
     }
 
-    fn get_bytes_lz(&self, offset: usize) -> Vec<u8> {
+    fn get_bytes_cursor(&self, offset: usize, len: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        Ok(Cursor::new(self.get_bytes(offset, len)?))
+    }
+
+    // Null entries have no data.
+    fn get_bytes_lz(&self, offset: usize) -> FileSystemResult<Vec<u8>> {
+        if offset == 0 || offset >= self.data.len() {
+            return Err(FileSystemError::InvalidData(format!("Compressed data at 0x{:06X} is outside of the ROM", offset)));
+        }
+
         let mut decompressed_data = vec![0u8; 0x10000];
         let decompressed_len = lz_decompress(&self.data, &mut decompressed_data, offset);
         decompressed_data.resize(decompressed_len, 0);
 
-        decompressed_data
+        Ok(decompressed_data)
     }
 
-    fn get_bytes_cursor_lz(&self, offset: usize) -> Cursor<Vec<u8>> {
-        let decompressed_data = self.get_bytes_lz(offset);
+    fn get_bytes_cursor_lz(&self, offset: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        let decompressed_data = self.get_bytes_lz(offset)?;
 
-        Cursor::new(decompressed_data)
+        Ok(Cursor::new(decompressed_data))
     }
 
-    fn read_string_list(&self, pointers_start: usize, count: usize) -> Vec<String> {
+    // Strings that have not been located for this release are named after their index.
+    fn read_string_list(&self, pointers_start: Option<usize>, count: usize, name: &str) -> FileSystemResult<Vec<String>> {
+        let Some(pointers_start) = pointers_start else {
+            return Ok((0..count).map(|index| format!("{} {}", name, index)).collect());
+        };
+
         let segment = pointers_start & 0xFF0000;
-        let segment_data = self.data[segment..segment + 0x10000].to_vec();
-        let mut cursor = Cursor::new(segment_data);
+        let mut cursor = self.get_bytes_cursor(segment, 0x10000)?;
 
         let entries = get_relative_entries(&self.data, pointers_start, count, 0, 0x10000);
         let mut strings = Vec::<String>::with_capacity(count);
         for entry in entries.iter() {
-            cursor.seek(SeekFrom::Start(entry.address as u64)).unwrap();
+            cursor.seek(SeekFrom::Start(entry.address as u64))?;
             strings.push(self.text_decoder.decode_huffman_string(&mut cursor));
         }
 
-        strings
+        Ok(strings)
     }
 }
 
 impl FileSystemBackendTrait for FileSystemBackendSnes {
-    fn get_world_header_data(&self, world_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor(self.world_header_entries[world_index].address, self.world_header_entries[world_index].length)
+    fn get_world_header_data(&self, world_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor(table_get(&self.world_header_entries, world_index, "world header")?.address, table_get(&self.world_header_entries, world_index, "world header")?.length)
     }
 
-    fn get_world_map_tile_data(&self, world_map_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_map_tile_entries[world_map_index].address)
+    fn get_world_map_tile_data(&self, world_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_map_tile_entries, world_map_index, "world map tile")?.address)
     }
 
-    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_map_props_entries[world_map_props_index].address)
+    fn get_world_map_tile_props_data(&self, world_map_props_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_map_props_entries, world_map_props_index, "world map props")?.address)
     }
 
-    fn get_world_tileset12_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
-        if self.world_tileset_entries[chips_index].address == 0 {
-            return None;
+    fn get_world_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if table_get(&self.world_tileset_entries, chips_index, "world tileset")?.address == 0 {
+            return Ok(None);
         }
 
-        let data = self.get_bytes_lz(self.world_tileset_entries[chips_index].address);
+        let data = self.get_bytes_lz(table_get(&self.world_tileset_entries, chips_index, "world tileset")?.address)?;
         let pixels = self.convert_planar_chips_to_linear(data, 128, 4);
-        Some(pixels)
+        Ok(Some(pixels))
     }
 
-    fn get_world_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
-        if self.world_tileset_entries[chips_index].address == 0 {
-            return None;
+    fn get_world_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if table_get(&self.world_tileset_entries, chips_index, "world tileset")?.address == 0 {
+            return Ok(None);
         }
 
-        let data = self.get_bytes_lz(self.world_tileset_entries[chips_index].address);
+        let data = self.get_bytes_lz(table_get(&self.world_tileset_entries, chips_index, "world tileset")?.address)?;
         let pixels = self.convert_planar_chips_to_linear(data, 128, 2);
-        Some(pixels)
+        Ok(Some(pixels))
     }
 
-    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_tileset12_assembly_entries[assembly_index].address)
+    fn get_world_tileset12_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_tileset12_assembly_entries, assembly_index, "world tileset12 assembly")?.address)
     }
 
-    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_tileset3_assembly_entries[assembly_index].address)
+    fn get_world_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_tileset3_assembly_entries, assembly_index, "world tileset3 assembly")?.address)
     }
 
-    fn get_world_music_data(&self, music_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_music_entries[music_index].address)
+    fn get_world_music_data(&self, music_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_music_entries, music_index, "world music")?.address)
     }
 
-    fn get_world_exits_triggers_data(&self, exits_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_exit_entries[exits_index].address)
+    fn get_world_exits_triggers_data(&self, exits_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_exit_entries, exits_index, "world exit")?.address)
     }
 
-    fn get_world_exit_names(&self, _language: &str) -> Vec<String> {
-        self.read_string_list(0x6F400, 112)
+    fn get_world_exit_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        self.read_string_list(self.offsets.world_exit_names, 112, "Exit")
     }
 
-    fn get_world_names(&self, _language: &str) -> Vec<String> {
-        self.read_string_list(0x6F4D4, 6)
+    fn get_world_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        self.read_string_list(self.offsets.world_names, 6, "World")
     }
 
-    fn get_world_sprite_data(&self) -> Vec<u8> {
+    fn get_world_sprite_data(&self) -> FileSystemResult<Vec<u8>> {
         self.get_bytes(0x3E000, 8192)
     }
 
-    fn get_world_sprite_graphics(&self, _world_index: usize, tiles_index: usize) -> Option<Vec<u8>> {
-        if self.world_sprite_entries[tiles_index].address == 0 {
-            return None;
+    fn get_world_sprite_graphics(&self, _world_index: usize, tiles_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if table_get(&self.world_sprite_entries, tiles_index, "world sprite")?.address == 0 {
+            return Ok(None);
         }
 
-        let data = self.get_bytes_lz(self.world_sprite_entries[tiles_index].address);
+        let data = self.get_bytes_lz(table_get(&self.world_sprite_entries, tiles_index, "world sprite")?.address)?;
         let pixels = self.convert_planar_chips_to_linear(data, 128, 4);
-        Some(pixels)
+        Ok(Some(pixels))
     }
 
-    fn get_world_player_sprite_graphics(&self) -> Option<Vec<u8>> {
+    fn get_world_player_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
         self.get_world_sprite_graphics(0, 0)
     }
 
-    fn get_world_epoch_sprite_graphics(&self) -> Option<Vec<u8>> {
+    fn get_world_epoch_sprite_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
         self.get_world_sprite_graphics(0, 1)
     }
 
-    fn get_world_palette(&self, world_palette_index: usize) -> Palette {
-        let data = self.get_bytes_cursor_lz(self.world_palette_entries[world_palette_index].address);
+    fn get_world_palette(&self, world_palette_index: usize) -> FileSystemResult<Palette> {
+        let data = self.get_bytes_cursor_lz(table_get(&self.world_palette_entries, world_palette_index, "world palette")?.address)?;
         self.read_palette(data, 0, 16, 16, 0, 0)
     }
 
-    fn get_world_player_palettes(&self) -> Palette {
-        let data = self.get_bytes_cursor_lz(self.world_palette_entries[0].address);
+    fn get_world_player_palettes(&self) -> FileSystemResult<Palette> {
+        let data = self.get_bytes_cursor_lz(table_get(&self.world_palette_entries, 0, "world palette")?.address)?;
         self.read_palette(data, 0, 16, 8, 0, 0)
     }
 
-    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.world_palette_entries[world_palette_index].address)
+    fn get_world_palette_anim_data(&self, world_palette_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.world_palette_entries, world_palette_index, "world palette")?.address)
     }
 
-    fn get_world_script_data(&self, script_index: usize) -> Vec<u8> {
-        self.get_bytes_lz(self.world_script_entries[script_index].address)
+    fn get_world_script_data(&self, script_index: usize) -> FileSystemResult<Vec<u8>> {
+        self.get_bytes_lz(table_get(&self.world_script_entries, script_index, "world script")?.address)
     }
 
-    fn get_scene_palette_anim_data(&self) -> (Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>) {
-        (
-            self.get_bytes_cursor(0x3DF9C7, 0xB6),
-            self.get_bytes_cursor(0x3DFA77, 0x56E),
-            self.get_bytes_cursor(0x367380, 0xC80),
-        )
+    fn get_scene_palette_anim_data(&self) -> FileSystemResult<(Cursor<Vec<u8>>, Cursor<Vec<u8>>, Cursor<Vec<u8>>)> {
+        Ok((
+            self.get_bytes_cursor(0x3DF9C7, 0xB6)?,
+            self.get_bytes_cursor(0x3DFA77, 0x56E)?,
+            self.get_bytes_cursor(0x367380, 0xC80)?,
+        ))
     }
 
-    fn get_scene_palette(&self, scene_palette_index: usize) -> Palette {
-        let data = self.get_bytes_cursor(0x3624C0 + scene_palette_index * 210, 210);
+    fn get_scene_palette(&self, scene_palette_index: usize) -> FileSystemResult<Palette> {
+        let data = self.get_bytes_cursor(0x3624C0 + scene_palette_index * 210, 210)?;
         self.read_palette(data, 16, 15, 7, 1, 0)
     }
 
-    fn get_scene_header_data(&self, scene_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_header_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_bytes_cursor(0x360000 + scene_index * 14, 14)
     }
 
-    fn get_scene_map_data(&self, scene_map_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.scene_map_entries[scene_map_index].address)
+    fn get_scene_map_data(&self, scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.scene_map_entries, scene_map_index, "scene map")?.address)
     }
 
-    fn get_scene_layer_priorities(&self, _scene_map_index: usize) -> Cursor<Vec<u8>> {
-        Cursor::new([3u8, 1, 2, 2].to_vec())
+    fn get_scene_layer_priorities(&self, _scene_map_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        Ok(Cursor::new([3u8, 1, 2, 2].to_vec()))
     }
 
-    fn get_scene_tileset_data(&self, tileset_index: usize) -> Cursor<Vec<u8>> {
+    fn get_scene_tileset_data(&self, tileset_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
         self.get_bytes_cursor(0x361C00 + tileset_index * 8, 8)
     }
 
-    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> Option<Vec<u8>> {
-        if self.scene_tileset_entries[chips_index].address == 0 {
-            return None;
+    fn get_scene_tileset3_graphics(&self, chips_index: usize) -> FileSystemResult<Option<Vec<u8>>> {
+        if table_get(&self.scene_tileset_entries, chips_index, "scene tileset")?.address == 0 {
+            return Ok(None);
         }
-        let data = self.get_bytes_lz(self.scene_tileset_entries[chips_index].address);
-        Some(self.convert_planar_chips_to_linear(data, 128, 2))
+        let data = self.get_bytes_lz(table_get(&self.scene_tileset_entries, chips_index, "scene tileset")?.address)?;
+        Ok(Some(self.convert_planar_chips_to_linear(data, 128, 2)))
     }
 
-    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> Option<Cursor<Vec<u8>>> {
-        if self.scene_tileset3_assembly_entries[assembly_index].address == 0 {
-            return None;
+    fn get_scene_tileset3_assembly_data(&self, assembly_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+        if table_get(&self.scene_tileset3_assembly_entries, assembly_index, "scene tileset3 assembly")?.address == 0 {
+            return Ok(None);
         }
-        Some(self.get_bytes_cursor_lz(self.scene_tileset3_assembly_entries[assembly_index].address))
+        Ok(Some(self.get_bytes_cursor_lz(table_get(&self.scene_tileset3_assembly_entries, assembly_index, "scene tileset3 assembly")?.address)?))
     }
 
-    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> Vec<u8> {
-        let data = self.get_bytes_lz(self.scene_tileset_entries[chips_index].address);
-        self.convert_planar_chips_to_linear(data, 128, 4)
+    fn get_scene_tileset12_graphics(&self, chips_index: usize) -> FileSystemResult<Vec<u8>> {
+        let data = self.get_bytes_lz(table_get(&self.scene_tileset_entries, chips_index, "scene tileset")?.address)?;
+        Ok(self.convert_planar_chips_to_linear(data, 128, 4))
     }
 
-    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.scene_tileset12_assembly_entries[index_assembly].address)
+    fn get_scene_tileset12_assembly_data(&self, index_assembly: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.scene_tileset12_assembly_entries, index_assembly, "scene tileset12 assembly")?.address)
     }
 
-    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> Option<Cursor<Vec<u8>>> {
-        if self.scene_tileset12_animation_entries[chip_anims_index].address == 0 {
-            return None;
+    fn get_scene_tileset12_animation_data(&self, chip_anims_index: usize) -> FileSystemResult<Option<Cursor<Vec<u8>>>> {
+        if table_get(&self.scene_tileset12_animation_entries, chip_anims_index, "scene tileset12 animation")?.address == 0 {
+            return Ok(None);
         }
 
-        Some(self.get_bytes_cursor(self.scene_tileset12_animation_entries[chip_anims_index].address, self.scene_tileset12_animation_entries[chip_anims_index].length))
+        Ok(Some(self.get_bytes_cursor(table_get(&self.scene_tileset12_animation_entries, chip_anims_index, "scene tileset12 animation")?.address, table_get(&self.scene_tileset12_animation_entries, chip_anims_index, "scene tileset12 animation")?.length)?))
     }
 
-    fn get_scene_exit_data(&self, scene_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor(self.scene_exit_entries[scene_index].address, self.scene_exit_entries[scene_index].length)
+    fn get_scene_exit_data(&self, scene_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor(table_get(&self.scene_exit_entries, scene_index, "scene exit")?.address, table_get(&self.scene_exit_entries, scene_index, "scene exit")?.length)
     }
 
-    fn get_scene_names(&self, _language: &str) -> Vec<String> {
-        let mut reader = File::open(&"data/snes_na_scenes.txt").unwrap();
-        let mut buffer = vec![0u8; reader.metadata().unwrap().len() as usize];
-        reader.read_exact(&mut buffer).unwrap();
+    fn get_scene_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        let mut reader = File::open(&"data/snes_na_scenes.txt").map_err(|_| FileSystemError::FileNotFound("data/snes_na_scenes.txt".to_string()))?;
+        let mut buffer = vec![0u8; reader.metadata()?.len() as usize];
+        reader.read_exact(&mut buffer)?;
 
         self.read_text_string_list(Cursor::new(buffer), None, None)
     }
 
-    fn get_scene_treasure_data(&self) -> (Vec<u32>, Cursor<Vec<u8>>) {
-        let mut pointer_data = self.get_bytes_cursor(0x35F000, 0x402);
+    fn get_scene_treasure_data(&self) -> FileSystemResult<(Vec<u32>, Cursor<Vec<u8>>)> {
+        let mut pointer_data = self.get_bytes_cursor(0x35F000, 0x402)?;
         let mut pointers = vec![0u32; 513];
         for pointer in pointers.iter_mut() {
-            *pointer = pointer_data.read_u16::<LittleEndian>().unwrap() as u32 - 0xF402;
+            *pointer = (pointer_data.read_u16::<LittleEndian>()? as u32).checked_sub(0xF402).ok_or(FileSystemError::InvalidData("Treasure pointer is before the treasure data".to_string()))?;
         }
 
-        (
+        Ok((
             pointers,
-            self.get_bytes_cursor(0x35F402, 0x3E4),
-        )
+            self.get_bytes_cursor(0x35F402, 0x3E4)?,
+        ))
     }
 
-    fn get_scene_script_data(&self, scene_script_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor_lz(self.scene_script_entries[scene_script_index].address)
+    fn get_scene_script_data(&self, scene_script_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor_lz(table_get(&self.scene_script_entries, scene_script_index, "scene script")?.address)
     }
 
-    fn get_sprite_header_data(&self, sprite_index: usize) -> Cursor<Vec<u8>> {
+    fn get_sprite_header_data(&self, sprite_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
 
         // Regular PC/NPC sprites.
         if sprite_index < 0x100 {
@@ -387,135 +563,225 @@ impl FileSystemBackendTrait for FileSystemBackendSnes {
         self.get_bytes_cursor(0x24F600 + (sprite_index - 256) * 10, 10)
     }
 
-    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> Cursor<Vec<u8>> {
-        self.get_bytes_cursor(self.sprite_assembly_entries[sprite_assembly_index].address, self.sprite_assembly_entries[sprite_assembly_index].length)
+    fn get_sprite_assembly_data(&self, sprite_assembly_index: usize) -> FileSystemResult<Cursor<Vec<u8>>> {
+        self.get_bytes_cursor(table_get(&self.sprite_assembly_entries, sprite_assembly_index, "sprite assembly")?.address, table_get(&self.sprite_assembly_entries, sprite_assembly_index, "sprite assembly")?.length)
     }
 
-    fn get_sprite_animation_data(&self) -> (Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>) {
-        let offset = self.sprite_anim_frame_entries[0].address;
+    fn get_sprite_animation_data(&self) -> FileSystemResult<(Vec<usize>, Cursor<Vec<u8>>, Vec<usize>, Cursor<Vec<u8>>)> {
+        let offset = table_get(&self.sprite_anim_frame_entries, 0, "sprite anim frame")?.address;
         let mut frames_pointers = Vec::with_capacity(self.sprite_anim_frame_entries.len());
         for entry in self.sprite_anim_frame_entries.iter() {
             frames_pointers.push(entry.address - offset);
         }
 
-        let offset = self.sprite_anim_duration_entries[0].address;
+        let offset = table_get(&self.sprite_anim_duration_entries, 0, "sprite anim duration")?.address;
         let mut duration_pointers = Vec::with_capacity(self.sprite_anim_frame_entries.len());
         for entry in self.sprite_anim_duration_entries.iter() {
             duration_pointers.push(entry.address - offset);
         }
 
-        (
+        Ok((
             frames_pointers,
-            self.get_bytes_cursor(self.sprite_anim_frame_data_entry.address, self.sprite_anim_frame_data_entry.length),
+            self.get_bytes_cursor(self.sprite_anim_frame_data_entry.address, self.sprite_anim_frame_data_entry.length)?,
             duration_pointers,
-            self.get_bytes_cursor(self.sprite_anim_duration_data_entry.address, self.sprite_anim_duration_data_entry.length),
-        )
+            self.get_bytes_cursor(self.sprite_anim_duration_data_entry.address, self.sprite_anim_duration_data_entry.length)?,
+        ))
     }
 
-    fn get_sprite_palette(&self, sprite_index: usize) -> Option<Palette> {
-        let entry = &self.sprite_palette_entries[sprite_index];
+    fn get_sprite_palette(&self, sprite_index: usize) -> FileSystemResult<Option<Palette>> {
+        let entry = table_get(&self.sprite_palette_entries, sprite_index, "sprite palette")?;
         if entry.address == 0 {
-            return None;
+            return Ok(None);
         }
-        let data = self.get_bytes_cursor(entry.address, 24);
+        let data = self.get_bytes_cursor(entry.address, 24)?;
 
-        Some(self.read_palette(data, 0, 12, 1, 1, 3))
+        Ok(Some(self.read_palette(data, 0, 12, 1, 1, 3)?))
     }
 
-    fn get_sprite_graphics(&self, sprite_index: usize, _chip_count: usize, compressed: bool) -> Vec<u8> {
-        let entry = &self.sprite_entries[sprite_index];
+    fn get_sprite_graphics(&self, sprite_index: usize, _chip_count: usize, compressed: bool) -> FileSystemResult<Vec<u8>> {
+        let entry = table_get(&self.sprite_entries, sprite_index, "sprite")?;
         let data = if compressed {
-            self.get_bytes_lz(entry.address)
+            self.get_bytes_lz(entry.address)?
         } else {
-            self.data[entry.address..entry.address + entry.length].to_vec()
+            self.get_bytes(entry.address, entry.length)?
         };
 
-        self.convert_planar_chips_to_linear(data, 128, 4)
+        Ok(self.convert_planar_chips_to_linear(data, 128, 4))
     }
 
-    fn get_item_names(&self, _language: &str) -> Vec<String> {
-        let mut data = self.get_bytes_cursor(0xC0B5E, 0xA71);
-
-        let mut strings = Vec::<String>::new();
-        for _ in 0..data.get_ref().len() / 11 {
-            let mut item = vec![0u8; 11];
-            data.read_exact(&mut item).unwrap();
-            strings.push(self.text_decoder.decode_mapped_string(item));
-        }
+    fn get_item_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        let Some((address, length)) = self.offsets.item_names else {
+            return Ok((0..ITEM_COUNT).map(|index| format!("Item {}", index)).collect());
+        };
+        let mut data = self.get_bytes_cursor(address, ITEM_COUNT * length)?;
+        let strings = read_mapped_strings(&self.text_decoder, &mut data, length, ITEM_COUNT)?;
 
         // todo: remove first character, this is either a space or item type symbol we do not
         //  need (yet).
 
-        strings
+        Ok(strings)
     }
 
-    fn get_player_names(&self, _language: &str) -> Vec<String> {
-        [
-            String::from("Crono"),
-            String::from("Marle"),
-            String::from("Lucca"),
-            String::from("Robo"),
-            String::from("Frog"),
-            String::from("Ayla"),
-            String::from("Magus"),
-            String::from("Epoch"),
-        ].to_vec()
+    // Without located tech names, text falls back to numbered techs.
+    fn get_tech_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        let Some((address, length, count)) = self.offsets.tech_names else {
+            return Ok(Vec::new());
+        };
+        let mut data = self.get_bytes_cursor(address, count * length)?;
+
+        read_mapped_strings(&self.text_decoder, &mut data, length, count)
     }
 
-    fn get_textbox_string_table(&self, address: usize, _language: &str) -> Vec<String> {
-        let page_start = address & 0xFF0000;
-        const MAX_STR_LEN: usize = 512;
+    fn get_languages(&self) -> FileSystemResult<Vec<String>> {
+        Ok(vec![self.offsets.language.to_string()])
+    }
 
-        let mut strings = Vec::<String>::new();
-        for index in 0..256 {
+    fn get_keyed_strings(&self, _language: &str) -> FileSystemResult<Vec<(String, String, String)>> {
+        Ok(Vec::new())
+    }
 
-            // Cludgy way to read a 16 bit pointer, the next one, then verify if this is still
-            // likely a valid one based on their difference.
-            let ptr_start = address + index * 2;
-            let ptr = self.data[ptr_start] as usize | ((self.data[ptr_start + 1] as usize) << 8);
-            let ptr_next = self.data[ptr_start + 2] as usize | ((self.data[ptr_start + 3] as usize) << 8);
-            if ptr_next < ptr || ptr_next - ptr > MAX_STR_LEN {
-                break;
-            }
+    fn get_player_names(&self, _language: &str) -> FileSystemResult<Vec<String>> {
+        Ok(self.offsets.player_names.iter().map(|name| name.to_string()).collect())
+    }
 
-            // Decode the string.
-            let mut data = self.get_bytes_cursor(page_start + ptr, MAX_STR_LEN);
-            let str = self.text_decoder.decode_huffman_string(&mut data);
-            strings.push(str);
+    // Shop item lists and item prices have not been located in the ROM yet.
+    fn get_shop_items(&self, _shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>> {
+        Ok(None)
+    }
+
+    fn get_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>> {
+        Ok(None)
+    }
+
+    fn get_textbox_string_table(&self, address: usize, _language: &str) -> FileSystemResult<Vec<String>> {
+        let mut strings = Vec::<String>::new();
+        for offset in self.textbox_string_offsets(address)? {
+            let mut data = self.get_bytes_cursor(offset, MAX_TEXTBOX_STRING_LEN)?;
+            strings.push(self.text_decoder.decode_huffman_string(&mut data));
         }
 
-        strings
+        Ok(strings)
     }
 
-    fn get_ui_theme_cursor_graphics(&self) -> (Bitmap, Palette) {
-        let data = self.data[0x3F9CF0..0x3F9CF0 + 0x100].to_vec();
+    fn get_font_8_graphics(&self) -> FileSystemResult<Option<Vec<u8>>> {
+        let Some(offset) = self.offsets.font_8 else {
+            return Ok(None);
+        };
+
+        let data = self.get_bytes(offset, 256 * 16)?;
+        Ok(Some(self.convert_planar_chips_to_linear(data, 128, 2)))
+    }
+
+    fn get_font_16_graphics(&self) -> FileSystemResult<Option<(Vec<u8>, Vec<u8>)>> {
+        let Some((offset, widths_offset)) = self.offsets.font_16 else {
+            return Ok(None);
+        };
+
+        // Each row of 16 characters is stored as a row of top halves followed by a row of bottom
+        // halves, which the linear conversion puts in place.
+        let data = self.get_bytes(offset, 96 * 32)?;
+        let widths = self.get_bytes(widths_offset, 96)?;
+        Ok(Some((self.convert_planar_chips_to_linear(data, 128, 2), widths)))
+    }
+
+    fn get_ui_theme_cursor_graphics(&self) -> FileSystemResult<(Bitmap, Palette)> {
+        let data = self.get_bytes(0x3F9CF0, 0x100)?;
         let raw = self.convert_planar_chips_to_linear(data, 128, 4);
         let cursor_bitmap = Bitmap::from_raw_data(128, 64, raw);
-        let cursor_palette = self.read_palette(self.get_bytes_cursor(0x3F9DF0, 32), 0, 16, 1, 0, 0);
+        let cursor_palette = self.read_palette(self.get_bytes_cursor(0x3F9DF0, 32)?, 0, 16, 1, 0, 0)?;
 
-        (cursor_bitmap, cursor_palette)
+        Ok((cursor_bitmap, cursor_palette))
     }
 
-    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> (Bitmap, Palette) {
+    fn get_ui_theme_window_graphics(&self, ui_theme_index: usize) -> FileSystemResult<(Bitmap, Palette)> {
         let start = 0x3F9E10 + ui_theme_index * 0x280;
-        let data = self.data[start..start + 0x1400].to_vec();
+        let data = self.get_bytes(start, 0x1400)?;
         let raw = self.convert_planar_chips_to_linear(data, 64, 4);
 
         let window_bitmap = Bitmap::from_raw_data(64, 24, raw);
-        let window_palette = self.read_palette(self.get_bytes_cursor(0x3FB210 + ui_theme_index * 16, 16), 0, 8, 1, 0, 8);
+        let window_palette = self.read_palette(self.get_bytes_cursor(0x3FB210 + ui_theme_index * 16, 16)?, 0, 8, 1, 0, 8)?;
 
-        (window_bitmap, window_palette)
+        Ok((window_bitmap, window_palette))
     }
 }
 
+/// Reads a ROM image, and returns the file format header if present and the ROM data without it.
+/// Use identify_rom to check if the data is supported.
+pub fn read_rom(rom_path: &Path) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let mut data = fs::read(rom_path).map_err(|e| format!("Could not open ROM '{}': {}.", rom_path.display(), e))?;
+
+    // Strip the file format header if present, it makes the size 512 bytes more than a multiple of 32 KiB.
+    let mut file_header = Vec::new();
+    if data.len() % 0x8000 == 0x200 || has_rom_title(&data, 0x101C0) {
+        file_header = data[..0x200.min(data.len())].to_vec();
+        data = data[file_header.len()..].to_vec();
+    }
+
+    Ok((file_header, data))
+}
+
+fn has_rom_title(data: &[u8], header_address: usize) -> bool {
+    data.get(header_address..header_address + ROM_TITLE.len()) == Some(ROM_TITLE)
+}
+
 fn get_bytes_cursor(data: &Vec<u8>, offset: usize, len: usize) -> Cursor<Vec<u8>> {
     Cursor::new(data[offset..(offset + len)].to_vec())
 }
 
+// Summarizes where tables were found. Only expanded ROMs list the tables, with how many of their
+// entries are in the expanded part.
+// Reads a number of fixed length strings in the 8x8 font's character mapping.
+fn read_mapped_strings(text_decoder: &TextDecoder, data: &mut Cursor<Vec<u8>>, length: usize, count: usize) -> FileSystemResult<Vec<String>> {
+    let mut strings = Vec::<String>::new();
+    for _ in 0..count {
+        let mut string = vec![0u8; length];
+        data.read_exact(&mut string)?;
+        strings.push(text_decoder.decode_mapped_string(string));
+    }
+
+    Ok(strings)
+}
+
+fn print_layout(data: &[u8], mapping: RomMapping, tables: &[(&str, &Vec<Entry>)]) {
+    println!("ROM layout: {} KiB, {:?}{}.", data.len() / 1024, mapping, if mapping == RomMapping::ExHiRom { ", expanded" } else { "" });
+    if mapping != RomMapping::ExHiRom {
+        return;
+    }
+
+    for (name, entries) in tables {
+        let expanded = entries.iter().filter(|entry| entry.address >= UNEXPANDED_ROM_SIZE).count();
+        println!("  {}: {} entries, {} in expanded ROM.", name, entries.len(), expanded);
+    }
+}
+
+// The larger of the original table size and the size needed for the largest index used, as long as
+// the pointer table still fits before the next table and the end of its bank.
+fn table_count(data: &[u8], pointers_address: usize, original_count: usize, used_count: usize, table_addresses: &[usize]) -> usize {
+    if used_count <= original_count {
+        return original_count;
+    }
+
+    let bank_end = (pointers_address | 0xFFFF) + 1;
+    let table_end = table_addresses.iter()
+        .copied()
+        .filter(|address| *address > pointers_address)
+        .fold(bank_end.min(data.len()), usize::min);
+    let max_count = (table_end - pointers_address) / 3;
+    if used_count > max_count {
+        println!("Table at 0x{:06X} would need {} entries, but only {} fit.", pointers_address, used_count, max_count);
+        return max_count;
+    }
+
+    println!("Table at 0x{:06X} was enlarged from {} to {} entries.", pointers_address, original_count, used_count);
+    used_count
+}
+
 fn calculate_entry_sizes(entries: &mut Vec<Entry>, end_address: usize) {
     for j in 0..entries.len() {
         let start = entries[j].address;
-        let mut found_end = end_address;
+
+        // Entries that were moved past the original data end at their bank end at most.
+        let mut found_end = if start < end_address { end_address } else { (start | 0xFFFF) + 1 };
         for i in 0..entries.len() {
             if entries[i].address > start && entries[i].address < found_end {
                 found_end = entries[i].address;
@@ -536,8 +802,9 @@ fn calculate_ordered_entry_sizes(entries: &mut Vec<Entry>, end_address: usize) {
     }
 }
 
-fn get_entries(data: &Vec<u8>, pointers_address: usize, pointer_count: usize, last_entry_end_address: usize) -> Vec<Entry> {
+fn get_entries(data: &Vec<u8>, mapping: RomMapping, pointers_address: usize, pointer_count: usize, last_entry_end_address: usize) -> Vec<Entry> {
     let mut entries = Vec::<Entry>::with_capacity(pointer_count);
+    let mut invalid_count = 0;
 
     let mut pointer_data = get_bytes_cursor(&data, pointers_address, pointer_count * 3);
     for _ in 0..pointer_count {
@@ -548,14 +815,24 @@ fn get_entries(data: &Vec<u8>, pointers_address: usize, pointer_count: usize, la
                 address: 0,
                 length: 0,
             });
+        } else if let Some(address) = mapping.to_offset(bank << 16 | pointer, data.len()) {
+            entries.push(Entry {
+                address,
+                length: 0,
+            });
         } else {
+            invalid_count += 1;
             entries.push(Entry {
-                address: 0x10000 * (bank - 0xC0) + pointer,
+                address: 0,
                 length: 0,
             });
         }
     }
 
+    if invalid_count > 0 {
+        println!("Table at 0x{:06X} has {} pointers outside of the ROM.", pointers_address, invalid_count);
+    }
+
     calculate_entry_sizes(&mut entries, last_entry_end_address);
 
     entries
@@ -597,3 +874,169 @@ fn get_relative_entries(data: &Vec<u8>, pointers_address: usize, pointer_count:
 
     entries
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::collections::BTreeSet;
+    use super::*;
+    use crate::GameMode;
+    use crate::filesystem::filesystem::FileSystem;
+    use crate::scene_script::scene_script_decoder;
+    use crate::scene_script::scene_script_ops::Op;
+    use crate::util::lz_compress::lz_compress;
+    use crate::util::lz_decompress::lz_small_bit_width;
+    use crate::text_processor::{page_lines, TextProcessor};
+
+    #[test]
+    fn rom_mapping() {
+        let data = vec![0u8; 0x600000];
+        assert_eq!(RomMapping::detect(&data), RomMapping::ExHiRom);
+        assert_eq!(RomMapping::detect(&data[..0x400000]), RomMapping::HiRom);
+
+        assert_eq!(RomMapping::HiRom.to_offset(0xC12345, 0x400000), Some(0x012345));
+        assert_eq!(RomMapping::HiRom.to_offset(0x412345, 0x400000), None);
+        assert_eq!(RomMapping::ExHiRom.to_offset(0x412345, 0x600000), Some(0x412345));
+        assert_eq!(RomMapping::ExHiRom.to_offset(0x612345, 0x600000), None);
+        assert_eq!(RomMapping::ExHiRom.to_offset(0x012345, 0x600000), None);
+
+        for offset in [0x012345, 0x3FFFFF, 0x400000, 0x5ABCDE] {
+            let address = RomMapping::ExHiRom.to_address(offset);
+            assert_eq!(RomMapping::ExHiRom.to_offset(address, 0x600000), Some(offset));
+        }
+    }
+
+    #[test]
+    fn tech_names_in_dialogue() {
+
+        // Names padded with spaces, in the 8x8 font's character mapping.
+        let mut data = Vec::new();
+        for name in ["Cyclone", "Slash", "Lightning"] {
+            let mut bytes: Vec<u8> = name.bytes()
+                .map(|char| match char {
+                    b'A'..=b'Z' => char - b'A' + 0xA0,
+                    _ => char - b'a' + 0xBA,
+                })
+                .collect();
+            bytes.resize(11, 0xFF);
+            data.extend(bytes);
+        }
+
+        let names = read_mapped_strings(&TextDecoder::new(), &mut Cursor::new(data), 11, 3).unwrap();
+        assert_eq!(names, vec!["Cyclone", "Slash", "Lightning"]);
+
+        let mut text_processor = TextProcessor::new();
+        text_processor.set_tech_names(names);
+        let (pages, warnings) = text_processor.process_dialog_text_checked("Use <NAME_TEC>01</NAME_TEC>!", 0, String::new());
+        assert_eq!(page_lines(&pages[0]), vec!["Use Slash!".to_string()]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn enlarged_table_count() {
+        let data = vec![0u8; 0x600000];
+        assert_eq!(table_count(&data, 0x361E00, 202, 100, &SCENE_POINTER_TABLES), 202);
+        assert_eq!(table_count(&data, 0x361E00, 202, 240, &SCENE_POINTER_TABLES), 240);
+
+        // Tables stop at the next table, or at the end of the bank for the last one.
+        assert_eq!(table_count(&data, 0x361E00, 202, 300, &SCENE_POINTER_TABLES), 256);
+        assert_eq!(table_count(&data, 0x3621C0, 19, 40, &SCENE_POINTER_TABLES), 32);
+        assert_eq!(table_count(&data, 0x3CF9F0, 513, 1000, &SCENE_POINTER_TABLES), 517);
+    }
+
+    // Set CTVIEWER_SNES_ROM to the path of a North American ROM to recompress all of its
+    // compressed blocks.
+    #[test]
+    fn recompress_rom_blocks() {
+        let Some(rom_path) = env::var_os("CTVIEWER_SNES_ROM") else {
+            return;
+        };
+        let backend = FileSystemBackendSnes::new(Path::new(&rom_path)).unwrap();
+
+        // Sprite graphics from index 7 onward are compressed.
+        let tables = [
+            &backend.world_map_tile_entries,
+            &backend.world_map_props_entries,
+            &backend.world_tileset_entries,
+            &backend.world_tileset12_assembly_entries,
+            &backend.world_tileset3_assembly_entries,
+            &backend.world_music_entries,
+            &backend.world_exit_entries,
+            &backend.world_sprite_entries,
+            &backend.world_palette_entries,
+            &backend.world_script_entries,
+            &backend.scene_map_entries,
+            &backend.scene_tileset_entries,
+            &backend.scene_tileset12_assembly_entries,
+            &backend.scene_tileset3_assembly_entries,
+            &backend.scene_script_entries,
+        ];
+        let mut addresses: Vec<usize> = tables.iter()
+            .flat_map(|entries| entries.iter().map(|entry| entry.address))
+            .chain(backend.sprite_entries[7..].iter().map(|entry| entry.address))
+            .filter(|address| *address != 0)
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+
+        let block_count = addresses.len();
+        let mut compressed_size = 0;
+        for address in addresses {
+            let data = backend.get_bytes_lz(address).unwrap();
+            let small_bit_width = lz_small_bit_width(&backend.data, address);
+            let compressed = lz_compress(&data, small_bit_width).unwrap();
+
+            let mut decompressed = vec![0u8; 0x10000];
+            let length = lz_decompress(&compressed, &mut decompressed, 0);
+            assert_eq!(&decompressed[..length], &data[..], "Block at 0x{:06X}", address);
+
+            compressed_size += compressed.len();
+        }
+
+        println!("Recompressed {} blocks to {} bytes.", block_count, compressed_size);
+    }
+
+    // Set CTVIEWER_SNES_ROM to the path of a North American ROM to encode every string in the
+    // textbox string tables that scene scripts use, and decode them again.
+    #[test]
+    fn text_encode_round_trip() {
+        let Some(rom_path) = env::var_os("CTVIEWER_SNES_ROM") else {
+            return;
+        };
+
+        let backend = FileSystemBackendSnes::new(Path::new(&rom_path)).unwrap();
+        let encoder = TextEncoder::new(&backend.text_decoder);
+        let fs = FileSystem::new(Box::new(FileSystemBackendSnes::new(Path::new(&rom_path)).unwrap()), GameMode::Snes);
+
+        let mut script_indices = BTreeSet::new();
+        for scene_index in 0..SCENE_COUNT {
+            script_indices.insert(fs.read_scene_script_index(scene_index).unwrap());
+        }
+
+        let mut addresses = BTreeSet::new();
+        for script_index in script_indices {
+            let script = fs.read_scene_script(script_index).unwrap();
+            let mut data = Cursor::new(script.get_data().clone());
+            while data.position() < data.get_ref().len() as u64 {
+                let op_byte = data.read_u8().unwrap();
+                match scene_script_decoder::op_decode_opcode(op_byte, &mut data, fs.mode) {
+                    Ok(Op::TextSetTable { address }) => { addresses.insert(address); },
+                    Ok(_) => {},
+                    Err(_) => break,
+                }
+            }
+        }
+        assert!(!addresses.is_empty());
+
+        for address in addresses {
+            for offset in backend.textbox_string_offsets(address).unwrap() {
+                let mut data = backend.get_bytes_cursor(offset, MAX_TEXTBOX_STRING_LEN).unwrap();
+                let text = backend.text_decoder.decode_huffman_string(&mut data);
+                let encoded = encoder.encode(&text).unwrap();
+
+                assert_eq!(backend.text_decoder.decode_huffman_string(&mut Cursor::new(encoded.clone())), text, "0x{:06X}", offset);
+                assert!(encoded.len() as u64 <= data.position(), "0x{:06X} grew from {} to {} bytes", offset, data.position(), encoded.len());
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use std::io;
+
+/// Errors from reading game data. These describe what could not be read, so that a broken scene or
+/// world can be reported without taking down the viewer.
+#[derive(Debug)]
+pub enum FileSystemError {
+
+    // A file that is not present in the source data.
+    FileNotFound(String),
+
+    // An index past the end of one of the data tables.
+    IndexOutOfRange {
+        table: &'static str,
+        index: usize,
+        count: usize,
+    },
+
+    // Data that could not be parsed.
+    InvalidData(String),
+
+    Io(io::Error),
+}
+
+pub type FileSystemResult<T> = Result<T, FileSystemError>;
+
+impl fmt::Display for FileSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileSystemError::FileNotFound(path) => write!(f, "File {} not found", path),
+            FileSystemError::IndexOutOfRange { table, index, count } => write!(f, "Index {} is out of range for {} ({} entries)", index, table, count),
+            FileSystemError::InvalidData(message) => write!(f, "Invalid data: {}", message),
+            FileSystemError::Io(error) => write!(f, "Could not read data: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for FileSystemError {}
+
+impl From<io::Error> for FileSystemError {
+    fn from(error: io::Error) -> Self {
+        FileSystemError::Io(error)
+    }
+}
+
+impl From<FileSystemError> for io::Error {
+    fn from(error: FileSystemError) -> Self {
+        match error {
+            FileSystemError::Io(error) => error,
+            FileSystemError::FileNotFound(_) => io::Error::new(io::ErrorKind::NotFound, error),
+            _ => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// Returns an item from a data table, or an error naming the table if the index is out of range.
+pub fn table_get<'a, T>(items: &'a [T], index: usize, table: &'static str) -> FileSystemResult<&'a T> {
+    items.get(index).ok_or(FileSystemError::IndexOutOfRange {
+        table,
+        index,
+        count: items.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_index_out_of_range() {
+        let items = [1, 2, 3];
+        assert_eq!(*table_get(&items, 2, "items").unwrap(), 3);
+
+        let error = table_get(&items, 3, "items").unwrap_err();
+        assert!(matches!(error, FileSystemError::IndexOutOfRange { table: "items", index: 3, count: 3 }));
+        assert_eq!(error.to_string(), "Index 3 is out of range for items (3 entries)");
+    }
+}
@@ -0,0 +1,72 @@
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
+use crate::filesystem::filesystem::FileSystem;
+use crate::filesystem::text_decoder::{font_16_glyph_map, font_8_glyph_map};
+use crate::software_renderer::bitmap::Bitmap;
+use crate::software_renderer::bitmap_font::{BitmapFont, NativeFonts};
+
+impl FileSystem {
+
+    /// Reads the game's own fonts, if they are known for this data. Font data that does not look
+    /// like a font, such as from a ROM that moved it, is an error.
+    pub fn read_fonts(&self) -> FileSystemResult<Option<NativeFonts>> {
+        let Some(font_8_data) = self.backend.get_font_8_graphics()? else {
+            return Ok(None);
+        };
+        let Some((font_16_data, widths)) = self.backend.get_font_16_graphics()? else {
+            return Ok(None);
+        };
+
+        let height_8 = font_8_data.len() as u32 / 128;
+        let height_16 = font_16_data.len() as u32 / 128;
+        let small = BitmapFont::new(Bitmap::from_raw_data(128, height_8, font_8_data), 8, 8, vec![8; 256], font_8_glyph_map());
+        let regular = BitmapFont::new(Bitmap::from_raw_data(128, height_16, font_16_data), 8, 16, widths, font_16_glyph_map());
+
+        // Letters must have pixels and fit their glyph, and spaces must be empty.
+        let valid = (0..regular.glyph_count()).all(|glyph| regular.get_glyph_width(glyph) <= 8) &&
+            ["A", "z", "0"].iter().all(|text| regular.get_glyph_index(text).is_some_and(|glyph| regular.get_glyph_width(glyph) > 0 && regular.glyph_has_pixels(glyph))) &&
+            regular.get_glyph_index(" ").is_some_and(|glyph| !regular.glyph_has_pixels(glyph)) &&
+            ["A", "z", "0"].iter().all(|text| small.get_glyph_index(text).is_some_and(|glyph| small.glyph_has_pixels(glyph))) &&
+            small.get_glyph_index(" ").is_some_and(|glyph| !small.glyph_has_pixels(glyph));
+        if !valid {
+            return Err(FileSystemError::InvalidData("font data does not look like a font".to_string()));
+        }
+
+        Ok(Some(NativeFonts {
+            regular,
+            small,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::Path;
+    use crate::filesystem::backend_snes::FileSystemBackendSnes;
+    use crate::GameMode;
+    use super::*;
+
+    // Set CTVIEWER_SNES_ROM to the path of a North American ROM to read its fonts.
+    #[test]
+    fn native_font_widths() {
+        let Some(rom_path) = env::var_os("CTVIEWER_SNES_ROM") else {
+            return;
+        };
+        let backend = FileSystemBackendSnes::new(Path::new(&rom_path)).unwrap();
+        let fs = FileSystem::new(Box::new(backend), GameMode::Snes);
+        let fonts = fs.read_fonts().unwrap().unwrap();
+
+        let width = |text: &str| fonts.regular.get_glyph_width(fonts.regular.get_glyph_index(text).unwrap());
+        for text in ["A", "M", "W", "a", "i", "l", "m", "w", "0", "!", "?"] {
+            assert!((1..=8).contains(&width(text)), "{} is {} pixels wide", text, width(text));
+        }
+
+        // The dialogue font is proportional.
+        assert!(width("i") < width("m"));
+        assert!(width("l") < width("w"));
+        assert!(width("!") < width("M"));
+
+        // The small font is fixed width.
+        assert_eq!(fonts.small.get_glyph_width(fonts.small.get_glyph_index("i").unwrap()), 8);
+    }
+}
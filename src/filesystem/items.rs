@@ -0,0 +1,15 @@
+use crate::filesystem::error::FileSystemResult;
+use crate::filesystem::filesystem::FileSystem;
+use crate::party::items::ItemId;
+
+impl FileSystem {
+    /// Returns the items sold in a shop, or None if shop data is not available for this game data.
+    pub fn read_shop_items(&self, shop_index: usize) -> FileSystemResult<Option<Vec<ItemId>>> {
+        self.backend.get_shop_items(shop_index)
+    }
+
+    /// Returns the price of every item indexed by item id, or None if it is not available.
+    pub fn read_item_prices(&self) -> FileSystemResult<Option<Vec<u32>>> {
+        self.backend.get_item_prices()
+    }
+}
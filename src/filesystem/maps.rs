@@ -3,6 +3,7 @@ use std::io::Read;
 
 use byteorder::ReadBytesExt;
 
+use crate::filesystem::error::FileSystemResult;
 use crate::filesystem::filesystem::FileSystem;
 use crate::map::EffectFlags;
 use crate::map::LayerScrollMode;
@@ -34,8 +35,8 @@ struct SceneMapHeader {
 impl FileSystem {
 
     // Load a world map.
-    pub fn read_world_map(&self, index: usize, index_props: usize, index_music: usize, tileset_l12: &TileSet, tileset_l3: &TileSet) -> (WorldMap, Map) {
-        let mut data_map = self.backend.get_world_map_tile_data(index);
+    pub fn read_world_map(&self, index: usize, index_props: usize, index_music: usize, tileset_l12: &TileSet, tileset_l3: &TileSet) -> FileSystemResult<(WorldMap, Map)> {
+        let mut data_map = self.backend.get_world_map_tile_data(index)?;
 
         let mut layer_1 = MapLayer::new(192, 128);
         let mut layer_2 = MapLayer::new(192, 128);
@@ -54,8 +55,8 @@ impl FileSystem {
         }
 
         // Read 2x2 chip map tiles and assemble chips from them.
-        read_map_layer_tiles(&mut layer_1, &mut data_map, 0);
-        read_map_layer_tiles(&mut layer_2, &mut data_map, 256);
+        read_map_layer_tiles(&mut layer_1, &mut data_map, 0)?;
+        read_map_layer_tiles(&mut layer_2, &mut data_map, 256)?;
         layer_1.assemble_chips(&tileset_l12, 0, 0, layer_1.tile_width, layer_1.tile_height);
         layer_2.assemble_chips(&tileset_l12, 0, 0, layer_2.tile_width, layer_2.tile_height);
 
@@ -76,14 +77,14 @@ impl FileSystem {
 
         // Read tile properties. Each tile in the tileset has fixed properties associated with it.
         let mut chips: Vec<WorldChip> = vec![WorldChip::default(); layer_1.chips.len()];
-        let mut data_props = self.backend.get_world_map_tile_props_data(index_props);
-        read_world_tile_props(&mut data_props, &layer_2, &mut chips);
+        let mut data_props = self.backend.get_world_map_tile_props_data(index_props)?;
+        read_world_tile_props(&mut data_props, &layer_2, &mut chips)?;
 
         // Read music for each map chip.
         // Each byte contains 2 values that indicate what music should play when the player is on a given
         // tile. The music "tiles" are 8x16 in size. Here we expand them into 8x8 chips for the map to
         // make things easier in the rest of the code.
-        let music_data = self.read_world_music_data(index_music, layer_2.tile_width, layer_2.tile_height);
+        let music_data = self.read_world_music_data(index_music, layer_2.tile_width, layer_2.tile_height)?;
         let chip_width = layer_2.chip_width;
         for (index, music) in music_data.iter().enumerate() {
             let tile_x = (index * 2) as u32 % layer_2.tile_width;
@@ -105,7 +106,7 @@ impl FileSystem {
             chips[dest_index + 3 + chip_width as usize].music = value;
         }
 
-        (
+        Ok((
             WorldMap {
                 index,
                 width: layer_1.chip_width,
@@ -121,21 +122,21 @@ impl FileSystem {
                 layers: [layer_1, layer_2, layer_3],
                 layer_priorities: [3, 2, 2, 1],
             }
-        )
+        ))
     }
 
     // Read a scene map.
-    pub fn read_scene_map(&self, index: usize, tileset_l12: &TileSet, tileset_l3: &TileSet) -> (SceneMap, Map) {
-        let mut data = self.backend.get_scene_map_data(index);
+    pub fn read_scene_map(&self, index: usize, tileset_l12: &TileSet, tileset_l3: &TileSet) -> FileSystemResult<(SceneMap, Map)> {
+        let mut data = self.backend.get_scene_map_data(index)?;
 
         // Read layer data.
         let header = SceneMapHeader {
-            layer12_size: data.read_u8().unwrap(),
-            bits: data.read_u8().unwrap(),
-            scroll_l2: data.read_u8().unwrap(),
-            scroll_l3: data.read_u8().unwrap(),
-            screen_flags: data.read_u8().unwrap(),
-            effect_flags: data.read_u8().unwrap(),
+            layer12_size: data.read_u8()?,
+            bits: data.read_u8()?,
+            scroll_l2: data.read_u8()?,
+            scroll_l3: data.read_u8()?,
+            screen_flags: data.read_u8()?,
+            effect_flags: data.read_u8()?,
         };
         let scroll_bits = (header.bits & 0x70) >> 4;
 
@@ -143,26 +144,26 @@ impl FileSystem {
         let width_l1 = (((header.layer12_size >> 0) & 0x3) * 16 + 16) as u32;
         let height_l1 = (((header.layer12_size >> 2) & 0x3) * 16 + 16) as u32;
         let mut layer_1 = MapLayer::new(width_l1 * 2, height_l1 * 2);
-        read_map_layer_tiles(&mut layer_1, &mut data, 0);
+        read_map_layer_tiles(&mut layer_1, &mut data, 0)?;
 
         // Read layer 2 tiles.
         let width_l2 = (((header.layer12_size >> 4) & 0x3) * 16 + 16) as u32;
         let height_l2 = (((header.layer12_size >> 6) & 0x3) * 16 + 16) as u32;
         let mut layer_2 = MapLayer::new(width_l2 * 2, height_l2 * 2);
-        read_map_layer_tiles(&mut layer_2, &mut data, 0);
+        read_map_layer_tiles(&mut layer_2, &mut data, 0)?;
 
         // Read layer 3 tiles if the layer is enabled.
         let width_l3 = (((header.bits >> 0) & 0x3) * 16 + 16) as u32;
         let height_l3 = (((header.bits >> 2) & 0x3) * 16 + 16) as u32;
         let mut layer_3 = MapLayer::new(width_l3 * 2, height_l3 * 2);
         if (header.bits & 0x80) > 0 {
-            read_map_layer_tiles(&mut layer_3, &mut data, 0);
+            read_map_layer_tiles(&mut layer_3, &mut data, 0)?;
         }
 
         // Read tile properties.
         let scene_width = std::cmp::max(width_l1, width_l2);
         let scene_height = std::cmp::max(height_l1, height_l2);
-        let scene_tile_props = read_scene_map_tile_props(scene_width, scene_height, &mut data);
+        let scene_tile_props = read_scene_map_tile_props(scene_width, scene_height, &mut data)?;
 
         // Post-process map tiles.
         // If L*_TILE_ADD is set, the tiles refer to the upper bank (index + 256).
@@ -221,11 +222,11 @@ impl FileSystem {
         // Read some unknown layer priority data per map.
         // These are unique to the PC version, so might be related to how it renders maps, where it
         // might emulate SNES behaviour only to be able to render them efficiently.
-        let mut prio_data = self.backend.get_scene_layer_priorities(index);
+        let mut prio_data = self.backend.get_scene_layer_priorities(index)?;
         let mut layer_priorities = [0u8; 4];
-        prio_data.read_exact(&mut layer_priorities).unwrap();
+        prio_data.read_exact(&mut layer_priorities)?;
 
-        (
+        Ok((
             SceneMap {
                 index,
                 props: ScenePropLayer {
@@ -241,17 +242,17 @@ impl FileSystem {
                 layer_priorities,
                 layers: [layer_1, layer_2, layer_3],
             },
-        )
+        ))
     }
 
     // Read music data.
-    fn read_world_music_data(&self, index_music: usize, tile_width: u32, tile_height: u32) -> Vec<u8> {
-        let mut data = self.backend.get_world_music_data(index_music);
+    fn read_world_music_data(&self, index_music: usize, tile_width: u32, tile_height: u32) -> FileSystemResult<Vec<u8>> {
+        let mut data = self.backend.get_world_music_data(index_music)?;
         let len = ((tile_width * tile_height) / 2) as usize;
         let mut exact_data = vec![0u8; len];
-        data.read_exact(&mut exact_data).unwrap();
+        data.read_exact(&mut exact_data)?;
 
-        exact_data
+        Ok(exact_data)
     }
 }
 
@@ -271,19 +272,21 @@ fn decode_scene_layer_scroll_speed(bits: u8) -> (f64, f64) {
 }
 
 // Read raw map tile data.
-fn read_map_layer_tiles(layer: &mut MapLayer, data: &mut Cursor<Vec<u8>>, tile_offset: usize) {
+fn read_map_layer_tiles(layer: &mut MapLayer, data: &mut Cursor<Vec<u8>>, tile_offset: usize) -> FileSystemResult<()> {
     let mut tiles_raw = vec![0u8; layer.tiles.len()];
-    data.read_exact(&mut tiles_raw).unwrap();
+    data.read_exact(&mut tiles_raw)?;
 
     for (index, tile) in layer.tiles.iter_mut().enumerate() {
         *tile = tiles_raw[index] as usize + tile_offset;
     }
+
+    Ok(())
 }
 
 // Read tile properties for a world map.
-fn read_world_tile_props(data: &mut Cursor<Vec<u8>>, layer: &MapLayer, world_chips: &mut Vec<WorldChip>) {
+fn read_world_tile_props(data: &mut Cursor<Vec<u8>>, layer: &MapLayer, world_chips: &mut Vec<WorldChip>) -> FileSystemResult<()> {
     let mut props_raw = vec![0u8; 0x200];
-    data.read_exact(&mut props_raw).unwrap();
+    data.read_exact(&mut props_raw)?;
 
     for (index, map_tile) in layer.tiles.iter().enumerate() {
         let src_x = index as u32 % layer.tile_width;
@@ -325,10 +328,12 @@ fn read_world_tile_props(data: &mut Cursor<Vec<u8>>, layer: &MapLayer, world_chi
             }
         }
     }
+
+    Ok(())
 }
 
 // Read Scene map tile properties.
-fn read_scene_map_tile_props(width: u32, height: u32, data: &mut Cursor<Vec<u8>>) -> Vec<SceneTileProps> {
+fn read_scene_map_tile_props(width: u32, height: u32, data: &mut Cursor<Vec<u8>>) -> FileSystemResult<Vec<SceneTileProps>> {
 
     // Properties are stored with basic RLE compression. Each prop is 3 bytes.
     let mut tile_props = Vec::<SceneTileProps>::new();
@@ -343,7 +348,7 @@ fn read_scene_map_tile_props(width: u32, height: u32, data: &mut Cursor<Vec<u8>>
 
         // Duplicate n times.
         if props.flags.contains(SceneTileFlags::RLE_COMPRESSED) {
-            let repeat = data.read_u8().unwrap() as u32;
+            let repeat = data.read_u8()? as u32;
             let repeat = if repeat > 0 { repeat } else { 256 };
             for _ in 0..repeat {
                 tile_props.push(props.clone());
@@ -368,7 +373,7 @@ fn read_scene_map_tile_props(width: u32, height: u32, data: &mut Cursor<Vec<u8>>
         tile_props.truncate(count_expected);
     }
 
-    tile_props
+    Ok(tile_props)
 }
 
 // Parse 3 bytes worth of scene tile property data.
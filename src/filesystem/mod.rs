@@ -1,7 +1,13 @@
 pub mod filesystem;
 pub mod backend;
 pub mod backend_pc;
+pub mod backend_overlay;
 pub mod backend_snes;
+pub mod error;
+pub mod resourcesbin;
+pub mod rom_identify;
+pub mod rom_writer;
+pub mod rom_patch;
 
 mod maps;
 mod scene;
@@ -10,7 +16,9 @@ mod palette;
 mod sprite;
 mod world;
 mod strings;
-mod text_decoder;
-mod resourcesbin;
+mod items;
+pub mod text_decoder;
+mod text_encoder;
 mod ui;
+mod font;
 
@@ -5,6 +5,7 @@ use std::io::Seek;
 use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
 
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
 use crate::game_palette::GamePalette;
 use crate::scene::palette_anim::PaletteAnim;
@@ -16,20 +17,20 @@ use crate::software_renderer::palette::Palette;
 impl FileSystem {
 
     // Read a world tile and sprite palette.
-    pub fn read_world_palette(&self, index: usize) -> GamePalette {
-        GamePalette {
+    pub fn read_world_palette(&self, index: usize) -> FileSystemResult<GamePalette> {
+        Ok(GamePalette {
             index,
-            palette: self.backend.get_world_palette(index),
-        }
+            palette: self.backend.get_world_palette(index)?,
+        })
     }
 
     // Read a scene tile and sprite palette. Sprite colors are unused by the PC version. Instead,
     // the palettes in sprite BMP files are used.
-    pub fn read_scene_palette(&self, index: usize) -> GamePalette {
-        GamePalette {
+    pub fn read_scene_palette(&self, index: usize) -> FileSystemResult<GamePalette> {
+        Ok(GamePalette {
             index,
-            palette: self.backend.get_scene_palette(index),
-        }
+            palette: self.backend.get_scene_palette(index)?,
+        })
     }
 
     // Each world has palette data for tile and sprite palette animations.
@@ -38,25 +39,25 @@ impl FileSystem {
     // elsewhere or hardcoded somewhere.
     // The remaining palette data can be used by objects from world scripts. "Command04" in
     // Temporal Flux copies palette data to the current object's palette.
-    pub fn read_world_palette_anim_data(&self, palette_anim_index: usize) -> GamePalette {
-        let mut data = self.backend.get_world_palette_anim_data(palette_anim_index);
+    pub fn read_world_palette_anim_data(&self, palette_anim_index: usize) -> FileSystemResult<GamePalette> {
+        let mut data = self.backend.get_world_palette_anim_data(palette_anim_index)?;
 
         // Read all data as 2-byte colors.
         let count = data.get_ref().len() / 2;
         let mut colors = Vec::<Color>::new();
         for _ in 0..count {
-            colors.push(FileSystem::read_color(&mut data));
+            colors.push(FileSystem::read_color(&mut data)?);
         }
 
-        GamePalette {
+        Ok(GamePalette {
             index: palette_anim_index,
             palette: Palette::from_colors(&colors),
-        }
+        })
     }
 
     // Read a set of palette animations.
     // Palette animations change colors in an existing palette according to predefined methods.
-    pub fn read_palette_anim_set(&self, index: usize) -> PaletteAnimSet {
+    pub fn read_palette_anim_set(&self, index: usize) -> FileSystemResult<PaletteAnimSet> {
         let mut set = PaletteAnimSet {
             index,
             anims: Vec::new(),
@@ -66,22 +67,22 @@ impl FileSystem {
             mut address_data,
             mut anim_data,
             mut color_data,
-        ) = self.backend.get_scene_palette_anim_data();
+        ) = self.backend.get_scene_palette_anim_data()?;
 
         // Find the right address of the descriptor data for this set.
         let anim_count = address_data.get_ref().len() / 2;
         if index >= anim_count {
-            panic!("Palette animation index is out of bounds.");
+            return Err(FileSystemError::IndexOutOfRange { table: "palette animations", index, count: anim_count });
         }
-        address_data.seek(SeekFrom::Start((index * 2) as u64)).unwrap();
-        let offset = address_data.read_u16::<LittleEndian>().unwrap() as u64;
+        address_data.seek(SeekFrom::Start((index * 2) as u64))?;
+        let offset = address_data.read_u16::<LittleEndian>()? as u64;
 
-        anim_data.seek(SeekFrom::Start(offset)).unwrap();
+        anim_data.seek(SeekFrom::Start(offset))?;
         let mut anim_index = 0;
         loop {
 
             // Hitting a 0 indicates the end of this set.
-            let id = anim_data.read_u8().unwrap();
+            let id = anim_data.read_u8()?;
             if id == 0 {
                 break;
             }
@@ -107,9 +108,9 @@ impl FileSystem {
             let mut anim = PaletteAnim {
                 index: anim_index,
                 anim_type,
-                color_index: anim_data.read_u8().unwrap() as usize,
-                color_count: anim_data.read_u8().unwrap() as usize,
-                delay: (anim_data.read_u8().unwrap() as f64) * (1.0 / 60.0),
+                color_index: anim_data.read_u8()? as usize,
+                color_count: anim_data.read_u8()? as usize,
+                delay: (anim_data.read_u8()? as f64) * (1.0 / 60.0),
                 frames: Vec::new(),
                 colors: Vec::new(),
                 current_frame: 0,
@@ -121,7 +122,8 @@ impl FileSystem {
 
                 // Use the first 2 bytes of the SNES address as the address and map it into the
                 // PalAnimaData.dat file.
-                let address = anim_data.read_u16::<LittleEndian>().unwrap() as u64 - 0x7380;
+                let address = (anim_data.read_u16::<LittleEndian>()? as u64).checked_sub(0x7380)
+                    .ok_or_else(|| FileSystemError::InvalidData(format!("Palette animation {} in set {} has an invalid color address", anim_index, index)))?;
                 let _ = anim_data.read_u8();
 
                 // Read predefined frames or generate them if it is a linear sequence. Generating
@@ -131,7 +133,7 @@ impl FileSystem {
                 anim.frames = vec![0; frame_count as usize + 1];
                 if id & 0xF0 == 0x80 {
                     for value in anim.frames.iter_mut() {
-                        *value = anim_data.read_u8().unwrap() as usize;
+                        *value = anim_data.read_u8()? as usize;
                     }
                 } else {
                     for (index, value) in anim.frames.iter_mut().enumerate() {
@@ -148,9 +150,9 @@ impl FileSystem {
                 let total_color_count = (set_count + 1) * anim.color_count;
 
                 // Read all color set data for this sequence.
-                color_data.seek(SeekFrom::Start(address)).unwrap();
+                color_data.seek(SeekFrom::Start(address))?;
                 for _ in 0..total_color_count {
-                    anim.colors.push(FileSystem::read_color(&mut color_data));
+                    anim.colors.push(FileSystem::read_color(&mut color_data)?);
                 }
             }
 
@@ -158,12 +160,12 @@ impl FileSystem {
             anim_index += 1;
         }
 
-        set
+        Ok(set)
     }
 
     // Read an SNES 5 bits per component color value.
-    pub fn read_color(reader: &mut Cursor<Vec<u8>>) -> Color {
-        let data = reader.read_u16::<LittleEndian>().unwrap();
-        Palette::decode_snes_color(data)
+    pub fn read_color(reader: &mut Cursor<Vec<u8>>) -> FileSystemResult<Color> {
+        let data = reader.read_u16::<LittleEndian>()?;
+        Ok(Palette::decode_snes_color(data))
     }
 }
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::fs::{copy, read_dir, remove_file, rename, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Error, ErrorKind, Read, Seek, SeekFrom, Write};
 use std::path::Path;
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
 use flate2::bufread::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 
 const BASE_SEED: i32 = 0x19000000;
 const VAL1: i32 = 0x41C64E6D;
@@ -101,6 +103,28 @@ impl ResourcesBin {
         self.files.contains_key(path)
     }
 
+    /// Returns all file paths, sorted.
+    pub fn file_paths(&self) -> Vec<&String> {
+        let mut paths: Vec<&String> = self.files.keys().collect();
+        paths.sort();
+
+        paths
+    }
+
+    /// Returns the size of a file after decompression, without decompressing all of it.
+    pub fn file_size(&self, path: &str) -> std::io::Result<u64> {
+        let entry = self.files.get(path).ok_or_else(|| Error::new(ErrorKind::NotFound, format!("File not found: {}", path)))?;
+
+        let mut file = File::open(&self.bin_path)?;
+        file.seek(SeekFrom::Start(entry.offset))?;
+        let mut data = vec![0; 4];
+        file.read_exact(&mut data)?;
+
+        // The decompressed size precedes the compressed data.
+        let mut size = decode_as_cursor(&mut Cursor::new(data), 0, 4, entry.offset as i32);
+        Ok(size.read_u32::<BigEndian>()? as u64)
+    }
+
     pub fn file_get(&self, path: &str) -> Cursor<Vec<u8>> {
         let entry = self.files.get(path).expect(&format!("File not found: {}", path));
 
@@ -116,6 +140,180 @@ impl ResourcesBin {
         // Decode and decompress. Use the entry offset as the block seed.
         decode_gz(&mut data_cursor, 0, entry.size as usize, entry.offset as i32)
     }
+
+    /// Replaces the contents of a single file in the resources file on disk. The new data is
+    /// written over the old data if it fits, otherwise after the last file. The directory and
+    /// header are rewritten to match. The changes are made to a copy that then replaces the
+    /// original, so that a failure does not leave a damaged resources file behind.
+    pub fn replace_file(&mut self, path: &str, data: &[u8]) -> std::io::Result<()> {
+        let entry = self.files.get(path).ok_or_else(|| Error::new(ErrorKind::NotFound, format!("File not found: {}", path)))?;
+
+        // Files are encoded with their offset as the block seed, so moving one means encoding it again.
+        let mut offset = entry.offset;
+        let mut packed = encode_gz(data, offset)?;
+        if packed.len() as u64 > entry.size {
+            offset = self.files.values()
+                .filter(|other| other.path != path)
+                .map(|other| other.offset + other.size)
+                .fold(entry.offset, u64::max);
+            if offset != entry.offset {
+                packed = encode_gz(data, offset)?;
+            }
+        }
+
+        let mut temp_name = self.bin_path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = self.bin_path.with_file_name(temp_name);
+        let result = self.write_replaced(&temp_path, path, offset, packed.len() as u64, &packed)
+            .and_then(|_| rename(&temp_path, &self.bin_path));
+        if result.is_err() {
+            let _ = remove_file(&temp_path);
+            return result;
+        }
+
+        let entry = self.files.get_mut(path).unwrap();
+        entry.offset = offset;
+        entry.size = packed.len() as u64;
+
+        Ok(())
+    }
+
+    // Writes a copy of the resources file to temp_path, with the packed data of one file at a new offset.
+    fn write_replaced(&self, temp_path: &Path, path: &str, offset: u64, size: u64, packed: &[u8]) -> std::io::Result<()> {
+        copy(&self.bin_path, temp_path)?;
+
+        let mut file = OpenOptions::new().read(true).write(true).open(temp_path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(packed)?;
+
+        let entries: Vec<ResourceFile> = self.files.values()
+            .map(|entry| ResourceFile {
+                path: entry.path.clone(),
+                offset: if entry.path == path { offset } else { entry.offset },
+                size: if entry.path == path { size } else { entry.size },
+            })
+            .collect();
+
+        // Place the directory after the last file again.
+        let directory_offset = entries.iter().map(|entry| entry.offset + entry.size).max().unwrap_or(16);
+        let directory = encode_gz(&build_directory(entries.iter()), directory_offset)?;
+        file.seek(SeekFrom::Start(directory_offset))?;
+        file.write_all(&directory)?;
+
+        let file_length = directory_offset + directory.len() as u64;
+        file.set_len(file_length)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&build_header(file_length, directory_offset, directory.len() as u64)?)?;
+        file.sync_all()?;
+
+        Ok(())
+    }
+}
+
+/// Writes a new resources file containing the given files, with forward slashes in their paths.
+pub fn write_resources_bin(path: &Path, files: &[(String, Vec<u8>)]) -> std::io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+
+    // The header is written last, once the directory location is known.
+    file.write_all(&[0u8; 16])?;
+
+    let mut entries = Vec::new();
+    let mut offset = 16;
+    for (entry_path, data) in files {
+        let packed = encode_gz(data, offset)?;
+        file.write_all(&packed)?;
+
+        entries.push(ResourceFile {
+            path: entry_path.clone(),
+            offset,
+            size: packed.len() as u64,
+        });
+        offset += packed.len() as u64;
+    }
+
+    let directory = encode_gz(&build_directory(entries.iter()), offset)?;
+    file.write_all(&directory)?;
+
+    let file_length = offset + directory.len() as u64;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&build_header(file_length, offset, directory.len() as u64)?)?;
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Writes a new resources file from all files in a directory tree, such as one extracted from
+/// another resources file. Returns the number of files written.
+pub fn write_resources_bin_from_directory(source_path: &Path, path: &Path) -> std::io::Result<usize> {
+    let mut files = Vec::new();
+    collect_files(source_path, source_path, &mut files)?;
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+
+    write_resources_bin(path, &files)?;
+
+    Ok(files.len())
+}
+
+fn collect_files(root_path: &Path, path: &Path, files: &mut Vec<(String, Vec<u8>)>) -> std::io::Result<()> {
+    for dir_entry in read_dir(path)? {
+        let entry_path = dir_entry?.path();
+        if entry_path.is_dir() {
+            collect_files(root_path, &entry_path, files)?;
+            continue;
+        }
+
+        // Entry paths are relative to the root, with forward slashes.
+        let relative = entry_path.strip_prefix(root_path).unwrap();
+        let mut components = Vec::new();
+        for component in relative.components() {
+            let name = component.as_os_str().to_str()
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Path is not valid UTF-8: {}", entry_path.display())))?;
+            components.push(name);
+        }
+
+        let mut data = Vec::new();
+        File::open(&entry_path)?.read_to_end(&mut data)?;
+        files.push((components.join("/"), data));
+    }
+
+    Ok(())
+}
+
+fn build_header(file_length: u64, directory_offset: u64, directory_length: u64) -> std::io::Result<Vec<u8>> {
+    if file_length > u32::MAX as u64 {
+        return Err(Error::new(ErrorKind::InvalidData, "Resources file would be larger than 4 GiB"));
+    }
+
+    let mut header = Vec::with_capacity(16);
+    header.write_all(b"ARC1")?;
+    header.write_u32::<LittleEndian>(file_length as u32)?;
+    header.write_u32::<LittleEndian>(directory_offset as u32)?;
+    header.write_u32::<LittleEndian>(directory_length as u32)?;
+
+    Ok(encode(&header, 0))
+}
+
+// Entries sorted by path, followed by the null-terminated paths they point to.
+fn build_directory<'a>(files: impl Iterator<Item = &'a ResourceFile>) -> Vec<u8> {
+    let mut files: Vec<&ResourceFile> = files.collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut entries = Vec::new();
+    let mut paths = Vec::new();
+    let paths_offset = 4 + files.len() * 12;
+
+    entries.write_u32::<LittleEndian>(files.len() as u32).unwrap();
+    for file in files {
+        entries.write_u32::<LittleEndian>((paths_offset + paths.len()) as u32).unwrap();
+        entries.write_u32::<LittleEndian>(file.offset as u32).unwrap();
+        entries.write_u32::<LittleEndian>(file.size as u32).unwrap();
+
+        paths.extend_from_slice(file.path.as_bytes());
+        paths.push(0);
+    }
+    entries.extend_from_slice(&paths);
+
+    entries
 }
 
 fn decode_gz(data: &mut Cursor<Vec<u8>>, offset: u64, length: usize, block_seed: i32) -> Cursor<Vec<u8>> {
@@ -127,11 +325,29 @@ fn decode_gz(data: &mut Cursor<Vec<u8>>, offset: u64, length: usize, block_seed:
 
     // Read back just the earlier specified data length.
     let mut buf = vec![0u8; len];
-    gz_decoder.read(&mut buf).unwrap();
+    gz_decoder.read_exact(&mut buf).unwrap();
 
     Cursor::new(buf)
 }
 
+fn encode_gz(data: &[u8], offset: u64) -> std::io::Result<Vec<u8>> {
+    let mut gz_encoder = GzEncoder::new(Vec::new(), Compression::best());
+    gz_encoder.write_all(data)?;
+
+    // Prefix the compressed data with the uncompressed length, as decode_gz expects.
+    let mut output = Vec::new();
+    output.write_u32::<BigEndian>(data.len() as u32)?;
+    output.extend_from_slice(&gz_encoder.finish()?);
+
+    // Use the data offset as the block seed.
+    Ok(encode(&output, offset as i32))
+}
+
+// The encoding XORs the data with a seeded stream, so encoding is the same as decoding.
+fn encode(data: &[u8], block_seed: i32) -> Vec<u8> {
+    decode(&mut Cursor::new(data.to_vec()), 0, data.len(), block_seed)
+}
+
 fn decode_as_cursor(data: &mut Cursor<Vec<u8>>, offset: u64, length: usize, block_seed: i32) -> Cursor<Vec<u8>> {
     Cursor::new(decode(data, offset, length, block_seed))
 }
@@ -150,3 +366,190 @@ fn decode(data: &mut Cursor<Vec<u8>>, offset: u64, length: usize, block_seed: i3
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{create_dir_all, remove_dir_all};
+    use std::path::PathBuf;
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("ctviewer_{}_{}", name, std::process::id()));
+        if path.exists() {
+            remove_dir_all(&path).unwrap();
+        }
+        create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    fn test_files() -> Vec<(String, Vec<u8>)> {
+        let mut large = Vec::new();
+        for i in 0..100000u32 {
+            large.push((i.wrapping_mul(2654435761) >> 24) as u8);
+        }
+
+        vec![
+            ("Game/common/bankc6.bin".to_string(), large),
+            ("Game/field/Mapinfo/mapinfo_0.dat".to_string(), vec![1, 2, 3, 4, 5, 6, 7, 8]),
+            ("Game/field/empty.dat".to_string(), Vec::new()),
+            ("Localize/it/msg/w_map.txt".to_string(), "0,Zeal\r\n1,Guardia\r\n".as_bytes().to_vec()),
+        ]
+    }
+
+    fn assert_contents(resources: &ResourcesBin, files: &[(String, Vec<u8>)]) {
+        assert_eq!(resources.files.len(), files.len());
+        for (path, data) in files {
+            assert_eq!(&resources.file_get(path).into_inner(), data, "{}", path);
+            assert_eq!(resources.file_size(path).unwrap(), data.len() as u64, "{}", path);
+        }
+    }
+
+    #[test]
+    fn write_read_write_read() {
+        let dir = test_dir("resources_round_trip");
+        let files = test_files();
+
+        write_resources_bin(&dir.join("first.bin"), &files).unwrap();
+        let first = ResourcesBin::new(&dir.join("first.bin"));
+        assert_contents(&first, &files);
+
+        let read_files: Vec<(String, Vec<u8>)> = first.file_paths()
+            .into_iter()
+            .map(|path| (path.clone(), first.file_get(path).into_inner()))
+            .collect();
+        write_resources_bin(&dir.join("second.bin"), &read_files).unwrap();
+        assert_contents(&ResourcesBin::new(&dir.join("second.bin")), &files);
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    // Set CTVIEWER_RESOURCES_BIN to the path of the Steam resources.bin to round-trip all of it.
+    #[test]
+    fn steam_resources_round_trip() {
+        let Some(source_path) = env::var_os("CTVIEWER_RESOURCES_BIN") else {
+            return;
+        };
+
+        let source = ResourcesBin::new(Path::new(&source_path));
+        let files: Vec<(String, Vec<u8>)> = source.file_paths()
+            .into_iter()
+            .map(|path| (path.clone(), source.file_get(path).into_inner()))
+            .collect();
+
+        let dir = test_dir("resources_steam");
+        write_resources_bin(&dir.join("resources.bin"), &files).unwrap();
+        assert_contents(&ResourcesBin::new(&dir.join("resources.bin")), &files);
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replace_file() {
+        let dir = test_dir("resources_replace");
+        let path = dir.join("resources.bin");
+        let mut files = test_files();
+        write_resources_bin(&path, &files).unwrap();
+
+        // Smaller data is written over the old data.
+        let mut resources = ResourcesBin::new(&path);
+        let offset = resources.files["Game/common/bankc6.bin"].offset;
+        files[0].1 = vec![0xAA; 16];
+        resources.replace_file("Game/common/bankc6.bin", &files[0].1).unwrap();
+        assert_eq!(resources.files["Game/common/bankc6.bin"].offset, offset);
+        assert_contents(&ResourcesBin::new(&path), &files);
+
+        // Larger data is moved after the last file.
+        let offset = resources.files["Game/field/Mapinfo/mapinfo_0.dat"].offset;
+        files[1].1 = (0..5000u32).map(|i| (i * 7) as u8).collect();
+        resources.replace_file("Game/field/Mapinfo/mapinfo_0.dat", &files[1].1).unwrap();
+        assert!(resources.files["Game/field/Mapinfo/mapinfo_0.dat"].offset > offset);
+        assert_contents(&ResourcesBin::new(&path), &files);
+
+        assert!(resources.replace_file("Game/missing.dat", &[]).is_err());
+        assert!(!dir.join("resources.bin.tmp").exists());
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    // The 16 byte header is encoded with block seed 0 and holds the "ARC1" signature, the file
+    // length, the directory offset and the directory length as little-endian 32 bit values.
+    fn arc1_header(file_length: u32, directory_offset: u32, directory_length: u32) -> Vec<u8> {
+        let mut header = b"ARC1".to_vec();
+        header.extend_from_slice(&file_length.to_le_bytes());
+        header.extend_from_slice(&directory_offset.to_le_bytes());
+        header.extend_from_slice(&directory_length.to_le_bytes());
+
+        encode(&header, 0)
+    }
+
+    #[test]
+    fn arc1_header_layout() {
+        let dir = test_dir("resources_header");
+        let files = test_files();
+        write_resources_bin(&dir.join("written.bin"), &files).unwrap();
+
+        // The header of a written file matches the layout, with the directory after the last file.
+        let written = std::fs::read(dir.join("written.bin")).unwrap();
+        let resources = ResourcesBin::new(&dir.join("written.bin"));
+        let directory_offset = resources.files.values().map(|entry| entry.offset + entry.size).max().unwrap();
+        let expected = arc1_header(written.len() as u32, directory_offset as u32, (written.len() as u64 - directory_offset) as u32);
+        assert_eq!(&written[0..16], &expected[..]);
+
+        // A file assembled by hand from the layout can be read: header, one entry and a directory
+        // holding the entry count, the entry and its null-terminated path.
+        let data = vec![9, 8, 7, 6, 5];
+        let entry = encode_gz(&data, 16).unwrap();
+        let mut directory = Vec::new();
+        directory.write_u32::<LittleEndian>(1).unwrap();
+        directory.write_u32::<LittleEndian>(16).unwrap();
+        directory.write_u32::<LittleEndian>(16).unwrap();
+        directory.write_u32::<LittleEndian>(entry.len() as u32).unwrap();
+        directory.extend_from_slice(b"Game/test.dat\0");
+        let directory_offset = 16 + entry.len() as u32;
+        let directory = encode_gz(&directory, directory_offset as u64).unwrap();
+
+        let file_length = directory_offset + directory.len() as u32;
+        let mut assembled = arc1_header(file_length, directory_offset, directory.len() as u32);
+        assembled.extend_from_slice(&entry);
+        assembled.extend_from_slice(&directory);
+        File::create(dir.join("assembled.bin")).unwrap().write_all(&assembled).unwrap();
+
+        let resources = ResourcesBin::new(&dir.join("assembled.bin"));
+        assert_eq!(resources.file_paths(), vec!["Game/test.dat"]);
+        assert_eq!(resources.file_get("Game/test.dat").into_inner(), data);
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn file_size_missing() {
+        let dir = test_dir("resources_file_size");
+        write_resources_bin(&dir.join("resources.bin"), &test_files()).unwrap();
+
+        let resources = ResourcesBin::new(&dir.join("resources.bin"));
+        assert_eq!(resources.file_size("Game/field/empty.dat").unwrap(), 0);
+        let error = resources.file_size("Game/missing.dat").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_from_directory() {
+        let dir = test_dir("resources_directory");
+        let files = test_files();
+        for (path, data) in &files {
+            let file_path = dir.join("tree").join(path);
+            create_dir_all(file_path.parent().unwrap()).unwrap();
+            File::create(&file_path).unwrap().write_all(data).unwrap();
+        }
+
+        let count = write_resources_bin_from_directory(&dir.join("tree"), &dir.join("resources.bin")).unwrap();
+        assert_eq!(count, files.len());
+        assert_contents(&ResourcesBin::new(&dir.join("resources.bin")), &files);
+
+        remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+use crate::filesystem::backend_snes::RomMapping;
+
+const ROM_TITLE: &[u8] = b"CHRONO TRIGGER       ";
+const MIN_ROM_SIZE: usize = 4 * 1024 * 1024;
+const MAX_ROM_SIZE: usize = 8 * 1024 * 1024;
+
+// Offsets inside the internal ROM header.
+const HEADER_TITLE_OFFSET: usize = 0x00;
+const HEADER_DESTINATION_OFFSET: usize = 0x19;
+const HEADER_CHECKSUM_COMPLEMENT_OFFSET: usize = 0x1C;
+const HEADER_CHECKSUM_OFFSET: usize = 0x1E;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RomStatus {
+    Good,
+
+    // A dump with damaged or missing data, which cannot be used.
+    BadDump,
+
+    // A modified ROM, which is supported as far as it keeps the original data layout.
+    Hack,
+
+    // A release that cannot be used.
+    Unsupported,
+}
+
+#[derive(Debug)]
+pub struct KnownRom {
+    pub crc32: u32,
+    pub name: &'static str,
+    pub status: RomStatus,
+
+    // Shown to the user for anything but good dumps.
+    pub message: &'static str,
+}
+
+// CRC32 checksums of known dumps, without a file format header. ROMs that are not listed are
+// modified ROMs or bad dumps, which the header checksum helps tell apart.
+const KNOWN_ROMS: [KnownRom; 2] = [
+    KnownRom {
+        crc32: 0x2D206BF7,
+        name: "Chrono Trigger (USA)",
+        status: RomStatus::Good,
+        message: "",
+    },
+    KnownRom {
+        crc32: 0x4D014C20,
+        name: "Chrono Trigger (Japan)",
+        status: RomStatus::Unsupported,
+        message: "The Japanese release is not supported, only the North American release is.",
+    },
+];
+
+/// What a ROM was identified as.
+#[derive(Debug)]
+pub struct RomInfo {
+    pub name: String,
+    pub mapping: RomMapping,
+    pub crc32: u32,
+    pub known: Option<&'static KnownRom>,
+    pub checksum_valid: bool,
+}
+
+/// Identifies ROM data without its file format header by comparing its checksum against known
+/// dumps. ROMs that are not known are identified by their internal header. Returns why the ROM
+/// cannot be used if it is not supported.
+pub fn identify_rom(data: &[u8]) -> Result<RomInfo, String> {
+    identify_rom_with(data, &KNOWN_ROMS)
+}
+
+fn identify_rom_with(data: &[u8], known_roms: &'static [KnownRom]) -> Result<RomInfo, String> {
+    if data.len() < MIN_ROM_SIZE {
+        return Err(format!("ROM is {} bytes, less than the 4 MiB of Chrono Trigger. It is a different game or an incomplete dump.", data.len()));
+    }
+    if data.len() > MAX_ROM_SIZE {
+        return Err(format!("ROM is {} bytes, more than the largest possible (ExHiROM) size of 8 MiB.", data.len()));
+    }
+
+    let mapping = RomMapping::detect(data);
+    let header = &data[mapping.header_address()..mapping.header_address() + 0x20];
+
+    let mut crc = flate2::Crc::new();
+    crc.update(data);
+    let crc32 = crc.sum();
+
+    let stored_checksum = read_u16(header, HEADER_CHECKSUM_OFFSET);
+    let checksum_valid = stored_checksum ^ read_u16(header, HEADER_CHECKSUM_COMPLEMENT_OFFSET) == 0xFFFF && stored_checksum == calculate_checksum(data);
+
+    if let Some(known) = known_roms.iter().find(|known| known.crc32 == crc32) {
+        match known.status {
+            RomStatus::BadDump => return Err(format!("ROM is a known bad dump, {}. {}", known.name, known.message)),
+            RomStatus::Unsupported => return Err(format!("ROM is {}. {}", known.name, known.message)),
+            RomStatus::Good | RomStatus::Hack => {},
+        }
+
+        return Ok(RomInfo {
+            name: known.name.to_string(),
+            mapping,
+            crc32,
+            known: Some(known),
+            checksum_valid,
+        });
+    }
+
+    let title = &header[HEADER_TITLE_OFFSET..HEADER_TITLE_OFFSET + ROM_TITLE.len()];
+    if title != ROM_TITLE {
+        let printable: String = title.iter().map(|&c| if c.is_ascii_graphic() || c == b' ' { c as char } else { '.' }).collect();
+        return Err(format!("ROM header title is \"{}\" instead of \"CHRONO TRIGGER\". It is a different game, or an interleaved or bad dump.", printable.trim_end()));
+    }
+
+    match header[HEADER_DESTINATION_OFFSET] {
+        0x00 => return Err("ROM is the Japanese release, which is not supported. Only the North American release is.".to_string()),
+        0x01 => {},
+        destination => return Err(format!("ROM header destination code is 0x{:02X}. Only the North American release is supported.", destination)),
+    };
+
+    Ok(RomInfo {
+        name: "Unknown North American ROM".to_string(),
+        mapping,
+        crc32,
+        known: None,
+        checksum_valid,
+    })
+}
+
+/// Prints what a ROM was identified as, and warns about what might not work with it.
+pub fn print_rom_info(info: &RomInfo, size: usize) {
+    println!("Identified {}, {:?}, {} KiB, CRC32 {:08X}.", info.name, info.mapping, size / 1024, info.crc32);
+
+    match info.known {
+        Some(known) if known.status == RomStatus::Hack => println!("This is a modified ROM: {}", known.message),
+        Some(_) => {},
+        None if info.checksum_valid => println!("The ROM is not a known dump but its header checksum is valid, it is likely a modified ROM."),
+        None => println!("The ROM is not a known dump and its header checksum is invalid, it is a modified ROM or a bad dump."),
+    }
+}
+
+/// The sum of all bytes of the ROM. Data past the largest power of two is mirrored to fill up the
+/// same size again.
+pub fn calculate_checksum(data: &[u8]) -> u16 {
+    let mut size = 1;
+    while size * 2 <= data.len() {
+        size *= 2;
+    }
+
+    let mut sum = data[..size].iter().fold(0u32, |sum, value| sum.wrapping_add(*value as u32));
+    let rest = &data[size..];
+    if !rest.is_empty() {
+        for i in 0..size {
+            sum = sum.wrapping_add(rest[i % rest.len()] as u32);
+        }
+    }
+
+    sum as u16
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    data[offset] as u16 | (data[offset + 1] as u16) << 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_rom(size: usize, title: &[u8], destination: u8) -> Vec<u8> {
+        let mut data = vec![0u8; size];
+        let header_address = RomMapping::detect(&data).header_address();
+        data[header_address..header_address + title.len()].copy_from_slice(title);
+        data[header_address + HEADER_DESTINATION_OFFSET] = destination;
+
+        data
+    }
+
+    #[test]
+    fn identify_unknown_roms() {
+        let info = identify_rom(&test_rom(MIN_ROM_SIZE, ROM_TITLE, 0x01)).unwrap();
+        assert_eq!(info.mapping, RomMapping::HiRom);
+        assert!(info.known.is_none());
+        assert!(!info.checksum_valid);
+
+        let info = identify_rom(&test_rom(0x600000, ROM_TITLE, 0x01)).unwrap();
+        assert_eq!(info.mapping, RomMapping::ExHiRom);
+    }
+
+    #[test]
+    fn unsupported_roms() {
+        assert!(identify_rom(&vec![0u8; 0x100000]).unwrap_err().contains("less than the 4 MiB"));
+        assert!(identify_rom(&vec![0u8; MAX_ROM_SIZE + 0x8000]).unwrap_err().contains("more than"));
+        assert!(identify_rom(&test_rom(MIN_ROM_SIZE, b"SECRET OF MANA       ", 0x01)).unwrap_err().contains("\"SECRET OF MANA\""));
+        assert!(identify_rom(&test_rom(MIN_ROM_SIZE, ROM_TITLE, 0x02)).unwrap_err().contains("0x02"));
+        assert!(identify_rom(&test_rom(MIN_ROM_SIZE, ROM_TITLE, 0x00)).unwrap_err().contains("Japanese"));
+    }
+
+    // Identifies a test ROM against a table that lists it with a status.
+    fn identify_known(status: RomStatus) -> Result<RomInfo, String> {
+        let data = test_rom(MIN_ROM_SIZE, ROM_TITLE, 0x01);
+        let mut crc = flate2::Crc::new();
+        crc.update(&data);
+        let known_roms = Box::leak(Box::new([KnownRom {
+            crc32: crc.sum(),
+            name: "Test ROM",
+            status,
+            message: "Test message.",
+        }]));
+
+        identify_rom_with(&data, known_roms)
+    }
+
+    #[test]
+    fn known_good_dump() {
+        let info = identify_known(RomStatus::Good).unwrap();
+        assert_eq!(info.name, "Test ROM");
+        assert_eq!(info.known.unwrap().status, RomStatus::Good);
+    }
+
+    #[test]
+    fn known_bad_dump() {
+        let error = identify_known(RomStatus::BadDump).unwrap_err();
+        assert!(error.contains("known bad dump, Test ROM"));
+        assert!(error.contains("Test message."));
+    }
+
+    #[test]
+    fn known_hack() {
+        let info = identify_known(RomStatus::Hack).unwrap();
+        assert_eq!(info.known.unwrap().status, RomStatus::Hack);
+        assert_eq!(info.known.unwrap().message, "Test message.");
+    }
+
+    #[test]
+    fn known_unsupported() {
+        let error = identify_known(RomStatus::Unsupported).unwrap_err();
+        assert!(error.contains("Test ROM"));
+        assert!(error.contains("Test message."));
+    }
+
+    #[test]
+    fn checksum() {
+
+        // The part above the largest power of two is mirrored.
+        assert_eq!(calculate_checksum(&[1, 1, 1, 1, 2, 3]), 4 + 2 + 3 + 2 + 3);
+    }
+}
@@ -0,0 +1,385 @@
+use std::fs;
+use std::path::Path;
+use flate2::Crc;
+
+const FILE_HEADER_SIZE: usize = 0x200;
+
+/// Applies IPS and BPS patch files in order to ROM data without its file format header. The
+/// file format header is only used to apply patches made for headered ROMs.
+pub fn apply_patch_files(file_header: &[u8], data: Vec<u8>, patch_paths: &[String], ips_headered: bool) -> Result<Vec<u8>, String> {
+    let mut data = data;
+    for (index, patch_path) in patch_paths.iter().enumerate() {
+        let patch = fs::read(Path::new(patch_path)).map_err(|e| format!("Could not read patch {}: {}", patch_path, e))?;
+
+        println!("Applying patch {} of {}: {}.", index + 1, patch_paths.len(), patch_path);
+        data = apply_patch(file_header, &data, &patch, ips_headered).map_err(|e| format!("Could not apply patch {}: {}", patch_path, e))?;
+    }
+
+    Ok(data)
+}
+
+/// Applies an IPS or BPS patch to ROM data, detected by the patch signature. IPS patches have no
+/// checksums to tell whether they were made for a headered ROM, so ips_headered says so instead.
+pub fn apply_patch(file_header: &[u8], data: &[u8], patch: &[u8], ips_headered: bool) -> Result<Vec<u8>, String> {
+    if patch.starts_with(b"PATCH") {
+
+        // ROMs without a copier header are patched as if they had an empty one.
+        if ips_headered {
+            let empty_header = [0u8; FILE_HEADER_SIZE];
+            let file_header = if file_header.len() == FILE_HEADER_SIZE { file_header } else { &empty_header };
+            return strip_header(apply_ips(&[file_header, data].concat(), patch)?);
+        }
+
+        let patched = apply_ips(data, patch)?;
+        if patched.len() > data.len() && patched.len() <= data.len() + FILE_HEADER_SIZE {
+            println!("Patch makes the ROM slightly larger, it may be made for a headered ROM. Use --ips-headered if so.");
+        }
+        return Ok(patched);
+    }
+    if !patch.starts_with(b"BPS1") {
+        return Err("Unknown patch format, only IPS and BPS patches are supported".to_string());
+    }
+
+    // Patches for headered ROMs include the header in their checksums.
+    if file_header.len() == FILE_HEADER_SIZE && patch.len() >= 12 {
+        let source_crc = read_u32(patch, patch.len() - 12);
+        if crc32(data) != source_crc && crc32(&[file_header, data].concat()) == source_crc {
+            println!("Patch is for a headered ROM.");
+            return strip_header(apply_bps(&[file_header, data].concat(), patch)?);
+        }
+    }
+
+    apply_bps(data, patch)
+}
+
+fn strip_header(patched: Vec<u8>) -> Result<Vec<u8>, String> {
+    if patched.len() < FILE_HEADER_SIZE {
+        return Err("Patched ROM is smaller than its header".to_string());
+    }
+
+    Ok(patched[FILE_HEADER_SIZE..].to_vec())
+}
+
+// A list of records that each replace bytes at an offset, optionally followed by a size to
+// truncate to.
+fn apply_ips(data: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = data.to_vec();
+    let mut pos = 5;
+    let mut record_count = 0;
+    loop {
+        let record = patch.get(pos..pos + 3).ok_or("IPS patch ends without an EOF marker")?;
+        if record == b"EOF" {
+            pos += 3;
+            break;
+        }
+
+        let offset = read_u24_be(patch, pos)?;
+        let size = read_u16_be(patch, pos + 3)?;
+        pos += 5;
+
+        // A size of 0 repeats a single byte.
+        let bytes = if size == 0 {
+            let count = read_u16_be(patch, pos)?;
+            let value = *patch.get(pos + 2).ok_or("IPS patch record is truncated")?;
+            pos += 3;
+            vec![value; count]
+        } else {
+            let bytes = patch.get(pos..pos + size).ok_or("IPS patch record is truncated")?.to_vec();
+            pos += size;
+            bytes
+        };
+
+        if output.len() < offset + bytes.len() {
+            output.resize(offset + bytes.len(), 0);
+        }
+        output[offset..offset + bytes.len()].copy_from_slice(&bytes);
+        record_count += 1;
+    }
+
+    if patch.len() >= pos + 3 {
+        output.truncate(read_u24_be(patch, pos)?);
+    }
+
+    println!("Applied {} IPS records.", record_count);
+
+    Ok(output)
+}
+
+// Actions that copy from the source, the patch or earlier output, with checksums for all three.
+fn apply_bps(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, String> {
+    if patch.len() < 4 + 12 {
+        return Err("BPS patch is truncated".to_string());
+    }
+
+    let footer = patch.len() - 12;
+    let source_crc = read_u32(patch, footer);
+    let target_crc = read_u32(patch, footer + 4);
+    let patch_crc = read_u32(patch, footer + 8);
+
+    if crc32(&patch[..footer + 8]) != patch_crc {
+        return Err("BPS patch checksum mismatch, the patch file is damaged".to_string());
+    }
+    if crc32(source) != source_crc {
+        return Err(format!("ROM checksum {:08X} does not match the checksum {:08X} the BPS patch expects", crc32(source), source_crc));
+    }
+
+    let mut pos = 4;
+    let source_size = read_varint(patch, &mut pos)?;
+    let target_size = read_varint(patch, &mut pos)?;
+    let metadata_size = read_varint(patch, &mut pos)?;
+    pos += metadata_size;
+    if source_size != source.len() {
+        return Err(format!("ROM size {} does not match the size {} the BPS patch expects", source.len(), source_size));
+    }
+
+    let mut target = Vec::with_capacity(target_size);
+    let mut source_relative = 0isize;
+    let mut target_relative = 0isize;
+    while pos < footer {
+        let action = read_varint(patch, &mut pos)?;
+        let length = (action >> 2) + 1;
+        if target.len() + length > target_size {
+            return Err("BPS patch writes past the end of the ROM".to_string());
+        }
+
+        match action & 3 {
+            // Source read.
+            0 => {
+                let start = target.len();
+                target.extend_from_slice(source.get(start..start + length).ok_or("BPS patch reads past the end of the ROM")?);
+            },
+
+            // Target read.
+            1 => {
+                target.extend_from_slice(patch.get(pos..pos + length).filter(|_| pos + length <= footer).ok_or("BPS patch is truncated")?);
+                pos += length;
+            },
+
+            // Source copy.
+            2 => {
+                source_relative += read_signed_varint(patch, &mut pos)?;
+                let start = usize::try_from(source_relative).map_err(|_| "BPS patch copies from before the start of the ROM")?;
+                target.extend_from_slice(source.get(start..start + length).ok_or("BPS patch copies past the end of the ROM")?);
+                source_relative += length as isize;
+            },
+
+            // Target copy, which can overlap the bytes being written.
+            _ => {
+                target_relative += read_signed_varint(patch, &mut pos)?;
+                let start = usize::try_from(target_relative).map_err(|_| "BPS patch copies from before the start of the output")?;
+                if start >= target.len() {
+                    return Err("BPS patch copies from past the end of the output".to_string());
+                }
+                for index in start..start + length {
+                    target.push(target[index]);
+                }
+                target_relative += length as isize;
+            },
+        }
+    }
+
+    if target.len() != target_size {
+        return Err(format!("BPS patch output is {} bytes instead of {}", target.len(), target_size));
+    }
+    if crc32(&target) != target_crc {
+        return Err("BPS patch output checksum mismatch".to_string());
+    }
+
+    println!("Applied BPS patch, checksums match.");
+
+    Ok(target)
+}
+
+fn read_varint(patch: &[u8], pos: &mut usize) -> Result<usize, String> {
+    let mut value: usize = 0;
+    let mut shift: usize = 1;
+    loop {
+        let byte = *patch.get(*pos).ok_or("BPS patch is truncated")? as usize;
+        *pos += 1;
+
+        value = (byte & 0x7F).checked_mul(shift).and_then(|x| x.checked_add(value)).ok_or("BPS patch number is too large")?;
+        if byte & 0x80 != 0 {
+            return Ok(value);
+        }
+        shift = shift.checked_mul(0x80).ok_or("BPS patch number is too large")?;
+        value = value.checked_add(shift).ok_or("BPS patch number is too large")?;
+    }
+}
+
+// The lowest bit is the sign.
+fn read_signed_varint(patch: &[u8], pos: &mut usize) -> Result<isize, String> {
+    let value = read_varint(patch, pos)?;
+    let magnitude = (value >> 1) as isize;
+
+    Ok(if value & 1 != 0 { -magnitude } else { magnitude })
+}
+
+fn read_u24_be(patch: &[u8], pos: usize) -> Result<usize, String> {
+    let bytes = patch.get(pos..pos + 3).ok_or("IPS patch is truncated")?;
+    Ok((bytes[0] as usize) << 16 | (bytes[1] as usize) << 8 | bytes[2] as usize)
+}
+
+fn read_u16_be(patch: &[u8], pos: usize) -> Result<usize, String> {
+    let bytes = patch.get(pos..pos + 2).ok_or("IPS patch is truncated")?;
+    Ok((bytes[0] as usize) << 8 | bytes[1] as usize)
+}
+
+fn read_u32(patch: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([patch[pos], patch[pos + 1], patch[pos + 2], patch[pos + 3]])
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+    crc.update(data);
+    crc.sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_varint(output: &mut Vec<u8>, value: usize) {
+        let mut value = value;
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                output.push(byte | 0x80);
+                return;
+            }
+            output.push(byte);
+            value -= 1;
+        }
+    }
+
+    fn write_signed_varint(output: &mut Vec<u8>, value: isize) {
+        write_varint(output, (value.unsigned_abs() << 1) | (value < 0) as usize);
+    }
+
+    fn build_bps(source: &[u8], target: &[u8], actions: &[u8]) -> Vec<u8> {
+        let mut patch = b"BPS1".to_vec();
+        write_varint(&mut patch, source.len());
+        write_varint(&mut patch, target.len());
+        write_varint(&mut patch, 0);
+        patch.extend_from_slice(actions);
+        patch.extend_from_slice(&crc32(source).to_le_bytes());
+        patch.extend_from_slice(&crc32(target).to_le_bytes());
+        patch.extend_from_slice(&crc32(&patch).to_le_bytes());
+
+        patch
+    }
+
+    // Source read, target read, source copy and an overlapping target copy.
+    fn test_bps(source: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut actions = Vec::new();
+        write_varint(&mut actions, (2 - 1) << 2);
+        write_varint(&mut actions, ((3 - 1) << 2) | 1);
+        actions.extend_from_slice(&[7, 8, 9]);
+        write_varint(&mut actions, ((2 - 1) << 2) | 2);
+        write_signed_varint(&mut actions, 4);
+        write_varint(&mut actions, ((4 - 1) << 2) | 3);
+        write_signed_varint(&mut actions, 6);
+
+        let mut target = source[0..2].to_vec();
+        target.extend_from_slice(&[7, 8, 9]);
+        target.extend_from_slice(&source[4..6]);
+        target.extend_from_slice(&[source[5], source[5], source[5], source[5]]);
+
+        (build_bps(source, &target, &actions), target)
+    }
+
+    #[test]
+    fn ips() {
+        let mut patch = b"PATCH".to_vec();
+        patch.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x02, 0xAA, 0xBB]);
+        patch.extend_from_slice(&[0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x03, 0xCC]);
+        patch.extend_from_slice(b"EOF");
+
+        let patched = apply_patch(&[], &[0; 8], &patch, false).unwrap();
+        assert_eq!(patched, vec![0, 0xAA, 0xBB, 0, 0, 0, 0xCC, 0xCC, 0xCC]);
+
+        // Truncate to 4 bytes.
+        patch.extend_from_slice(&[0x00, 0x00, 0x04]);
+        assert_eq!(apply_patch(&[], &[0; 8], &patch, false).unwrap(), vec![0, 0xAA, 0xBB, 0]);
+
+        assert!(apply_patch(&[], &[0; 8], b"PATCH\x00\x00\x01\x00\x05\xAA", false).is_err());
+    }
+
+    #[test]
+    fn ips_for_headered_rom() {
+        let file_header = vec![0x55; FILE_HEADER_SIZE];
+        let data = vec![0; 8];
+
+        // The first record writes to the header, the second is at ROM offset 2.
+        let mut patch = b"PATCH".to_vec();
+        patch.extend_from_slice(&[0x00, 0x00, 0x10, 0x00, 0x01, 0x66]);
+        patch.extend_from_slice(&[0x00, 0x02, 0x02, 0x00, 0x02, 0xAA, 0xBB]);
+        patch.extend_from_slice(b"EOF");
+        assert_eq!(apply_patch(&file_header, &data, &patch, true).unwrap(), vec![0, 0, 0xAA, 0xBB, 0, 0, 0, 0]);
+
+        // ROM files without a header are patched the same way.
+        assert_eq!(apply_patch(&[], &data, &patch, true).unwrap(), vec![0, 0, 0xAA, 0xBB, 0, 0, 0, 0]);
+
+        // Without the flag the same offsets are taken as they are.
+        let patched = apply_patch(&file_header, &data, &patch, false).unwrap();
+        assert_eq!(patched[0x10], 0x66);
+        assert_eq!(&patched[0x202..0x204], &[0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn ips_headered_first_record_past_header() {
+        let file_header = vec![0x55; FILE_HEADER_SIZE];
+        let data = vec![0; 8];
+
+        // A patch for a headered ROM that does not touch the header still needs its offsets moved.
+        let mut patch = b"PATCH".to_vec();
+        patch.extend_from_slice(&[0x00, 0x02, 0x05, 0x00, 0x01, 0xAA]);
+        patch.extend_from_slice(b"EOF");
+        assert_eq!(apply_patch(&file_header, &data, &patch, true).unwrap(), vec![0, 0, 0, 0, 0, 0xAA, 0, 0]);
+    }
+
+    #[test]
+    fn ips_headerless_low_offsets() {
+        let file_header = vec![0x55; FILE_HEADER_SIZE];
+        let data = vec![0; 8];
+
+        // A patch for an unheadered ROM that changes its first bytes is applied as it is, even if
+        // the ROM file has a header.
+        let mut patch = b"PATCH".to_vec();
+        patch.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x02, 0xAA, 0xBB]);
+        patch.extend_from_slice(b"EOF");
+        assert_eq!(apply_patch(&file_header, &data, &patch, false).unwrap(), vec![0, 0xAA, 0xBB, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn bps() {
+        let source: Vec<u8> = (0..8).collect();
+        let (patch, target) = test_bps(&source);
+        assert_eq!(apply_patch(&[], &source, &patch, false).unwrap(), target);
+
+        // Wrong source data.
+        assert!(apply_patch(&[], &[0; 8], &patch, false).is_err());
+
+        // Damaged patch.
+        let mut damaged = patch.clone();
+        damaged[8] ^= 1;
+        assert!(apply_patch(&[], &source, &damaged, false).is_err());
+    }
+
+    #[test]
+    fn bps_for_headered_rom() {
+        let file_header = vec![0x55; FILE_HEADER_SIZE];
+        let data: Vec<u8> = (0..8).collect();
+
+        let headered = [&file_header[..], &data[..]].concat();
+        let mut actions = Vec::new();
+        write_varint(&mut actions, (FILE_HEADER_SIZE + 4 - 1) << 2);
+        write_varint(&mut actions, ((4 - 1) << 2) | 1);
+        actions.extend_from_slice(&[1, 1, 1, 1]);
+        let mut target = headered[..FILE_HEADER_SIZE + 4].to_vec();
+        target.extend_from_slice(&[1, 1, 1, 1]);
+        let patch = build_bps(&headered, &target, &actions);
+
+        assert_eq!(apply_patch(&file_header, &data, &patch, false).unwrap(), vec![0, 1, 2, 3, 1, 1, 1, 1]);
+    }
+}
@@ -0,0 +1,448 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use crate::filesystem::backend_snes::{read_rom, RomMapping};
+use crate::filesystem::rom_identify::{calculate_checksum, identify_rom};
+use crate::util::lz_compress::lz_compress;
+use crate::util::lz_decompress::{lz_compressed_size, lz_decompress, lz_small_bit_width};
+
+// Checksum complement and checksum offset in the internal ROM header.
+const HEADER_CHECKSUM_OFFSET: usize = 0x1C;
+
+// Scene exits are stored uncompressed and in scene order, with 16 bit pointers into their bank.
+const SCENE_EXIT_POINTERS_ADDRESS: usize = 0x250000;
+const SCENE_EXIT_COUNT: usize = 512;
+const SCENE_EXIT_END_ADDRESS: usize = 0x251A44;
+
+/// Compressed assets that are referenced from a table of 24 bit pointers.
+#[derive(Clone, Copy, Debug)]
+pub enum RomAsset {
+    SceneMap,
+    SceneTileset12Assembly,
+    SceneTileset3Assembly,
+    SceneScript,
+}
+
+impl RomAsset {
+    // Pointer table address and entry count.
+    fn pointer_table(&self) -> (usize, usize) {
+        match self {
+            RomAsset::SceneMap => (0x361E00, 202),
+            RomAsset::SceneTileset12Assembly => (0x362100, 64),
+            RomAsset::SceneTileset3Assembly => (0x3621C0, 19),
+            RomAsset::SceneScript => (0x3CF9F0, 513),
+        }
+    }
+}
+
+/// Replaces assets in a North American SNES ROM image. Data that no longer fits in its original
+/// location is moved to free space. Space is freed by assets that are moved or shrink, and more
+/// can be added for unused areas of the ROM.
+///
+/// The pointer tables are at fixed addresses of the unexpanded North American ROM, so other
+/// releases and expanded ROMs are refused.
+pub struct SnesRomWriter {
+    file_header: Vec<u8>,
+    data: Vec<u8>,
+    free_space: Vec<(usize, usize)>,
+    scene_exits_end: usize,
+}
+
+impl SnesRomWriter {
+    pub fn new(rom_path: &Path) -> Result<SnesRomWriter, String> {
+        let (file_header, data) = read_rom(rom_path)?;
+
+        Self::from_data(file_header, data)
+    }
+
+    /// Uses ROM data that was already read, with its file format header separated.
+    pub fn from_data(file_header: Vec<u8>, data: Vec<u8>) -> Result<SnesRomWriter, String> {
+        let info = identify_rom(&data)?;
+        if info.mapping != RomMapping::HiRom {
+            return Err(format!("{} is an expanded ROM, only the unexpanded North American ROM can be written.", info.name));
+        }
+
+        Ok(SnesRomWriter {
+            file_header,
+            data,
+            free_space: Vec::new(),
+            scene_exits_end: SCENE_EXIT_END_ADDRESS,
+        })
+    }
+
+    /// Marks a range of ROM addresses as unused, so that data can be moved there.
+    pub fn add_free_space(&mut self, start: usize, end: usize) -> Result<(), Error> {
+        if start >= end || end > self.data.len() {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Free space 0x{:06X}-0x{:06X} is not inside the ROM", start, end)));
+        }
+        self.free(start, end);
+
+        Ok(())
+    }
+
+    /// Compresses data and replaces a compressed asset with it.
+    pub fn replace_compressed(&mut self, asset: RomAsset, index: usize, data: &[u8]) -> Result<(), Error> {
+        let (table_address, count) = asset.pointer_table();
+        if index >= count {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("{:?} index {} is out of range, there are {}", asset, index, count)));
+        }
+
+        let addresses: Vec<Option<usize>> = (0..count).map(|i| self.read_pointer(table_address + i * 3)).collect();
+        let old_address = addresses[index];
+
+        // Keep the bit width of the original data.
+        let small_bit_width = old_address.is_some_and(|address| lz_small_bit_width(&self.data, address));
+        let compressed = lz_compress(data, small_bit_width)?;
+
+        // Data that other entries also point to cannot be overwritten.
+        let shared = old_address.is_some() && addresses.iter().enumerate().any(|(i, address)| i != index && *address == old_address);
+
+        let address = match old_address {
+            Some(old_address) if !shared => {
+                let old_size = lz_compressed_size(&self.data, old_address);
+                if compressed.len() <= old_size {
+                    self.free(old_address + compressed.len(), old_address + old_size);
+                    old_address
+                } else {
+                    self.free(old_address, old_address + old_size);
+                    self.allocate(compressed.len())?
+                }
+            },
+            _ => self.allocate(compressed.len())?,
+        };
+
+        self.data[address..address + compressed.len()].copy_from_slice(&compressed);
+        self.write_pointer(table_address + index * 3, address);
+
+        // Make sure the game will read back the same data.
+        let mut decompressed = vec![0u8; 0x10000];
+        let length = lz_decompress(&self.data, &mut decompressed, address);
+        if decompressed[..length] != *data {
+            return Err(Error::new(ErrorKind::InvalidData, format!("{:?} {} does not decompress to the same data", asset, index)));
+        }
+
+        if address != old_address.unwrap_or(0) {
+            println!("Moved {:?} {} to 0x{:06X}.", asset, index, address);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the exit data of a scene. All exit data is stored in order, so the following
+    /// scenes' exits are moved and their pointers updated.
+    pub fn replace_scene_exits(&mut self, scene_index: usize, data: &[u8]) -> Result<(), Error> {
+        if scene_index >= SCENE_EXIT_COUNT {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Scene index {} is out of range", scene_index)));
+        }
+
+        let bank_start = SCENE_EXIT_POINTERS_ADDRESS & 0xFF0000;
+        let addresses: Vec<usize> = (0..SCENE_EXIT_COUNT)
+            .map(|i| bank_start + self.read_u16(SCENE_EXIT_POINTERS_ADDRESS + i * 2))
+            .collect();
+
+        let mut exits: Vec<Vec<u8>> = Vec::with_capacity(SCENE_EXIT_COUNT);
+        for (i, start) in addresses.iter().enumerate() {
+            let end = addresses.get(i + 1).copied().unwrap_or(self.scene_exits_end);
+            exits.push(self.data[*start..end].to_vec());
+        }
+        exits[scene_index] = data.to_vec();
+
+        // Grow into free space directly after the exit data, or free what is no longer used.
+        let start = addresses[0];
+        let end = start + exits.iter().map(|exit| exit.len()).sum::<usize>();
+        if end > bank_start + 0x10000 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Scene exit data does not fit in its bank"));
+        }
+        if end > self.scene_exits_end {
+            self.claim(self.scene_exits_end, end)?;
+        } else {
+            self.free(end, self.scene_exits_end);
+        }
+        self.scene_exits_end = end;
+
+        let mut address = start;
+        for (i, exit) in exits.iter().enumerate() {
+            self.write_u16(SCENE_EXIT_POINTERS_ADDRESS + i * 2, address - bank_start);
+            self.data[address..address + exit.len()].copy_from_slice(exit);
+            address += exit.len();
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the strings of a textbox string table, given the ROM offset and length of each of
+    /// the original strings. The table holds 16 bit pointers to strings in its bank. The new
+    /// strings are stored one after another in the space of the original strings.
+    pub fn replace_string_table(&mut self, address: usize, original: &[(usize, usize)], strings: &[Vec<u8>]) -> Result<(), Error> {
+        if strings.len() != original.len() {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("String table at 0x{:06X} has {} strings, {} were given", address, original.len(), strings.len())));
+        }
+        let (Some(start), Some(end)) = (original.iter().map(|(offset, _)| *offset).min(), original.iter().map(|(offset, len)| offset + len).max()) else {
+            return Ok(());
+        };
+
+        let size = strings.iter().map(|string| string.len()).sum::<usize>();
+        if start + size > end {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Strings of {} bytes do not fit in the {} bytes of the original strings", size, end - start)));
+        }
+
+        let bank_start = address & 0xFF0000;
+        let mut offset = start;
+        for (i, string) in strings.iter().enumerate() {
+            self.write_u16(address + i * 2, offset - bank_start);
+            self.data[offset..offset + string.len()].copy_from_slice(string);
+            offset += string.len();
+        }
+
+        Ok(())
+    }
+
+    /// Updates the header checksum and writes the ROM image, with its file format header if the
+    /// source ROM had one.
+    pub fn write(&mut self, path: &Path) -> Result<(), Error> {
+        self.update_checksum();
+
+        let mut output = self.file_header.clone();
+        output.extend_from_slice(&self.data);
+        fs::write(path, output)
+    }
+
+    fn update_checksum(&mut self) {
+
+        // Sum as if the checksum is 0 and its complement 0xFFFF, which add up the same as any valid pair.
+        let checksum_address = RomMapping::detect(&self.data).header_address() + HEADER_CHECKSUM_OFFSET;
+        self.data[checksum_address..checksum_address + 4].copy_from_slice(&[0xFF, 0xFF, 0x00, 0x00]);
+        let checksum = calculate_checksum(&self.data);
+
+        self.write_u16(checksum_address, (checksum ^ 0xFFFF) as usize);
+        self.write_u16(checksum_address + 2, checksum as usize);
+    }
+
+    fn read_pointer(&self, address: usize) -> Option<usize> {
+        let pointer = self.read_u16(address);
+        let bank = self.data[address + 2] as usize;
+        if bank == 0 && pointer == 0 {
+            return None;
+        }
+
+        RomMapping::detect(&self.data).to_offset(bank << 16 | pointer, self.data.len())
+    }
+
+    fn write_pointer(&mut self, address: usize, pointer: usize) {
+        let rom_address = RomMapping::detect(&self.data).to_address(pointer);
+        self.write_u16(address, rom_address & 0xFFFF);
+        self.data[address + 2] = (rom_address >> 16) as u8;
+    }
+
+    fn read_u16(&self, address: usize) -> usize {
+        self.data[address] as usize | (self.data[address + 1] as usize) << 8
+    }
+
+    fn write_u16(&mut self, address: usize, value: usize) {
+        self.data[address] = value as u8;
+        self.data[address + 1] = (value >> 8) as u8;
+    }
+
+    // Adds a range to the free space, merging it with adjacent ranges.
+    fn free(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+
+        self.free_space.push((start, end));
+        self.free_space.sort();
+
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(self.free_space.len());
+        for (start, end) in self.free_space.drain(..) {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        self.free_space = merged;
+    }
+
+    // Takes the first free range that fits, without crossing a bank boundary.
+    fn allocate(&mut self, size: usize) -> Result<usize, Error> {
+        for (start, end) in self.free_space.clone() {
+            let mut address = start;
+            if (address & 0xFFFF) + size > 0x10000 {
+                address = (address & 0xFF0000) + 0x10000;
+            }
+            if address + size <= end {
+                self.claim(address, address + size)?;
+                return Ok(address);
+            }
+        }
+
+        Err(Error::new(ErrorKind::OutOfMemory, format!("Not enough free space in the ROM for {} bytes", size)))
+    }
+
+    // Removes a range from the free space, it must be entirely free.
+    fn claim(&mut self, start: usize, end: usize) -> Result<(), Error> {
+        let Some(index) = self.free_space.iter().position(|range| range.0 <= start && end <= range.1) else {
+            return Err(Error::new(ErrorKind::OutOfMemory, format!("0x{:06X}-0x{:06X} is not free space", start, end)));
+        };
+
+        let (range_start, range_end) = self.free_space.remove(index);
+        if end < range_end {
+            self.free_space.insert(index, (end, range_end));
+        }
+        if range_start < start {
+            self.free_space.insert(index, (range_start, start));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use crate::filesystem::backend::FileSystemBackendTrait;
+    use crate::filesystem::backend_snes::FileSystemBackendSnes;
+    use super::*;
+
+    // An empty 4 MiB ROM with the first scene map compressed at 0x100000.
+    fn test_writer() -> SnesRomWriter {
+        let mut writer = SnesRomWriter {
+            file_header: Vec::new(),
+            data: vec![0; 0x400000],
+            free_space: Vec::new(),
+            scene_exits_end: SCENE_EXIT_END_ADDRESS,
+        };
+
+        let compressed = lz_compress(&[1, 2, 3, 4], false).unwrap();
+        writer.data[0x100000..0x100000 + compressed.len()].copy_from_slice(&compressed);
+        writer.write_pointer(0x361E00, 0x100000);
+
+        // Scene exits all start empty, right after their pointers.
+        for i in 0..SCENE_EXIT_COUNT {
+            writer.write_u16(SCENE_EXIT_POINTERS_ADDRESS + i * 2, 0x400);
+        }
+        writer.scene_exits_end = 0x250400;
+
+        writer
+    }
+
+    fn decompress_at(writer: &SnesRomWriter, address: usize) -> Vec<u8> {
+        let mut data = vec![0u8; 0x10000];
+        let length = lz_decompress(&writer.data, &mut data, address);
+        data.truncate(length);
+
+        data
+    }
+
+    #[test]
+    fn replace_compressed_in_place_and_moved() {
+        let mut writer = test_writer();
+
+        // Shrinking data stays in place.
+        writer.replace_compressed(RomAsset::SceneMap, 0, &[9; 4]).unwrap();
+        assert_eq!(writer.read_pointer(0x361E00), Some(0x100000));
+        assert_eq!(decompress_at(&writer, 0x100000), vec![9; 4]);
+
+        // Growing data needs free space.
+        let data: Vec<u8> = (0..200).map(|i| (i * 37) as u8).collect();
+        assert!(writer.replace_compressed(RomAsset::SceneMap, 0, &data).is_err());
+
+        // Data is not placed across a bank boundary.
+        writer.add_free_space(0x20FFF0, 0x211000).unwrap();
+        writer.replace_compressed(RomAsset::SceneMap, 0, &data).unwrap();
+        assert_eq!(writer.read_pointer(0x361E00), Some(0x210000));
+        assert_eq!(decompress_at(&writer, 0x210000), data);
+
+        assert!(writer.replace_compressed(RomAsset::SceneMap, 202, &data).is_err());
+    }
+
+    #[test]
+    fn supported_roms() {
+        let rom = |size: usize, destination: u8| {
+            let mut data = vec![0u8; size];
+            let header_address = RomMapping::detect(&data).header_address();
+            data[header_address..header_address + 21].copy_from_slice(b"CHRONO TRIGGER       ");
+            data[header_address + 0x19] = destination;
+            data
+        };
+
+        assert!(SnesRomWriter::from_data(Vec::new(), rom(0x400000, 0x01)).is_ok());
+        assert!(SnesRomWriter::from_data(Vec::new(), rom(0x400000, 0x00)).err().unwrap().contains("Japanese release"));
+        assert!(SnesRomWriter::from_data(Vec::new(), rom(0x600000, 0x01)).err().unwrap().contains("expanded ROM"));
+    }
+
+    #[test]
+    fn replace_scene_exits() {
+        let mut writer = test_writer();
+        writer.add_free_space(0x250400, 0x250500).unwrap();
+
+        writer.replace_scene_exits(1, &[1; 7]).unwrap();
+        writer.replace_scene_exits(0, &[2; 7]).unwrap();
+        assert_eq!(writer.read_u16(SCENE_EXIT_POINTERS_ADDRESS), 0x400);
+        assert_eq!(writer.read_u16(SCENE_EXIT_POINTERS_ADDRESS + 2), 0x407);
+        assert_eq!(writer.read_u16(SCENE_EXIT_POINTERS_ADDRESS + 4), 0x40E);
+        assert_eq!(&writer.data[0x250400..0x25040E], &[2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1]);
+
+        // Shrinking frees the space again.
+        writer.replace_scene_exits(0, &[]).unwrap();
+        assert_eq!(writer.scene_exits_end, 0x250407);
+        assert_eq!(writer.free_space, vec![(0x250407, 0x250500)]);
+
+        assert!(writer.replace_scene_exits(2, &[0; 0x100]).is_err());
+    }
+
+    #[test]
+    fn replace_string_table() {
+        let mut writer = test_writer();
+
+        // Two strings of 4 and 3 bytes after a table at 0x1F0000.
+        writer.write_u16(0x1F0000, 0x10);
+        writer.write_u16(0x1F0002, 0x14);
+        let original = [(0x1F0010, 4), (0x1F0014, 3)];
+
+        writer.replace_string_table(0x1F0000, &original, &[vec![1, 1, 0], vec![2, 2, 2, 0]]).unwrap();
+        assert_eq!(writer.read_u16(0x1F0000), 0x10);
+        assert_eq!(writer.read_u16(0x1F0002), 0x13);
+        assert_eq!(&writer.data[0x1F0010..0x1F0017], &[1, 1, 0, 2, 2, 2, 0]);
+
+        assert!(writer.replace_string_table(0x1F0000, &original, &[vec![1, 1, 1, 1, 0], vec![2, 2, 2, 0]]).is_err());
+        assert!(writer.replace_string_table(0x1F0000, &original, &[vec![0]]).is_err());
+    }
+
+    #[test]
+    fn checksum() {
+        let mut writer = test_writer();
+        writer.update_checksum();
+
+        let checksum = writer.read_u16(0xFFDC + 2);
+        let complement = writer.read_u16(0xFFDC);
+        assert_eq!(checksum ^ complement, 0xFFFF);
+        assert_eq!(checksum as u16, calculate_checksum(&writer.data));
+    }
+
+    // Set CTVIEWER_SNES_ROM to the path of a North American ROM to write a copy with the same
+    // scene data and read it back.
+    #[test]
+    fn rewrite_rom_scene_data() {
+        let Some(rom_path) = env::var_os("CTVIEWER_SNES_ROM") else {
+            return;
+        };
+        let backend = FileSystemBackendSnes::new(Path::new(&rom_path)).unwrap();
+        let mut writer = SnesRomWriter::new(Path::new(&rom_path)).unwrap();
+
+        let map = backend.get_scene_map_data(1).unwrap().into_inner();
+        let script = backend.get_scene_script_data(1).unwrap().into_inner();
+        let exits = backend.get_scene_exit_data(1).unwrap().into_inner();
+        writer.replace_compressed(RomAsset::SceneMap, 1, &map).unwrap();
+        writer.replace_compressed(RomAsset::SceneScript, 1, &script).unwrap();
+        writer.replace_scene_exits(1, &exits).unwrap();
+
+        let output_path = env::temp_dir().join(format!("ctviewer_rom_{}.sfc", std::process::id()));
+        writer.write(&output_path).unwrap();
+        let written = FileSystemBackendSnes::new(&output_path).unwrap();
+        assert_eq!(written.get_scene_map_data(1).unwrap().into_inner(), map);
+        assert_eq!(written.get_scene_script_data(1).unwrap().into_inner(), script);
+        assert_eq!(written.get_scene_exit_data(1).unwrap().into_inner(), exits);
+
+        fs::remove_file(&output_path).unwrap();
+    }
+}
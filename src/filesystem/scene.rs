@@ -3,6 +3,7 @@ use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
 use crate::destination::Destination;
 use crate::facing::Facing;
+use crate::filesystem::error::{table_get, FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
 use crate::GameMode;
 use crate::scene::scene::{Scene, ScrollMask};
@@ -44,47 +45,51 @@ struct SceneHeader {
 
 impl FileSystem {
 
-    // Read a scene and related data.
-    pub fn read_scene(&self, scene_index: usize) -> Scene {
-        let mut data = self.backend.get_scene_header_data(scene_index);
+    // Read the index of the script a scene runs.
+    pub fn read_scene_script_index(&self, scene_index: usize) -> FileSystemResult<usize> {
+        Ok(self.read_scene_header(scene_index)?.script_index)
+    }
+
+    fn read_scene_header(&self, scene_index: usize) -> FileSystemResult<SceneHeader> {
+        let mut data = self.backend.get_scene_header_data(scene_index)?;
 
         let mut header = match self.mode {
             GameMode::Snes => SceneHeader {
-                music_index: data.read_u8().unwrap() as usize,
-                tileset_l12_index: data.read_u8().unwrap() as usize,
+                music_index: data.read_u8()? as usize,
+                tileset_l12_index: data.read_u8()? as usize,
                 tileset_l12_assembly_index: 0,
-                tileset_l3_index: data.read_u8().unwrap() as usize,
+                tileset_l3_index: data.read_u8()? as usize,
                 tileset_l3_assembly_index: 0,
-                palette_index: data.read_u8().unwrap() as usize,
-                map_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                chip_anims_index: data.read_u8().unwrap() as usize,
-                palette_anims_index: data.read_u8().unwrap() as usize,
-                script_index: data.read_u16::<LittleEndian>().unwrap() as usize,
+                palette_index: data.read_u8()? as usize,
+                map_index: data.read_u16::<LittleEndian>()? as usize,
+                chip_anims_index: data.read_u8()? as usize,
+                palette_anims_index: data.read_u8()? as usize,
+                script_index: data.read_u16::<LittleEndian>()? as usize,
                 unknown: 0,
                 scroll_mask: ScrollMask {
-                    left: data.read_u8().unwrap() as isize,
-                    top: data.read_u8().unwrap() as isize,
-                    right: data.read_u8().unwrap() as isize,
-                    bottom: data.read_u8().unwrap() as isize,
+                    left: data.read_u8()? as isize,
+                    top: data.read_u8()? as isize,
+                    right: data.read_u8()? as isize,
+                    bottom: data.read_u8()? as isize,
                 },
             },
             GameMode::Pc => SceneHeader {
-                music_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                tileset_l12_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                tileset_l12_assembly_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                tileset_l3_index: data.read_u16::<LittleEndian>().unwrap() as usize,
+                music_index: data.read_u16::<LittleEndian>()? as usize,
+                tileset_l12_index: data.read_u16::<LittleEndian>()? as usize,
+                tileset_l12_assembly_index: data.read_u16::<LittleEndian>()? as usize,
+                tileset_l3_index: data.read_u16::<LittleEndian>()? as usize,
                 tileset_l3_assembly_index: 0,
-                palette_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                palette_anims_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                map_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                chip_anims_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                script_index: data.read_u16::<LittleEndian>().unwrap() as usize,
-                unknown: data.read_u16::<LittleEndian>().unwrap() as u32,
+                palette_index: data.read_u16::<LittleEndian>()? as usize,
+                palette_anims_index: data.read_u16::<LittleEndian>()? as usize,
+                map_index: data.read_u16::<LittleEndian>()? as usize,
+                chip_anims_index: data.read_u16::<LittleEndian>()? as usize,
+                script_index: data.read_u16::<LittleEndian>()? as usize,
+                unknown: data.read_u16::<LittleEndian>()? as u32,
                 scroll_mask: ScrollMask {
-                    left: data.read_u8().unwrap() as isize,
-                    top: data.read_u8().unwrap() as isize,
-                    right: data.read_u8().unwrap() as isize,
-                    bottom: data.read_u8().unwrap() as isize,
+                    left: data.read_u8()? as isize,
+                    top: data.read_u8()? as isize,
+                    right: data.read_u8()? as isize,
+                    bottom: data.read_u8()? as isize,
                 },
             },
         };
@@ -101,14 +106,21 @@ impl FileSystem {
             }
         }
 
-        let tileset_l12 = self.read_scene_tileset_layer12(header.tileset_l12_index, header.tileset_l12_assembly_index, header.chip_anims_index);
-        let tileset_l3 = self.read_scene_tileset_layer3(header.tileset_l3_index, header.tileset_l3_assembly_index);
-        let (scene_map, map) = self.read_scene_map(header.map_index, &tileset_l12, &tileset_l3);
-        let palette = self.read_scene_palette(header.palette_index);
-        let palette_anims = self.read_palette_anim_set(header.palette_anims_index);
-        let exits = self.read_scene_exits(scene_index);
-        let treasure = self.read_scene_treasure(scene_index);
-        let script = self.read_scene_script(header.script_index);
+        Ok(header)
+    }
+
+    // Read a scene and related data.
+    pub fn read_scene(&self, scene_index: usize) -> FileSystemResult<Scene> {
+        let mut header = self.read_scene_header(scene_index)?;
+
+        let tileset_l12 = self.read_scene_tileset_layer12(header.tileset_l12_index, header.tileset_l12_assembly_index, header.chip_anims_index)?;
+        let tileset_l3 = self.read_scene_tileset_layer3(header.tileset_l3_index, header.tileset_l3_assembly_index)?;
+        let (scene_map, map) = self.read_scene_map(header.map_index, &tileset_l12, &tileset_l3)?;
+        let palette = self.read_scene_palette(header.palette_index)?;
+        let palette_anims = self.read_palette_anim_set(header.palette_anims_index)?;
+        let exits = self.read_scene_exits(scene_index)?;
+        let treasure = self.read_scene_treasure(scene_index)?;
+        let script = self.read_scene_script(header.script_index)?;
 
         // A disabled scroll mask must cover the entire map.
         if header.scroll_mask.left == 0x80 {
@@ -127,7 +139,7 @@ impl FileSystem {
         header.scroll_mask.right *= 16;
         header.scroll_mask.bottom *= 16;
 
-        Scene::new(
+        Ok(Scene::new(
             scene_index,
             header.music_index,
             header.unknown,
@@ -141,34 +153,35 @@ impl FileSystem {
             exits,
             treasure,
             script,
-        )
+        ))
     }
 
-    pub fn read_scene_script(&self, script_index: usize) -> SceneScript {
-        let mut data = self.backend.get_scene_script_data(script_index);
+    pub fn read_scene_script(&self, script_index: usize) -> FileSystemResult<SceneScript> {
+        let mut data = self.backend.get_scene_script_data(script_index)?;
 
         let mut actor_scripts: Vec<SceneActorScript> = Vec::new();
-        let actor_count = data.read_u8().unwrap() as u64;
+        let actor_count = data.read_u8()? as u64;
         let header_size = actor_count * 32;
         for _ in 0..actor_count {
             let mut ptrs = [0; 16];
             for ptr in ptrs.iter_mut() {
-                *ptr = data.read_u16::<LittleEndian>().unwrap() as u64 - header_size;
+                *ptr = (data.read_u16::<LittleEndian>()? as u64).checked_sub(header_size).ok_or(FileSystemError::InvalidData(format!("Scene script {} has a pointer into its header", script_index)))?;
             }
             actor_scripts.push(SceneActorScript::new(ptrs));
         }
 
-        let mut script_data = vec![0u8; data.get_ref().len() - header_size as usize - 1];
-        data.read_exact(&mut script_data).unwrap();
+        let script_size = data.get_ref().len().checked_sub(header_size as usize + 1).ok_or(FileSystemError::InvalidData(format!("Scene script {} is smaller than its header", script_index)))?;
+        let mut script_data = vec![0u8; script_size];
+        data.read_exact(&mut script_data)?;
 
-        SceneScript::new(script_index, script_data, actor_scripts, self.mode)
+        Ok(SceneScript::new(script_index, script_data, actor_scripts, self.mode))
     }
 
     // Read exits from scenes.
     // These are triggered when the player walks onto them in the scene, and takes them to a
     // new location (world or scene).
-    pub fn read_scene_exits(&self, scene_index: usize) -> Vec<SceneExit> {
-        let mut data = self.backend.get_scene_exit_data(scene_index);
+    pub fn read_scene_exits(&self, scene_index: usize) -> FileSystemResult<Vec<SceneExit>> {
+        let mut data = self.backend.get_scene_exit_data(scene_index)?;
         let count = match self.mode {
             GameMode::Pc => data.get_ref().len() / 8,
             GameMode::Snes => data.get_ref().len() / 7,
@@ -190,14 +203,14 @@ impl FileSystem {
             match self.mode {
                 GameMode::Pc => {
                     pos = Vec2Di32::new(
-                        data.read_u8().unwrap() as i32 * 16,
-                        data.read_u8().unwrap() as i32 * 16,
+                        data.read_u8()? as i32 * 16,
+                        data.read_u8()? as i32 * 16,
                     );
-                    let size_bits = data.read_u8().unwrap() as u32;
-                    let facing_shift = data.read_u8().unwrap();
-                    dest_index = data.read_u16::<LittleEndian>().unwrap() as usize;
-                    tile_x = data.read_u8().unwrap() as i32;
-                    tile_y = data.read_u8().unwrap() as i32;
+                    let size_bits = data.read_u8()? as u32;
+                    let facing_shift = data.read_u8()?;
+                    dest_index = data.read_u16::<LittleEndian>()? as usize;
+                    tile_x = data.read_u8()? as i32;
+                    tile_y = data.read_u8()? as i32;
                     facing = Facing::from_data(facing_shift);
                     facing_byte = facing_shift;
 
@@ -217,13 +230,13 @@ impl FileSystem {
                 // into the destination bytes.
                 GameMode::Snes => {
                     pos = Vec2Di32::new(
-                        data.read_u8().unwrap() as i32 * 16,
-                        data.read_u8().unwrap() as i32 * 16,
+                        data.read_u8()? as i32 * 16,
+                        data.read_u8()? as i32 * 16,
                     );
-                    let size_bits = data.read_u8().unwrap() as u32;
-                    let dest_index_facing = data.read_u16::<LittleEndian>().unwrap() as usize;
-                    tile_x = data.read_u8().unwrap() as i32;
-                    tile_y = data.read_u8().unwrap() as i32;
+                    let size_bits = data.read_u8()? as u32;
+                    let dest_index_facing = data.read_u16::<LittleEndian>()? as usize;
+                    tile_x = data.read_u8()? as i32;
+                    tile_y = data.read_u8()? as i32;
 
                     dest_index = dest_index_facing & 0x1FF;
                     facing_byte = (dest_index_facing >> 8) as u8;
@@ -251,26 +264,29 @@ impl FileSystem {
             });
         }
 
-        exits
+        Ok(exits)
     }
 
-    pub fn read_scene_treasure(&self, scene_index: usize) -> Vec<Treasure> {
-        let (pointers, mut data) = self.backend.get_scene_treasure_data();
+    pub fn read_scene_treasure(&self, scene_index: usize) -> FileSystemResult<Vec<Treasure>> {
+        let (pointers, mut data) = self.backend.get_scene_treasure_data()?;
+        let start = *table_get(&pointers, scene_index, "scene treasure")?;
+        let end = *table_get(&pointers, scene_index + 1, "scene treasure")?;
+        let size = end.checked_sub(start).ok_or(FileSystemError::InvalidData(format!("Scene {} treasure ends before it starts", scene_index)))?;
         let treasure_count = match self.mode {
-            GameMode::Pc => (pointers[scene_index + 1] - pointers[scene_index]) / 6,
-            GameMode::Snes => (pointers[scene_index + 1] - pointers[scene_index]) / 4,
+            GameMode::Pc => size / 6,
+            GameMode::Snes => size / 4,
         };
 
         let mut treasure: Vec<Treasure> = Vec::new();
-        data.seek(SeekFrom::Start(pointers[scene_index] as u64)).unwrap();
+        data.seek(SeekFrom::Start(start as u64))?;
         for index in 0..treasure_count {
             let id = format!("{}_{}", scene_index, index);
             let tile_pos = Vec2Di32::new(
-                data.read_u8().unwrap() as i32,
-                data.read_u8().unwrap() as i32,
+                data.read_u8()? as i32,
+                data.read_u8()? as i32,
             );
 
-            let contents = data.read_u16::<LittleEndian>().unwrap();
+            let contents = data.read_u16::<LittleEndian>()?;
 
             // Pointer to other location chest data.
             if tile_pos.x == 0 && tile_pos.y == 0 {
@@ -284,11 +300,11 @@ impl FileSystem {
             treasure.push(item);
 
             if matches!(self.mode, GameMode::Pc) {
-                data.read_u16::<LittleEndian>().unwrap();
+                data.read_u16::<LittleEndian>()?;
             }
         }
 
-        treasure
+        Ok(treasure)
     }
 }
 
@@ -4,6 +4,7 @@ use std::io::SeekFrom;
 use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
 use crate::assets::Assets;
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
 use crate::GameMode;
 use crate::software_renderer::bitmap::Bitmap;
@@ -21,18 +22,18 @@ use crate::world_script::world_animation_script::WorldAnimationScript;
 
 impl FileSystem {
 
-    pub fn read_sprite_header(&self, index: usize) -> SpriteHeader {
-        let mut data = self.backend.get_sprite_header_data(index);
+    pub fn read_sprite_header(&self, index: usize) -> FileSystemResult<SpriteHeader> {
+        let mut data = self.backend.get_sprite_header_data(index)?;
 
         let mut header = match self.mode {
             GameMode::Pc => SpriteHeader {
                 index,
-                bitmap_index: data.read_u8().unwrap() as usize,
-                assembly_index: data.read_u8().unwrap() as usize,
-                palette_index: data.read_u8().unwrap() as usize,
-                size_flags: data.read_u8().unwrap() as u32,
-                anim_index: data.read_u8().unwrap() as usize,
-                flags: data.read_u8().unwrap() as u32,
+                bitmap_index: data.read_u8()? as usize,
+                assembly_index: data.read_u8()? as usize,
+                palette_index: data.read_u8()? as usize,
+                size_flags: data.read_u8()? as u32,
+                anim_index: data.read_u8()? as usize,
+                flags: data.read_u8()? as u32,
                 hand_x: 0,
                 hand_y: 0,
                 enemy_unknown1: 0,
@@ -41,11 +42,11 @@ impl FileSystem {
             },
             GameMode::Snes => SpriteHeader {
                 index,
-                bitmap_index: data.read_u8().unwrap() as usize,
-                assembly_index: data.read_u8().unwrap() as usize,
-                palette_index: data.read_u8().unwrap() as usize,
-                anim_index: data.read_u8().unwrap() as usize,
-                size_flags: data.read_u8().unwrap() as u32,
+                bitmap_index: data.read_u8()? as usize,
+                assembly_index: data.read_u8()? as usize,
+                palette_index: data.read_u8()? as usize,
+                anim_index: data.read_u8()? as usize,
+                size_flags: data.read_u8()? as u32,
                 flags: 0,
                 hand_x: 0,
                 hand_y: 0,
@@ -63,24 +64,24 @@ impl FileSystem {
                 
         // Enemy sprites have more data.
         if data.get_ref().len() > 6 {
-            header.hand_x = data.read_i8().unwrap() as i32;
-            header.hand_y = data.read_i8().unwrap() as i32;
-            header.enemy_unknown1 = data.read_u8().unwrap() as u32;
-            header.enemy_unknown2 = data.read_u8().unwrap() as u32;
-            header.enemy_unknown3 = data.read_u8().unwrap() as u32;
+            header.hand_x = data.read_i8()? as i32;
+            header.hand_y = data.read_i8()? as i32;
+            header.enemy_unknown1 = data.read_u8()? as u32;
+            header.enemy_unknown2 = data.read_u8()? as u32;
+            header.enemy_unknown3 = data.read_u8()? as u32;
         }
 
-        header
+        Ok(header)
     }
 
     // Reads a sprite's assembly data.
-    pub fn read_sprite_assembly(&self, index: usize, size_flags: u32) -> (SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>) {
-        let mut data = self.backend.get_sprite_assembly_data(index);
+    pub fn read_sprite_assembly(&self, index: usize, size_flags: u32) -> FileSystemResult<(SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>)> {
+        let mut data = self.backend.get_sprite_assembly_data(index)?;
 
         let (assembly, frames) = match self.mode {
             GameMode::Pc => {
-                data.seek(SeekFrom::Start(3)).unwrap();
-                parse_pc_sprite_assembly(index, &mut data)
+                data.seek(SeekFrom::Start(3))?;
+                parse_pc_sprite_assembly(index, &mut data)?
             },
             GameMode::Snes => {
                 let (groups_per_frame, tiles_per_group) = match size_flags & 0x3 {
@@ -90,28 +91,28 @@ impl FileSystem {
                     3 => (3, 8),
                     _ => (1, 4),
                 };
-                parse_snes_sprite_assembly(index, groups_per_frame, tiles_per_group, &mut data)
+                parse_snes_sprite_assembly(index, groups_per_frame, tiles_per_group, &mut data)?
             },
         };
 
         if frames.len() == 0 {
-            panic!("Sprite assembly {} has no frames.", assembly.index);
+            return Err(FileSystemError::InvalidData(format!("Sprite assembly {} has no frames", assembly.index)));
         }
 
-        (assembly, frames)
+        Ok((assembly, frames))
     }
 
     // Read all sprite animations.
     //
     // Sprite animations are split into slots (sprite assembly frames) and durations.
     // They also list data for each of the 4 sprite facings.
-    pub fn read_sprite_animations(&self) -> HashMap<usize, SpriteAnimSet> {
+    pub fn read_sprite_animations(&self) -> FileSystemResult<HashMap<usize, SpriteAnimSet>> {
         let (
             slot_ptrs,
             mut slot_data,
             interval_ptrs,
             mut interval_data
-        ) = self.backend.get_sprite_animation_data();
+        ) = self.backend.get_sprite_animation_data()?;
 
         let slot_data = slot_data.get_mut();
         let interval_data = interval_data.get_mut();
@@ -142,16 +143,14 @@ impl FileSystem {
             anim_sets.insert(set_index, set);
        }
 
-        anim_sets
+        Ok(anim_sets)
     }
 
     // Read a sprite palette.
-    pub fn read_sprite_palette(&self, index: usize, offset: usize) -> Option<Palette> {
-        let palette_wrap = self.backend.get_sprite_palette(index);
-        if palette_wrap.is_none() {
-            return None;
-        }
-        let source_palette = palette_wrap.unwrap();
+    pub fn read_sprite_palette(&self, index: usize, offset: usize) -> FileSystemResult<Option<Palette>> {
+        let Some(source_palette) = self.backend.get_sprite_palette(index)? else {
+            return Ok(None);
+        };
         let mut palette = Palette::new(0);
 
         for i in 0..16 {
@@ -162,65 +161,66 @@ impl FileSystem {
             }
         }
 
-        Some(palette)
+        Ok(Some(palette))
     }
 
     // Read a sprite's graphics tiles.
-    pub fn read_sprite_tiles(&self, sprite_tiles_index: usize, chip_count: usize) -> Bitmap {
+    pub fn read_sprite_tiles(&self, sprite_tiles_index: usize, chip_count: usize) -> FileSystemResult<Bitmap> {
         match self.mode {
             GameMode::Pc => {
-                let data = self.backend.get_sprite_graphics(sprite_tiles_index, chip_count, false);
+                let data = self.backend.get_sprite_graphics(sprite_tiles_index, chip_count, false)?;
                 let bitmap_height = (data.len() as f64 / 256.0).ceil() as u32;
-                Bitmap::from_raw_data(256, bitmap_height, data)
+                Ok(Bitmap::from_raw_data(256, bitmap_height, data))
             },
             GameMode::Snes => {
-                let data = self.backend.get_sprite_graphics(sprite_tiles_index, chip_count, sprite_tiles_index > 6);
+                let data = self.backend.get_sprite_graphics(sprite_tiles_index, chip_count, sprite_tiles_index > 6)?;
                 let bitmap_height = (data.len() as f64 / 128.0).ceil() as u32;
-                Bitmap::from_raw_data(128, bitmap_height, data)
+                Ok(Bitmap::from_raw_data(128, bitmap_height, data))
             },
         }
     }
 
-    pub fn read_world_animation_script(&self) -> WorldAnimationScript {
-        let data = self.backend.get_world_sprite_data();
+    pub fn read_world_animation_script(&self) -> FileSystemResult<WorldAnimationScript> {
+        let data = self.backend.get_world_sprite_data()?;
         let count = if self.mode == GameMode::Pc { 168 } else { 166 };
-        WorldAnimationScript::new(&data, count)
+        Ok(WorldAnimationScript::new(&data, count))
     }
 
     // Read all sprite tile graphics for a given list of world graphics.
-    pub fn read_world_sprite_tiles_all(&self, world_index: usize, bitmap_indices: [usize; 4]) -> Bitmap {
+    pub fn read_world_sprite_tiles_all(&self, world_index: usize, bitmap_indices: [usize; 4]) -> FileSystemResult<Bitmap> {
         let mut bitmap_data = vec![0u8; 0x8000];
 
         for (index, packet_index) in bitmap_indices.iter().enumerate() {
             let offset = index * 0x2000;
-            self.read_world_sprite_tiles(world_index, *packet_index, offset, &mut bitmap_data)
+            self.read_world_sprite_tiles(world_index, *packet_index, offset, &mut bitmap_data)?;
         }
 
-        Bitmap::from_raw_data(128, 256, bitmap_data)
+        Ok(Bitmap::from_raw_data(128, 256, bitmap_data))
     }
 
     // Read a single sprite tile graphic set and place its pixel data directly into a bitmap.
-    pub fn read_world_sprite_tiles(&self, world_index: usize, tiles_index: usize, offset: usize, bitmap_data: &mut Vec<u8>) {
+    pub fn read_world_sprite_tiles(&self, world_index: usize, tiles_index: usize, offset: usize, bitmap_data: &mut Vec<u8>) -> FileSystemResult<()> {
         if tiles_index & 0x80 > 0 {
-            return;
+            return Ok(());
         }
 
-        let tile_pixel_data = self.backend.get_world_sprite_graphics(world_index, tiles_index);
-        if tile_pixel_data.is_some() {
-            let pixel_data = tile_pixel_data.unwrap();
+        let tile_pixel_data = self.backend.get_world_sprite_graphics(world_index, tiles_index)?;
+        if let Some(pixel_data) = tile_pixel_data {
             let data_len = pixel_data.len();
             bitmap_data[offset..offset + data_len].copy_from_slice(&pixel_data);
         }
+
+        Ok(())
     }
 
     // Return world player sprite data.
-    pub fn read_world_player_sprite_tiles(&self) -> Vec<u8> {
-        self.backend.get_world_player_sprite_graphics().unwrap()
+    pub fn read_world_player_sprite_tiles(&self) -> FileSystemResult<Vec<u8>> {
+        self.backend.get_world_player_sprite_graphics()?.ok_or(FileSystemError::InvalidData("There are no world player sprite graphics".to_string()))
     }
 
     // Return Epoch sprite data.
-    pub fn read_world_epoch_sprite_tiles(&self) -> Vec<u8> {
-        self.backend.get_world_epoch_sprite_graphics().unwrap()
+    pub fn read_world_epoch_sprite_tiles(&self) -> FileSystemResult<Vec<u8>> {
+        self.backend.get_world_epoch_sprite_graphics()?.ok_or(FileSystemError::InvalidData("There are no Epoch sprite graphics".to_string()))
     }
 }
 
@@ -263,11 +263,11 @@ fn parse_sprite_animation_frames(slot_data: &Vec<u8>, start_slot_offset: usize,
     frames
 }
 
-fn parse_pc_sprite_assembly(assembly_index: usize, data: &mut Cursor<Vec<u8>>) -> (SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>) {
+fn parse_pc_sprite_assembly(assembly_index: usize, data: &mut Cursor<Vec<u8>>) -> FileSystemResult<(SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>)> {
 
     // Number of frames in this assembly.
-    let frame_count = data.read_u16::<LittleEndian>().unwrap() as usize;
-    data.read_u16::<LittleEndian>().unwrap();
+    let frame_count = data.read_u16::<LittleEndian>()? as usize;
+    data.read_u16::<LittleEndian>()?;
     let mut assembly = SpriteAssembly {
         index: assembly_index,
         chip_max: 0,
@@ -279,13 +279,13 @@ fn parse_pc_sprite_assembly(assembly_index: usize, data: &mut Cursor<Vec<u8>>) -
         let mut frame = SpriteAssemblyFrame::new();
 
         // Number of tiles in this assembly frame.
-        let tile_count = data.read_u8().unwrap();
+        let tile_count = data.read_u8()?;
         for _ in 0..tile_count {
-            let value = data.read_u16::<LittleEndian>().unwrap() as usize;
+            let value = data.read_u16::<LittleEndian>()? as usize;
 
-            let x = data.read_i8().unwrap() as i32;
-            let y = data.read_i8().unwrap() as i32;
-            let flags_value = data.read_u8().unwrap();
+            let x = data.read_i8()? as i32;
+            let y = data.read_i8()? as i32;
+            let flags_value = data.read_u8()?;
 
             // Tile index is (almost) always missing bit 4. Weird.
             // Is this also the case for the SNES data?
@@ -330,10 +330,10 @@ fn parse_pc_sprite_assembly(assembly_index: usize, data: &mut Cursor<Vec<u8>>) -
         frames.insert(key, frame);
     }
 
-    (assembly, frames)
+    Ok((assembly, frames))
 }
 
-fn parse_snes_sprite_assembly(assembly_index: usize, groups_per_frame: usize, tiles_per_group: usize, data: &mut Cursor<Vec<u8>>) -> (SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>) {
+fn parse_snes_sprite_assembly(assembly_index: usize, groups_per_frame: usize, tiles_per_group: usize, data: &mut Cursor<Vec<u8>>) -> FileSystemResult<(SpriteAssembly, HashMap<u64, SpriteAssemblyFrame>)> {
     let tiles_per_frame = groups_per_frame * tiles_per_group;
     let chips_per_group = tiles_per_group * 4;
     let frame_count = data.get_ref().len() / (tiles_per_frame * 10);
@@ -354,14 +354,14 @@ fn parse_snes_sprite_assembly(assembly_index: usize, groups_per_frame: usize, ti
 
             // Upper row chips.
             for tile in 0..tiles_per_group {
-                frame.chips[group_start + tile * 4 + 0] = parse_snes_sprite_assembly_chip(data, 0, 0);
-                frame.chips[group_start + tile * 4 + 1] = parse_snes_sprite_assembly_chip(data, 8, 0);
+                frame.chips[group_start + tile * 4 + 0] = parse_snes_sprite_assembly_chip(data, 0, 0)?;
+                frame.chips[group_start + tile * 4 + 1] = parse_snes_sprite_assembly_chip(data, 8, 0)?;
             }
 
             // Bottom row chips.
             for tile in 0..tiles_per_group {
-                frame.chips[group_start + tile * 4 + 2] = parse_snes_sprite_assembly_chip(data, 0, 8);
-                frame.chips[group_start + tile * 4 + 3] = parse_snes_sprite_assembly_chip(data, 8, 8);
+                frame.chips[group_start + tile * 4 + 2] = parse_snes_sprite_assembly_chip(data, 0, 8)?;
+                frame.chips[group_start + tile * 4 + 3] = parse_snes_sprite_assembly_chip(data, 8, 8)?;
             }
         }
 
@@ -373,8 +373,8 @@ fn parse_snes_sprite_assembly(assembly_index: usize, groups_per_frame: usize, ti
             for tile in 0..tiles_per_group {
                 let tile_start = group_start + tile * 4;
 
-                let ox = data.read_i8().unwrap() as i32;
-                let oy = data.read_i8().unwrap() as i32;
+                let ox = data.read_i8()? as i32;
+                let oy = data.read_i8()? as i32;
 
                 // Tiles above -24 are considered to be the top of the sprite.
                 let is_top = oy < -24;
@@ -401,11 +401,11 @@ fn parse_snes_sprite_assembly(assembly_index: usize, groups_per_frame: usize, ti
         frames.insert(key, frame);
     }
 
-    (assembly, frames)
+    Ok((assembly, frames))
 }
 
-fn parse_snes_sprite_assembly_chip(data: &mut Cursor<Vec<u8>>, x: i32, y: i32) -> SpriteAssemblyChip {
-    let value = data.read_u16::<LittleEndian>().unwrap();
+fn parse_snes_sprite_assembly_chip(data: &mut Cursor<Vec<u8>>, x: i32, y: i32) -> FileSystemResult<SpriteAssemblyChip> {
+    let value = data.read_u16::<LittleEndian>()?;
 
     let chip = (value & 0x3FF) as usize;
     let src_x = ((chip % 16) * 8) as i32;
@@ -419,12 +419,12 @@ fn parse_snes_sprite_assembly_chip(data: &mut Cursor<Vec<u8>>, x: i32, y: i32) -
         flags |= SpriteAssemblyChipFlags::FLIP_Y;
     }
 
-    SpriteAssemblyChip {
+    Ok(SpriteAssemblyChip {
         src_index: chip,
         x, y,
         src_x, src_y,
         width: 8,
         height: 8,
         flags,
-    }
+    })
 }
@@ -1,19 +1,29 @@
+use crate::filesystem::error::FileSystemResult;
 use crate::filesystem::filesystem::FileSystem;
 
 impl FileSystem {
-    pub fn read_world_exit_names(&self, language: &str) -> Vec<String> {
+    pub fn read_languages(&self) -> FileSystemResult<Vec<String>> {
+        self.backend.get_languages()
+    }
+
+    // Returns the keyed strings from all message files as (message file, key, string).
+    pub fn read_keyed_strings(&self, language: &str) -> FileSystemResult<Vec<(String, String, String)>> {
+        self.backend.get_keyed_strings(language)
+    }
+
+    pub fn read_world_exit_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
         self.backend.get_world_exit_names(language)
     }
 
-    pub fn read_scene_names(&self, language: &str) -> Vec<String> {
+    pub fn read_scene_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
         self.backend.get_scene_names(language)
     }
 
-    pub fn read_world_names(&self, language: &str) -> Vec<String> {
-        let names = self.backend.get_world_names(language);
+    pub fn read_world_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        let names = self.backend.get_world_names(language)?;
 
         // Reorder these so they match world indexes.
-        [
+        Ok([
             names[0].clone(),
             names[1].clone(),
             names[2].clone(),
@@ -22,19 +32,25 @@ impl FileSystem {
             names[4].clone(),
             names[4].clone(),
             names[5].clone(),
-        ].to_vec()
+        ].to_vec())
     }
 
-    pub fn read_item_names(&self, language: &str) -> Vec<String> {
+    pub fn read_item_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
         self.backend.get_item_names(language)
     }
 
-    pub fn read_textbox_string_table(&self, address: usize, language: &str, strings: &mut Vec<String>) {
+    pub fn read_tech_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
+        self.backend.get_tech_names(language)
+    }
+
+    pub fn read_textbox_string_table(&self, address: usize, language: &str, strings: &mut Vec<String>) -> FileSystemResult<()> {
         strings.clear();
-        strings.extend(self.backend.get_textbox_string_table(address, language));
+        strings.extend(self.backend.get_textbox_string_table(address, language)?);
+
+        Ok(())
     }
 
-    pub fn read_player_character_names(&self, language: &str) -> Vec<String> {
+    pub fn read_player_character_names(&self, language: &str) -> FileSystemResult<Vec<String>> {
         self.backend.get_player_names(language)
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{Cursor, Seek, SeekFrom};
 use byteorder::{LittleEndian, ReadBytesExt};
 
@@ -21,12 +22,22 @@ const FONT_8_MAP: [&str; 256] = [
     "°", "<A>", "#", "#", "<L>", "<R>", "<H>", "<M>", "<P>", "", "<CORNER>", "(", ")", "¶", "¶", " "
 ];
 
+#[derive(Default)]
 pub struct TextDecoder {
     pub substrings: Vec<String>,
 }
 
 impl TextDecoder {
+
+    /// A decoder without a substring table.
+    pub fn new() -> Self {
+        TextDecoder {
+            substrings: Vec::new(),
+        }
+    }
+
     pub fn from_cursor(data: &mut Cursor<Vec<u8>>, substring_count: usize, start: u16) -> Self {
+        let mut decoder = TextDecoder::new();
 
         // Read substring pointers.
         let mut pointers = vec![0u16; substring_count];
@@ -35,16 +46,14 @@ impl TextDecoder {
         }
 
         // Decode all substrings.
-        let mut substrings = Vec::<String>::with_capacity(substring_count);
         for pointer in pointers {
             data.seek(SeekFrom::Start(pointer as u64)).unwrap();
             let len = data.read_u8().unwrap() as usize;
-            substrings.push(read_substring(data, len));
+            let substring = decoder.read_substring(data, len);
+            decoder.substrings.push(substring);
         }
 
-        TextDecoder {
-            substrings,
-        }
+        decoder
     }
 
     pub fn decode_huffman_string(&self, data: &mut Cursor<Vec<u8>>) -> String {
@@ -57,22 +66,19 @@ impl TextDecoder {
             if value == 0 {
                 break;
 
-            } else if value == 1 {
-                parts.push(parse_character(data.read_u8().unwrap()));
-            } else if value == 2 {
+            } else if value == 1 || value == 2 {
                 parts.push(parse_character(data.read_u8().unwrap()));
 
-
             // A delay of 0 (infinite) ends the string.
             } else if value == 3 {
                 let delay = data.read_u8().unwrap();
                 if delay == 0 {
                     break;
                 }
-                parts.push(format!("<WAIT>{:02x}</WAIT><AUTO_PAGE>", data.read_u8().unwrap()));
+                parts.push(format!("<WAIT>{:02x}</WAIT><AUTO_PAGE>", delay));
 
-            // A word from the dictionary.
-            } else if value >= 0x21 && value <= 0x9F {
+            // A word from the dictionary, if there is one.
+            } else if value >= 0x21 && value <= 0x9F && !self.substrings.is_empty() {
                 let index = value as usize - 0x21;
                 parts.push(self.substrings[index].clone());
 
@@ -94,39 +100,64 @@ impl TextDecoder {
         }
         parts.join("").trim_end().to_string()
     }
-}
 
-fn read_substring(data: &mut Cursor<Vec<u8>>, len: usize) -> String {
-    let mut parts = Vec::<String>::new();
-
-    for _ in 0..len {
-        let value = data.read_u8().unwrap();
-        if value == 0 {
-            break;
-        } else if value == 1 {
-            parts.push(parse_character(data.read_u8().unwrap()));
-        } else if value == 2 {
-            parts.push(parse_character(data.read_u8().unwrap()));
-
-        // A delay of 0 (infinite) ends the string.
-        } else if value == 3 {
-            let delay = data.read_u8().unwrap();
-            if delay == 0 {
+    fn read_substring(&self, data: &mut Cursor<Vec<u8>>, len: usize) -> String {
+        let mut parts = Vec::<String>::new();
+
+        for _ in 0..len {
+            let value = data.read_u8().unwrap();
+            if value == 0 {
                 break;
+            } else if value == 1 || value == 2 {
+                parts.push(parse_character(data.read_u8().unwrap()));
+
+            // A delay of 0 (infinite) ends the string.
+            } else if value == 3 {
+                let delay = data.read_u8().unwrap();
+                if delay == 0 {
+                    break;
+                }
+                parts.push(format!("<WAIT>{:02x}</WAIT><AUTO_PAGE>", delay));
+
+            } else if value >= 0x9F {
+                parts.push(parse_character(value));
+            } else {
+                parts.push(read_special_character(value, data));
             }
-            parts.push(format!("<WAIT>{:02x}</WAIT><AUTO_PAGE>", data.read_u8().unwrap()));
+        }
+
+        parts.join("")
+    }
+}
+
+/// Maps the text of each 8x8 font character to its index in the font. Tags such as "<HAND1>" are
+/// mapped as a whole. Characters that appear more than once map to their first index.
+pub fn font_8_glyph_map() -> HashMap<String, usize> {
+    let mut glyphs = HashMap::new();
+    for (index, text) in FONT_8_MAP.iter().enumerate() {
+        if *text != "¶" && !text.is_empty() {
+            glyphs.entry(text.to_string()).or_insert(index);
+        }
+    }
 
-        } else if value >= 0x9F {
-            parts.push(parse_character(value));
-        } else {
-            parts.push(read_special_character(value, data));
+    glyphs
+}
+
+/// Maps the text of each dialogue font character to its index in the font. The dialogue font
+/// starts at character code 0xA0.
+pub fn font_16_glyph_map() -> HashMap<String, usize> {
+    let mut glyphs = HashMap::new();
+    for code in 0xA0..=0xFF {
+        let text = parse_character(code);
+        if text != "<UNKNOWN>" {
+            glyphs.entry(text).or_insert(code as usize - 0xA0);
         }
     }
 
-    parts.join("")
+    glyphs
 }
 
-fn parse_character(value: u8) -> String {
+pub(super) fn parse_character(value: u8) -> String {
     if value >= 0xA0 && value <= 0xB9 {
         return char::from(0x41 + (value - 0xA0)).to_string();
     } else if value >= 0xBA && value <= 0xD3 {
@@ -162,7 +193,7 @@ fn parse_character(value: u8) -> String {
     }.to_string()
 }
 
-fn read_special_character(code: u8, data: &mut Cursor<Vec<u8>>) -> String {
+pub(super) fn read_special_character(code: u8, data: &mut Cursor<Vec<u8>>) -> String {
     if code == 0x12 {
         return format!("<NAME_TEC>{:02x}</NAME_TEC>", data.read_u8().unwrap());
     }
@@ -199,3 +230,37 @@ fn read_special_character(code: u8, data: &mut Cursor<Vec<u8>>) -> String {
         _ => "<UNKNOWN>",
     }.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_delay() {
+        let decoder = TextDecoder::new();
+
+        // The delay is the byte after the wait code, and the string continues after it.
+        let mut data = Cursor::new(vec![0xA0, 0x03, 0x10, 0xA1, 0x00]);
+        assert_eq!(decoder.decode_huffman_string(&mut data), "A<WAIT>10</WAIT><AUTO_PAGE>B");
+
+        // A delay of 0 ends the string.
+        let mut data = Cursor::new(vec![0xA0, 0x03, 0x00, 0xA1, 0x00]);
+        assert_eq!(decoder.decode_huffman_string(&mut data), "A");
+    }
+
+    #[test]
+    fn font_glyph_maps() {
+        let font_8 = font_8_glyph_map();
+        assert_eq!(font_8["<HAND1>"], 0x60);
+        assert_eq!(font_8["<HP0>"], 0x67);
+        assert_eq!(font_8["A"], 0xA0);
+        assert_eq!(font_8["0"], 0x73);
+        assert!(!font_8.contains_key("¶"));
+
+        let font_16 = font_16_glyph_map();
+        assert_eq!(font_16["A"], 0);
+        assert_eq!(font_16["a"], 0x1A);
+        assert_eq!(font_16[" "], 0x4F);
+        assert_eq!(font_16["#"], 0x53);
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use regex::Regex;
+use crate::filesystem::text_decoder::{parse_character, read_special_character, TextDecoder};
+
+// A piece of tagged text that is encoded as a whole. Tags that have no encoding of their own, such
+// as "<UNKNOWN>", can only be encoded through a dictionary substring that decodes to them.
+#[derive(Debug, PartialEq)]
+struct Token {
+    text: String,
+    bytes: Option<Vec<u8>>,
+}
+
+/// Encodes tagged text into the dictionary compressed format that TextDecoder::decode_huffman_string
+/// reads. Dictionary substrings are chosen so that the encoded string is as short as possible.
+pub struct TextEncoder {
+
+    // Fixed tags and characters, longest text first.
+    fixed: Vec<(String, Vec<u8>)>,
+
+    // The dictionary substrings as tokens.
+    substrings: Vec<Vec<Token>>,
+
+    regex_wait: Regex,
+    regex_name_tec: Regex,
+}
+
+impl TextEncoder {
+    pub fn new(decoder: &TextDecoder) -> TextEncoder {
+        // Everything the decoder can output from a single code without arguments.
+        let mut fixed: HashMap<String, Vec<u8>> = HashMap::new();
+        for code in 0x04..=0x20 {
+            if code == 0x12 {
+                continue;
+            }
+            let text = read_special_character(code, &mut Cursor::new(Vec::new()));
+            if text != "<UNKNOWN>" {
+                fixed.entry(text).or_insert_with(|| vec![code]);
+            }
+        }
+        for code in 0xA0..=0xFF {
+            let text = parse_character(code);
+            if text != "<UNKNOWN>" {
+                fixed.entry(text).or_insert_with(|| vec![code]);
+            }
+        }
+        let mut fixed: Vec<(String, Vec<u8>)> = fixed.into_iter().collect();
+        fixed.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then(a.0.cmp(&b.0)));
+
+        let mut encoder = TextEncoder {
+            fixed,
+            substrings: Vec::new(),
+
+            regex_wait: Regex::new(r"^<WAIT>([0-9a-f]{2})</WAIT><AUTO_PAGE>").unwrap(),
+            regex_name_tec: Regex::new(r"^<NAME_TEC>([0-9a-f]{2})</NAME_TEC>").unwrap(),
+        };
+
+        // Only the substrings that dictionary codes 0x21 to 0x9F can refer to.
+        encoder.substrings = decoder.substrings.iter()
+            .take(0x9F - 0x21 + 1)
+            .map(|substring| encoder.tokenize(substring).unwrap_or_default())
+            .collect();
+
+        encoder
+    }
+
+    /// Encodes a string, including the terminating NULL character.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>, String> {
+        let tokens = self.tokenize(text)?;
+
+        // Find the shortest encoding from the end of the string to its start. Each token is either
+        // encoded directly, or starts a dictionary substring.
+        let mut cost = vec![usize::MAX; tokens.len() + 1];
+        let mut choice: Vec<Option<(usize, Option<u8>)>> = vec![None; tokens.len() + 1];
+        cost[tokens.len()] = 0;
+        for index in (0..tokens.len()).rev() {
+            if let Some(bytes) = &tokens[index].bytes && cost[index + 1] != usize::MAX {
+                cost[index] = bytes.len() + cost[index + 1];
+                choice[index] = Some((1, None));
+            }
+
+            for (substring_index, substring) in self.substrings.iter().enumerate() {
+                let len = substring.len();
+                if len == 0 || index + len > tokens.len() || cost[index + len] == usize::MAX {
+                    continue;
+                }
+                if !substring.iter().zip(&tokens[index..index + len]).all(|(a, b)| a.text == b.text) {
+                    continue;
+                }
+                if 1 + cost[index + len] < cost[index] {
+                    cost[index] = 1 + cost[index + len];
+                    choice[index] = Some((len, Some(0x21 + substring_index as u8)));
+                }
+            }
+        }
+
+        let mut data = Vec::new();
+        let mut index = 0;
+        while index < tokens.len() {
+            match choice[index] {
+                Some((len, Some(code))) => {
+                    data.push(code);
+                    index += len;
+                },
+                Some((_, None)) => {
+                    data.extend(tokens[index].bytes.as_ref().unwrap());
+                    index += 1;
+                },
+                None => return Err(format!("Cannot encode '{}' in \"{}\".", tokens[index].text, text)),
+            }
+        }
+        data.push(0);
+
+        Ok(data)
+    }
+
+    fn tokenize(&self, text: &str) -> Result<Vec<Token>, String> {
+        let mut tokens = Vec::new();
+        let mut rest = text;
+        'next: while !rest.is_empty() {
+            if let Some(captures) = self.regex_wait.captures(rest) {
+                let ticks = u8::from_str_radix(&captures[1], 16).unwrap();
+                tokens.push(Token { text: captures[0].to_string(), bytes: Some(vec![0x03, ticks]) });
+                rest = &rest[captures[0].len()..];
+                continue;
+            }
+            if let Some(captures) = self.regex_name_tec.captures(rest) {
+                let tech = u8::from_str_radix(&captures[1], 16).unwrap();
+                tokens.push(Token { text: captures[0].to_string(), bytes: Some(vec![0x12, tech]) });
+                rest = &rest[captures[0].len()..];
+                continue;
+            }
+
+            for (fixed_text, bytes) in &self.fixed {
+                if rest.starts_with(fixed_text.as_str()) {
+                    tokens.push(Token { text: fixed_text.clone(), bytes: Some(bytes.clone()) });
+                    rest = &rest[fixed_text.len()..];
+                    continue 'next;
+                }
+            }
+
+            // Other tags.
+            if rest.starts_with('<') {
+                let end = rest.find('>').ok_or_else(|| format!("Unterminated tag in \"{}\".", text))?;
+                tokens.push(Token { text: rest[..=end].to_string(), bytes: None });
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            let character = rest.chars().next().unwrap();
+            return Err(format!("Character '{}' has no encoding in \"{}\".", character, text));
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_decoder() -> TextDecoder {
+        let mut decoder = TextDecoder::new();
+        decoder.substrings = vec![
+            "the".to_string(),
+            "the ".to_string(),
+            "he".to_string(),
+            "<BR>".to_string(),
+            "<UNKNOWN>".to_string(),
+        ];
+
+        decoder
+    }
+
+    #[test]
+    fn encode_round_trip() {
+        let decoder = test_decoder();
+        let encoder = TextEncoder::new(&decoder);
+
+        for text in [
+            "",
+            "Hello there!",
+            "<NAME_CRO>: the end<BR><INDENT>...<PAGE>",
+            "Wait<WAIT>1e</WAIT><AUTO_PAGE>for<NAME_TEC>05</NAME_TEC>",
+            "<WAIT>03</WAIT><BR>1000 G<NUMBER 16>",
+            "<UNKNOWN>the",
+        ] {
+            let data = encoder.encode(text).unwrap();
+            assert_eq!(decoder.decode_huffman_string(&mut Cursor::new(data)), text);
+        }
+    }
+
+    #[test]
+    fn encode_shortest() {
+        let encoder = TextEncoder::new(&test_decoder());
+
+        // "the " as one substring is shorter than "the" followed by a space.
+        assert_eq!(encoder.encode("the cat").unwrap(), vec![0x22, 0xBC, 0xBA, 0xCD, 0x00]);
+
+        // "t" followed by "he" is as short as "the", the first option found is used.
+        assert_eq!(encoder.encode("the").unwrap().len(), 2);
+        assert_eq!(encoder.encode("<BR>").unwrap(), vec![0x05, 0x00]);
+    }
+
+    #[test]
+    fn encode_errors() {
+        let encoder = TextEncoder::new(&test_decoder());
+        assert!(encoder.encode("<NOT_A_TAG>").is_err());
+        assert!(encoder.encode("<BR").is_err());
+        assert!(encoder.encode("ü").is_err());
+    }
+}
@@ -4,6 +4,7 @@ use std::io::Read;
 use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
 
+use crate::filesystem::error::FileSystemResult;
 use crate::filesystem::filesystem::FileSystem;
 use crate::GameMode;
 use crate::map::MapChip;
@@ -17,7 +18,7 @@ use crate::tileset::TileSet;
 impl FileSystem {
 
     // Read a layer 1 or 2 tileset for a world map.
-    pub fn read_world_tileset_layer12 (&self, index_chips: [usize; 8], index_assembly: usize) -> TileSet {
+    pub fn read_world_tileset_layer12 (&self, index_chips: [usize; 8], index_assembly: usize) -> FileSystemResult<TileSet> {
 
         // Read chip bitmap data.
         let mut bitmap_data = vec![0u8; 0x10000];
@@ -27,7 +28,7 @@ impl FileSystem {
             }
 
             // Load layer 1/2 chip graphics data referenced by the tileset.
-            let mut chips_data = self.backend.get_world_tileset12_graphics(*chipset);
+            let mut chips_data = self.backend.get_world_tileset12_graphics(*chipset)?;
             if let Some(chips_data) = &mut chips_data {
                 let data_len = chips_data.len();
                 set_chip_bitmap_data(&mut bitmap_data, chips_data, index * 0x2000, data_len);
@@ -38,48 +39,47 @@ impl FileSystem {
         let chip_bitmaps = split_chip_graphics(&bitmap_data, bitmap_data.len() / 64);
 
         // Read tile assembly data.
-        let mut assembly_data = self.backend.get_world_tileset12_assembly_data(index_assembly);
-        let tiles = parse_world_l12_tile_assembly(&mut assembly_data, 512, 16);
+        let mut assembly_data = self.backend.get_world_tileset12_assembly_data(index_assembly)?;
+        let tiles = parse_world_l12_tile_assembly(&mut assembly_data, 512, 16)?;
 
-        TileSet {
+        Ok(TileSet {
             index: 0,
             index_assembly,
             tiles,
             chip_bitmaps,
             animated_chip_bitmaps: Vec::new(),
             chip_anims: Vec::new(),
-        }
+        })
     }
 
     // Read a layer 3 tileset for a world map.
-    pub fn read_world_tileset_layer3 (&self, index_chips: usize, index_assembly: usize) -> TileSet {
+    pub fn read_world_tileset_layer3 (&self, index_chips: usize, index_assembly: usize) -> FileSystemResult<TileSet> {
         let mut chip_bitmaps = Vec::<Bitmap>::new();
         let mut tiles = Vec::<Tile>::new();
 
         if index_chips != 0x80 {
-            let chips_data = self.backend.get_world_tileset3_graphics(index_chips);
-            if chips_data.is_some() {
-                let data = chips_data.unwrap();
+            let chips_data = self.backend.get_world_tileset3_graphics(index_chips)?;
+            if let Some(data) = chips_data {
                 let chip_count = data.len() / 64;
                 chip_bitmaps.extend(split_chip_graphics(&data, chip_count));
             }
 
-            let mut assembly = self.backend.get_world_tileset3_assembly_data(index_assembly);
-            tiles.extend(parse_world_l3_tile_assembly(&mut assembly, 512, 4));
+            let mut assembly = self.backend.get_world_tileset3_assembly_data(index_assembly)?;
+            tiles.extend(parse_world_l3_tile_assembly(&mut assembly, 512, 4)?);
         }
 
-        TileSet {
+        Ok(TileSet {
             index: index_chips,
             index_assembly,
             tiles,
             chip_bitmaps,
             animated_chip_bitmaps: Vec::new(),
             chip_anims: Vec::new(),
-        }
+        })
     }
 
     // Read a tileset for a scene map layer 1 or 2.
-    pub fn read_scene_tileset_layer12 (&self, tileset_index: usize, index_assembly: usize, chip_anims_index: usize) -> TileSet {
+    pub fn read_scene_tileset_layer12 (&self, tileset_index: usize, index_assembly: usize, chip_anims_index: usize) -> FileSystemResult<TileSet> {
         let mut bitmap_data = vec![0u8; 0x14000];
         let mut animated_bitmap_data = vec![0u8; 0x8000];
 
@@ -92,9 +92,9 @@ impl FileSystem {
 
             // Each tileset refers to 8 sets of pixel data containing 128 8x8 pixel chips. The
             // last 2 sets are for animated tiles.
-            let mut data = self.backend.get_scene_tileset_data(tileset_index);
+            let mut data = self.backend.get_scene_tileset_data(tileset_index)?;
             let mut chipsets = [0u8; 8];
-            data.read_exact(&mut chipsets).unwrap();
+            data.read_exact(&mut chipsets)?;
 
             for (chipset_index, chipset) in chipsets.iter().enumerate() {
                 if *chipset == 0xFF {
@@ -102,7 +102,7 @@ impl FileSystem {
                 }
 
                 // Load layer 1/2 chip graphics data referenced by the tileset.
-                let mut chipset_data = self.backend.get_scene_tileset12_graphics(*chipset as usize);
+                let mut chipset_data = self.backend.get_scene_tileset12_graphics(*chipset as usize)?;
                 let data_len = chipset_data.len();
 
                 // Set 6 contains animated chips, store them separately.
@@ -120,17 +120,17 @@ impl FileSystem {
 
             // Using these chips, 16x16 tiles are constructed. Each tile has 4 corners. Each
             // corner refers to a chip, a palette and some flags to describe the tile.
-            let mut assembly_data = self.backend.get_scene_tileset12_assembly_data(index_assembly);
-            tiles.extend(parse_scene_tile_assembly(&mut assembly_data, 512, 16, self.mode));
+            let mut assembly_data = self.backend.get_scene_tileset12_assembly_data(index_assembly)?;
+            tiles.extend(parse_scene_tile_assembly(&mut assembly_data, 512, 16, self.mode)?);
 
             // Read tile animation data.
-            let anim_data = self.backend.get_scene_tileset12_animation_data(chip_anims_index);
-            if anim_data.is_some() {
-                chip_anims.extend(parse_chip_anims(&mut anim_data.unwrap(), self.mode));
+            let anim_data = self.backend.get_scene_tileset12_animation_data(chip_anims_index)?;
+            if let Some(mut anim_data) = anim_data {
+                chip_anims.extend(parse_chip_anims(&mut anim_data, self.mode)?);
             }
         }
 
-        TileSet {
+        Ok(TileSet {
             index: tileset_index,
             index_assembly,
 
@@ -138,53 +138,52 @@ impl FileSystem {
             chip_bitmaps,
             animated_chip_bitmaps,
             chip_anims,
-        }
+        })
     }
 
     // Read a tileset for layer 3 of scene maps.
-    pub fn read_scene_tileset_layer3 (&self, chips_index: usize, index_assembly: usize) -> TileSet {
+    pub fn read_scene_tileset_layer3 (&self, chips_index: usize, index_assembly: usize) -> FileSystemResult<TileSet> {
         let mut chip_bitmaps = Vec::<Bitmap>::new();
         let mut tiles = Vec::<Tile>::new();
 
         if chips_index != 0xFF && chips_index != 0xFFFF {
-            let chips_data = self.backend.get_scene_tileset3_graphics(chips_index);
-            if chips_data.is_some() {
-                let data = chips_data.unwrap();
+            let chips_data = self.backend.get_scene_tileset3_graphics(chips_index)?;
+            if let Some(data) = chips_data {
                 let chip_count = data.len() / 64;
                 chip_bitmaps.extend(split_chip_graphics(&data, chip_count));
             }
 
-            let assembly_data = self.backend.get_scene_tileset3_assembly_data(index_assembly);
-            if assembly_data.is_some() {
-                tiles.extend(parse_scene_tile_assembly(&mut assembly_data.unwrap(), 256, 4, self.mode));
+            let assembly_data = self.backend.get_scene_tileset3_assembly_data(index_assembly)?;
+            if let Some(mut assembly_data) = assembly_data {
+                tiles.extend(parse_scene_tile_assembly(&mut assembly_data, 256, 4, self.mode)?);
             }
         }
 
-        TileSet {
+        Ok(TileSet {
             index: chips_index,
             index_assembly,
             tiles,
             chip_bitmaps,
             animated_chip_bitmaps: Vec::new(),
             chip_anims: Vec::new(),
-        }
+        })
     }
 }
 
 // Read tile chip animation data.
 //
 // An animation lists an offset for and animates 4 chips in a tileset.
-fn parse_chip_anims(reader: &mut Cursor<Vec<u8>>, mode: GameMode) -> Vec<ChipAnim> {
+fn parse_chip_anims(reader: &mut Cursor<Vec<u8>>, mode: GameMode) -> FileSystemResult<Vec<ChipAnim>> {
     let mut anims = Vec::<ChipAnim>::new();
-    while reader.fill_buf().unwrap().len() > 0 {
-        let frame_count = reader.read_u8().unwrap() as usize;
+    while reader.fill_buf()?.len() > 0 {
+        let frame_count = reader.read_u8()? as usize;
         if frame_count == 0 || frame_count == 0x80 {
             break;
         }
 
         let dest_chip = match mode {
-            GameMode::Snes => (reader.read_u16::<LittleEndian>().unwrap() as usize - 0x2000) / 16,
-            GameMode::Pc => reader.read_u16::<LittleEndian>().unwrap() as usize / 32,
+            GameMode::Snes => (reader.read_u16::<LittleEndian>()? as usize - 0x2000) / 16,
+            GameMode::Pc => reader.read_u16::<LittleEndian>()? as usize / 32,
         };
 
         let mut anim = ChipAnim {
@@ -196,7 +195,7 @@ fn parse_chip_anims(reader: &mut Cursor<Vec<u8>>, mode: GameMode) -> Vec<ChipAni
 
         // Frame durations are listed first, then the source animated chip indices.
         for _ in 0..frame_count {
-            let duration_bits = reader.read_u8().unwrap() as usize;
+            let duration_bits = reader.read_u8()? as usize;
             let duration = match duration_bits & 0xF0 {
                 16  => 16.0,
                 32  => 12.0,
@@ -212,8 +211,8 @@ fn parse_chip_anims(reader: &mut Cursor<Vec<u8>>, mode: GameMode) -> Vec<ChipAni
         }
         for frame_index in 0..frame_count {
             let src_chip = match mode {
-                GameMode::Snes => (reader.read_u16::<LittleEndian>().unwrap() - 0x6000) as usize / 32,
-                GameMode::Pc => reader.read_u16::<LittleEndian>().unwrap() as usize / 32,
+                GameMode::Snes => (reader.read_u16::<LittleEndian>()? - 0x6000) as usize / 32,
+                GameMode::Pc => reader.read_u16::<LittleEndian>()? as usize / 32,
             };
             anim.frames[frame_index].src_chip = src_chip;
         }
@@ -221,7 +220,7 @@ fn parse_chip_anims(reader: &mut Cursor<Vec<u8>>, mode: GameMode) -> Vec<ChipAni
         anims.push(anim);
     }
 
-    anims
+    Ok(anims)
 }
 
 // Read and copy chip bitmap graphics data.
@@ -232,7 +231,7 @@ fn set_chip_bitmap_data(bitmap_data: &mut Vec<u8>, data: &mut Vec<u8>, offset: u
 }
 
 // Reads tile assembly data for a scene tileset.
-fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize, mode: GameMode) -> Vec<Tile> {
+fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize, mode: GameMode) -> FileSystemResult<Vec<Tile>> {
     let mut tiles = vec![Tile::default(); tile_count];
 
     // Read data for each tile corner (chip).
@@ -253,7 +252,7 @@ fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, pa
                 // byte 2
                 // priority: 1 bit
                 GameMode::Pc => {
-                    let data1 = reader.read_u16::<LittleEndian>().unwrap();
+                    let data1 = reader.read_u16::<LittleEndian>()?;
                     chip = (data1 & 0x3FF) as usize;
 
                     if data1 & 0x400 > 0 {
@@ -264,7 +263,7 @@ fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, pa
                     }
                     palette = ((data1 >> 12) & 0xF) as usize;
 
-                    let data2 = reader.read_u8().unwrap();
+                    let data2 = reader.read_u8()?;
                     if data2 & 0x01 > 0 {
                         flags |= MapChipFlags::PRIORITY;
                     }
@@ -276,7 +275,7 @@ fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, pa
                 // flip x: 1 bit
                 // flip y: 1 bit
                 GameMode::Snes => {
-                    let data1 = reader.read_u16::<LittleEndian>().unwrap();
+                    let data1 = reader.read_u16::<LittleEndian>()?;
                     if palette_size == 4 {
                         chip = (data1 & 0x3FF) as usize;
                     } else {
@@ -303,11 +302,11 @@ fn parse_scene_tile_assembly(reader: &mut Cursor<Vec<u8>>, tile_count: usize, pa
         }
     }
 
-    tiles
+    Ok(tiles)
 }
 
 // Read tile assembly data for world layer 1 or 2 tiles.
-fn parse_world_l12_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize) -> Vec<Tile> {
+fn parse_world_l12_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize) -> FileSystemResult<Vec<Tile>> {
     let mut tiles = vec![Tile::default(); tile_count];
 
     for tile in tiles.iter_mut() {
@@ -319,7 +318,7 @@ fn parse_world_l12_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize,
             // priority: 1 bit
             // flip x: 1 bit
             // flip y: 1 bit
-            let data1 = data.read_u16::<LittleEndian>().unwrap();
+            let data1 = data.read_u16::<LittleEndian>()?;
             if data1 & 0x2000 > 0 {
                 flags |= MapChipFlags::PRIORITY;
             }
@@ -338,19 +337,19 @@ fn parse_world_l12_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize,
         }
     }
 
-    tiles
+    Ok(tiles)
 }
 
 // Read tile assembly data for world layer 3 tiles.
 //
 // These are the same as the layer 1 or 2 assembly, except they are stored in order from left to
 // right, top to bottom.
-fn parse_world_l3_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize) -> Vec<Tile> {
+fn parse_world_l3_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize, palette_size: usize) -> FileSystemResult<Vec<Tile>> {
     let mut tiles = vec![Tile::default(); tile_count];
     for i in 0..tile_count * 4 {
         let mut flags = MapChipFlags::default();
 
-        let data1 = data.read_u16::<LittleEndian>().unwrap();
+        let data1 = data.read_u16::<LittleEndian>()?;
         if data1 & 0x2000 > 0 {
             flags |= MapChipFlags::PRIORITY;
         }
@@ -375,7 +374,7 @@ fn parse_world_l3_tile_assembly(data: &mut Cursor<Vec<u8>>, tile_count: usize, p
         };
     }
 
-    tiles
+    Ok(tiles)
 }
 
 // Unpack linear chip graphics data into 8x8 chip bitmaps.
@@ -1,16 +1,17 @@
+use crate::filesystem::error::{FileSystemError, FileSystemResult};
 use crate::filesystem::filesystem::FileSystem;
 use crate::software_renderer::bitmap::Bitmap;
 use crate::software_renderer::blit::{blit_bitmap_to_bitmap, BitmapBlitFlags};
 use crate::ui_theme::UiTheme;
 
 impl FileSystem {
-    pub fn read_ui_theme(&self, ui_theme_index: usize) -> UiTheme {
+    pub fn read_ui_theme(&self, ui_theme_index: usize) -> FileSystemResult<UiTheme> {
         if ui_theme_index > 7 {
-            panic!("UI theme index must be from 0 to 7.");
+            return Err(FileSystemError::IndexOutOfRange { table: "UI themes", index: ui_theme_index, count: 8 });
         }
 
-        let (ui_src_bitmap, ui_palette) = self.backend.get_ui_theme_window_graphics(ui_theme_index);
-        let (cursor_src_bitmap, cursor_palette) = self.backend.get_ui_theme_cursor_graphics();
+        let (ui_src_bitmap, ui_palette) = self.backend.get_ui_theme_window_graphics(ui_theme_index)?;
+        let (cursor_src_bitmap, cursor_palette) = self.backend.get_ui_theme_cursor_graphics()?;
 
         let mut window_bitmap = Bitmap::new(96, 128);
 
@@ -43,6 +44,6 @@ impl FileSystem {
         blit_bitmap_to_bitmap(&cursor_src_bitmap, &mut cursor_bitmap, 32, 0, 16, 8, 16, 0, BitmapBlitFlags::empty());
         blit_bitmap_to_bitmap(&cursor_src_bitmap, &mut cursor_bitmap, 48, 0, 16, 8, 16, 8, BitmapBlitFlags::empty());
 
-        UiTheme::new(window_bitmap, ui_palette, cursor_bitmap, cursor_palette)
+        Ok(UiTheme::new(window_bitmap, ui_palette, cursor_bitmap, cursor_palette))
     }
 }
@@ -5,6 +5,7 @@ use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
 use crate::destination::Destination;
 use crate::facing::Facing;
+use crate::filesystem::error::FileSystemResult;
 use crate::filesystem::filesystem::FileSystem;
 use crate::GameMode;
 use crate::util::vec2di32::Vec2Di32;
@@ -64,45 +65,56 @@ struct ExitData {
 
 impl FileSystem {
 
-    pub fn read_world(&self, index: usize) -> World {
+    // Read the index of the script a world runs.
+    pub fn read_world_script_index(&self, index: usize) -> FileSystemResult<usize> {
+        Ok(self.read_world_header(index)?.script_index)
+    }
+
+    fn read_world_header(&self, index: usize) -> FileSystemResult<WorldHeader> {
         let mut header = WorldHeader::default();
-        let mut data = self.backend.get_world_header_data(index);
+        let mut data = self.backend.get_world_header_data(index)?;
 
         for chip_l12 in header.chips_l12.iter_mut() {
-            *chip_l12 = data.read_u8().unwrap() as usize;
+            *chip_l12 = data.read_u8()? as usize;
         }
         for chip_l3 in header.chips_l3.iter_mut() {
-            *chip_l3 = data.read_u8().unwrap() as usize;
+            *chip_l3 = data.read_u8()? as usize;
         }
 
-        header.palette_index = data.read_u8().unwrap() as usize;
-        header.palette_anim_index = data.read_u8().unwrap() as usize;
+        header.palette_index = data.read_u8()? as usize;
+        header.palette_anim_index = data.read_u8()? as usize;
 
         for sprite_set in header.sprite_sets.iter_mut() {
-            *sprite_set = data.read_u8().unwrap() as usize;
+            *sprite_set = data.read_u8()? as usize;
         }
 
-        header.assembly_l12 = data.read_u8().unwrap() as usize;
-        header.map_index = data.read_u8().unwrap() as usize;
-        header.map_props_index = data.read_u8().unwrap() as usize;
-        header.music_props_index = data.read_u8().unwrap() as usize;
-        header.assembly_l3 = data.read_u8().unwrap() as usize;
-        header.exits_index = data.read_u8().unwrap() as usize;
-        header.script_index = data.read_u8().unwrap() as usize;
+        header.assembly_l12 = data.read_u8()? as usize;
+        header.map_index = data.read_u8()? as usize;
+        header.map_props_index = data.read_u8()? as usize;
+        header.music_props_index = data.read_u8()? as usize;
+        header.assembly_l3 = data.read_u8()? as usize;
+        header.exits_index = data.read_u8()? as usize;
+        header.script_index = data.read_u8()? as usize;
 
         if matches!(self.mode, GameMode::Pc) {
             header.palette_anim_index = index;
         }
 
-        let tileset_l12 = self.read_world_tileset_layer12(header.chips_l12, header.assembly_l12);
-        let tileset_l3 = self.read_world_tileset_layer3(header.chips_l3[0], header.assembly_l3);
-        let (world_map, map) = self.read_world_map(header.map_index, header.map_props_index, header.music_props_index, &tileset_l12, &tileset_l3);
-        let palette = self.read_world_palette(header.palette_index);
-        let palette_anim = self.read_world_palette_anim_data(header.palette_anim_index);
-        let (exits, triggers, scripted_exit_offsets) = self.read_world_exits_triggers(header.exits_index);
-        let script_data = self.backend.get_world_script_data(header.script_index);
+        Ok(header)
+    }
+
+    pub fn read_world(&self, index: usize) -> FileSystemResult<World> {
+        let header = self.read_world_header(index)?;
+
+        let tileset_l12 = self.read_world_tileset_layer12(header.chips_l12, header.assembly_l12)?;
+        let tileset_l3 = self.read_world_tileset_layer3(header.chips_l3[0], header.assembly_l3)?;
+        let (world_map, map) = self.read_world_map(header.map_index, header.map_props_index, header.music_props_index, &tileset_l12, &tileset_l3)?;
+        let palette = self.read_world_palette(header.palette_index)?;
+        let palette_anim = self.read_world_palette_anim_data(header.palette_anim_index)?;
+        let (exits, triggers, scripted_exit_offsets) = self.read_world_exits_triggers(header.exits_index)?;
+        let script_data = self.backend.get_world_script_data(header.script_index)?;
 
-        World {
+        Ok(World {
             index,
 
             tileset_l12,
@@ -118,16 +130,16 @@ impl FileSystem {
             triggers,
             script_offsets: scripted_exit_offsets,
             script_data,
-        }
+        })
     }
 
     // Read world exit data.
-    fn read_world_exits_triggers(&self, exits_index: usize) -> (Vec<WorldExit>, Vec<WorldTrigger>, Vec<u64>) {
-        let mut data = self.backend.get_world_exits_triggers_data(exits_index);
+    fn read_world_exits_triggers(&self, exits_index: usize) -> FileSystemResult<(Vec<WorldExit>, Vec<WorldTrigger>, Vec<u64>)> {
+        let mut data = self.backend.get_world_exits_triggers_data(exits_index)?;
 
         // Exits to other locations.
         let mut exits = Vec::<WorldExit>::new();
-        let exit_count = data.read_u8().unwrap() as usize;
+        let exit_count = data.read_u8()? as usize;
         for exit_index in 0..exit_count {
             let mut exit_data = ExitData::default();
 
@@ -135,21 +147,21 @@ impl FileSystem {
             // data in the last 7 bits.
             match self.mode {
                 GameMode::Snes => {
-                    exit_data.x = data.read_u8().unwrap();
-                    exit_data.y = data.read_u8().unwrap();
-                    exit_data.name_index = data.read_u8().unwrap();
-                    exit_data.scene_index = data.read_u16::<LittleEndian>().unwrap();
-                    exit_data.scene_tile_x = data.read_u8().unwrap() as i32;
-                    exit_data.scene_tile_y = data.read_u8().unwrap() as i32;
+                    exit_data.x = data.read_u8()?;
+                    exit_data.y = data.read_u8()?;
+                    exit_data.name_index = data.read_u8()?;
+                    exit_data.scene_index = data.read_u16::<LittleEndian>()?;
+                    exit_data.scene_tile_x = data.read_u8()? as i32;
+                    exit_data.scene_tile_y = data.read_u8()? as i32;
                 },
                 GameMode::Pc => {
-                    exit_data.x = data.read_u8().unwrap();
-                    exit_data.y = data.read_u8().unwrap();
-                    exit_data.name_index = data.read_u8().unwrap();
-                    exit_data.scene_index = data.read_u16::<LittleEndian>().unwrap();
-                    exit_data.scene_facing = data.read_u8().unwrap();
-                    exit_data.scene_tile_x = data.read_u8().unwrap() as i32;
-                    exit_data.scene_tile_y = data.read_u8().unwrap() as i32;
+                    exit_data.x = data.read_u8()?;
+                    exit_data.y = data.read_u8()?;
+                    exit_data.name_index = data.read_u8()?;
+                    exit_data.scene_index = data.read_u16::<LittleEndian>()?;
+                    exit_data.scene_facing = data.read_u8()?;
+                    exit_data.scene_tile_x = data.read_u8()? as i32;
+                    exit_data.scene_tile_y = data.read_u8()? as i32;
                 }
             }
 
@@ -221,11 +233,11 @@ impl FileSystem {
         // Cave is such a trigger, activated when the Heckran Cave exits the player at it's
         // coordinates. Once activated, they are disabled.
         let mut triggers = Vec::<WorldTrigger>::new();
-        let trigger_count = data.read_u8().unwrap() as usize;
+        let trigger_count = data.read_u8()? as usize;
         for trigger_index in 0..trigger_count {
-            let x = data.read_u8().unwrap() as usize;
-            let y = data.read_u8().unwrap() as usize;
-            let script_address_index = data.read_u8().unwrap() as usize;
+            let x = data.read_u8()? as usize;
+            let y = data.read_u8()? as usize;
+            let script_address_index = data.read_u8()? as usize;
 
             // End at null entry.
             if x == 0 && y == 0 && script_address_index == 0 {
@@ -247,20 +259,20 @@ impl FileSystem {
         // This starts overworld action 4 with the script address put into $1B47/$1B48.
         // Overworld action 4 seems to be an infinite data copy loop.
         // No overworld map uses this, it might be vestigial.
-        let unknown_count = data.read_u8().unwrap() as i64;
-        data.seek(SeekFrom::Current(unknown_count * 3)).unwrap();
+        let unknown_count = data.read_u8()? as i64;
+        data.seek(SeekFrom::Current(unknown_count * 3))?;
 
         // World script addresses. Scripted exits and triggers can refer to these by index.
         let mut script_addresses = Vec::<u64>::new();
-        let script_address_count = data.read_u8().unwrap() as usize;
+        let script_address_count = data.read_u8()? as usize;
         for _ in 0..script_address_count {
-            let mut address = data.read_u16::<LittleEndian>().unwrap() as u64;
+            let mut address = data.read_u16::<LittleEndian>()? as u64;
             if address > 0 {
                 address -= 0x400;
             }
             script_addresses.push(address);
         }
 
-        (exits, triggers, script_addresses)
+        Ok((exits, triggers, script_addresses))
     }
 }
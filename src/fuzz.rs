@@ -0,0 +1,98 @@
+use std::io::{Cursor, ErrorKind};
+use byteorder::ReadBytesExt;
+use crate::GameMode;
+use crate::scene_script::scene_script_decoder;
+use crate::world_script::world_script_decoder;
+
+const MODES: [GameMode; 2] = [GameMode::Snes, GameMode::Pc];
+
+/// Decodes arbitrary data as scene script ops in every game mode. Used by the fuzz targets, this
+/// must never panic.
+pub fn fuzz_scene_ops(data: &[u8]) {
+    for mode in MODES {
+        let mut cursor = Cursor::new(data.to_vec());
+        while let Ok(op_byte) = cursor.read_u8() {
+            match scene_script_decoder::op_decode_opcode(op_byte, &mut cursor, mode) {
+                Ok(_) => {},
+                Err(error) if error.kind() == ErrorKind::Unsupported => {},
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Decodes arbitrary data as world script ops in every game mode. Used by the fuzz targets, this
+/// must never panic.
+pub fn fuzz_world_ops(data: &[u8]) {
+    for mode in MODES {
+        let mut cursor = Cursor::new(data.to_vec());
+        while let Ok(op_byte) = cursor.read_u8() {
+            match world_script_decoder::op_decode_opcode(op_byte, &mut cursor, mode) {
+                Ok(_) => {},
+                Err(error) if error.kind() == ErrorKind::Unsupported => {},
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Xorshift, so that failures are reproducible without extra dependencies.
+    struct TestRandom {
+        state: u64,
+    }
+
+    impl TestRandom {
+        fn next(&mut self) -> u64 {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            self.state
+        }
+
+        fn bytes(&mut self, count: usize) -> Vec<u8> {
+            (0..count).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    // Every opcode followed by every length of random operand data, then random streams.
+    fn test_inputs() -> Vec<Vec<u8>> {
+        let mut random = TestRandom { state: 0x2545F4914F6CDD1D };
+        let mut inputs = Vec::new();
+
+        for op_byte in 0..=255u8 {
+            for _ in 0..8 {
+                let operands = random.bytes(24);
+                for length in 0..=operands.len() {
+                    let mut input = vec![op_byte];
+                    input.extend_from_slice(&operands[..length]);
+                    inputs.push(input);
+                }
+            }
+        }
+
+        for _ in 0..2000 {
+            let length = (random.next() % 256) as usize;
+            inputs.push(random.bytes(length));
+        }
+
+        inputs
+    }
+
+    #[test]
+    fn scene_ops_random_bytes() {
+        for input in test_inputs() {
+            fuzz_scene_ops(&input);
+        }
+    }
+
+    #[test]
+    fn world_ops_random_bytes() {
+        for input in test_inputs() {
+            fuzz_world_ops(&input);
+        }
+    }
+}
@@ -9,4 +9,5 @@ pub trait GameStateTrait {
     fn mouse_motion(&mut self, ctx: &Context, x: i32, y: i32);
     fn dump(&mut self, ctx: &Context);
     fn set_debug_mode(&mut self, mode: bool);
+    fn language_changed(&mut self, ctx: &mut Context);
 }
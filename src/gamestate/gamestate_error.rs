@@ -0,0 +1,124 @@
+use sdl3::event::Event;
+use crate::{Context, GameEvent};
+use crate::destination::Destination;
+use crate::facing::Facing;
+use crate::filesystem::error::FileSystemError;
+use crate::gamestate::gamestate::GameStateTrait;
+use crate::input::InputAction;
+use crate::l10n::IndexedType;
+use crate::renderer::{TextFlags, TextFont, TextRenderable};
+use crate::scene::scene::SCENE_COUNT;
+use crate::software_renderer::text::TextDrawFlags;
+use crate::util::vec2di32::Vec2Di32;
+
+const ERROR_WINDOW_MARGIN: i32 = 2;
+const ERROR_TEXT_COLOR: [u8; 4] = [231, 231, 231, 255];
+
+/// Shown when a scene or world could not be loaded. Lets the user pick another scene to go to, or
+/// go to the first world.
+pub struct GameStateError {
+    destination: Destination,
+    message: String,
+    target_scene: usize,
+    text: TextRenderable,
+
+    next_game_event: Option<GameEvent>,
+}
+
+impl GameStateError {
+    pub fn new(ctx: &mut Context, destination: Destination, error: &FileSystemError) -> GameStateError {
+        println!("Could not load {}: {}", destination.as_string(), error);
+        ctx.screen_fade.set(1.0);
+
+        let target_scene = match destination {
+            Destination::Scene { index, .. } => index,
+            Destination::World { .. } => 0,
+        };
+
+        let mut state = GameStateError {
+            destination,
+            message: error.to_string(),
+            target_scene,
+            text: TextRenderable::new(String::new(), TextFont::Regular, ERROR_TEXT_COLOR, TextDrawFlags::SHADOW, 0),
+
+            next_game_event: None,
+        };
+        state.update_text(ctx);
+
+        state
+    }
+
+    fn update_text(&mut self, ctx: &Context) {
+        let wrap_width = ctx.render.target.width as i32 - (ERROR_WINDOW_MARGIN + 1) * 16;
+        let text = format!(
+            "Could not load {} - {}.\n{}\n\nLeft/right: select scene {} - {}\nActivate: go to scene\nMap: go to world 0",
+            self.destination.as_string(),
+            self.destination.title(ctx),
+            self.message,
+            self.target_scene,
+            ctx.l10n.get_indexed(IndexedType::Scene, self.target_scene),
+        );
+        self.text = TextRenderable::new(text, TextFont::Regular, ERROR_TEXT_COLOR, TextDrawFlags::SHADOW, wrap_width);
+    }
+}
+
+impl GameStateTrait for GameStateError {
+    fn tick(&mut self, ctx: &mut Context, _delta: f64) -> Option<GameEvent> {
+        if ctx.input.was_pressed(InputAction::MoveLeft) {
+            self.target_scene = (self.target_scene + SCENE_COUNT - 1) % SCENE_COUNT;
+            self.update_text(ctx);
+        } else if ctx.input.was_pressed(InputAction::MoveRight) {
+            self.target_scene = (self.target_scene + 1) % SCENE_COUNT;
+            self.update_text(ctx);
+        }
+
+        if ctx.input.was_pressed(InputAction::Activate) {
+            self.next_game_event = Some(GameEvent::GotoDestination {
+                destination: Destination::Scene {
+                    index: self.target_scene,
+                    pos: Vec2Di32::new(128, 112),
+                    facing: Facing::Down,
+                    data: 0,
+                },
+                fade_in: true,
+            });
+        } else if ctx.input.was_pressed(InputAction::OpenMap) {
+            self.next_game_event = Some(GameEvent::GotoDestination {
+                destination: Destination::World {
+                    index: 0,
+                    pos: Vec2Di32::new(504, 448),
+                    data: 0,
+                },
+                fade_in: true,
+            });
+        }
+
+        self.next_game_event.take()
+    }
+
+    fn render(&mut self, ctx: &mut Context, _lerp: f64) {
+        let chip_width = ctx.render.target.width as i32 / 8 - ERROR_WINDOW_MARGIN * 2;
+        let chip_height = ctx.render.target.height as i32 / 8 - ERROR_WINDOW_MARGIN * 2;
+        let x = ERROR_WINDOW_MARGIN * 8;
+        let y = ERROR_WINDOW_MARGIN * 8;
+
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, y, chip_width, chip_height);
+        ctx.render.render_text(&mut self.text, x + 8, y + 8, TextFlags::empty());
+    }
+
+    fn get_title(&self, _ctx: &Context) -> String {
+        format!("Error loading {}", self.destination.as_string())
+    }
+
+    fn event(&mut self, _ctx: &mut Context, _event: &Event) {}
+
+    fn mouse_motion(&mut self, _ctx: &Context, _x: i32, _y: i32) {}
+
+    fn dump(&mut self, _ctx: &Context) {}
+
+    fn set_debug_mode(&mut self, _mode: bool) {}
+
+    fn language_changed(&mut self, ctx: &mut Context) {
+        self.update_text(ctx);
+    }
+}
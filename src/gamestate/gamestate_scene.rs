@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::io::Cursor;
 use sdl3::event::Event;
 use sdl3::mouse::MouseButton;
+use crate::filesystem::error::FileSystemResult;
 use crate::camera::Camera;
 use crate::{Context, GameEvent};
 use crate::scene::actor::{SceneActor, SceneActorClass, SceneActorFlags, SceneActorTask, DrawMode};
@@ -15,6 +16,7 @@ use crate::map_renderer::MapRenderer;
 use crate::next_destination::NextDestination;
 use crate::party::character::CharacterId;
 use crate::renderer::{TextFlags, TextFont, TextRenderable};
+use crate::scene::special_dialogue::SpecialDialogueTrait;
 use crate::scene::textbox::TextBox;
 use crate::scene::scene::Scene;
 use crate::scene::scene_map::SceneMap;
@@ -28,10 +30,16 @@ use crate::util::vec2di32::Vec2Di32;
 
 /// Mutable state for a scene.
 pub struct SceneState {
+    pub scene_index: usize,
     pub script_data: Cursor<Vec<u8>>,
     pub script_states: Vec<ActorScriptState>,
     pub textbox: TextBox,
     pub textbox_strings: Vec<String>,
+    pub textbox_strings_address: Option<usize>,
+
+    /// Shop, name entry or other dialogue opened by a script, if any.
+    pub special_dialogue: Option<Box<dyn SpecialDialogueTrait>>,
+
     pub actors: Vec<SceneActor>,
     pub player_actors: HashMap<CharacterId, usize>,
     pub next_destination: NextDestination,
@@ -64,14 +72,15 @@ pub struct GameStateScene {
 }
 
 impl GameStateScene {
-    pub fn new(ctx: &mut Context, scene_index: usize, pos: Vec2Df64, facing: Facing, fade_in: bool) -> GameStateScene {
-        let scene = ctx.fs.read_scene(scene_index);
+    pub fn new(ctx: &mut Context, scene_index: usize, pos: Vec2Df64, facing: Facing, fade_in: bool) -> FileSystemResult<GameStateScene> {
+        let scene = ctx.fs.read_scene(scene_index)?;
         println!("Entering scene {}: {}", scene.index, ctx.l10n.get_indexed(IndexedType::Scene, scene.index));
 
         ctx.memory.clear_local();
 
         // Create new shared scene state.
         let mut state = SceneState {
+            scene_index: scene.index,
             next_destination: NextDestination::new(),
             camera: Camera::new(
                 scene.scroll_mask.left as f64, scene.scroll_mask.top as f64,
@@ -81,6 +90,8 @@ impl GameStateScene {
             ),
             textbox: TextBox::new(ctx),
             textbox_strings: Vec::new(),
+            textbox_strings_address: None,
+            special_dialogue: None,
             actors: Vec::new(),
             player_actors: HashMap::new(),
             script_data: Cursor::new(scene.script.get_data().clone()),
@@ -131,7 +142,7 @@ impl GameStateScene {
             actor.update_sprite_state(sprite_state, &ctx.assets);
         }
 
-        GameStateScene {
+        Ok(GameStateScene {
             scene,
             state,
 
@@ -148,7 +159,15 @@ impl GameStateScene {
             debug_actor: None,
 
             next_game_event: None,
-        }
+        })
+    }
+
+    pub fn get_scene_index(&self) -> usize {
+        self.scene.index
+    }
+
+    pub fn get_state(&self) -> &SceneState {
+        &self.state
     }
 }
 
@@ -164,12 +183,25 @@ impl GameStateTrait for GameStateScene {
             self.state.camera.pos = self.state.actors[debug_actor].pos - Vec2Df64::new(self.state.camera.size.x - 64.0, self.state.camera.size.y / 2.0);
         }
 
-        self.process_input(ctx, delta);
+        // Special dialogues take all input while open.
+        if let Some(mut dialogue) = self.state.special_dialogue.take() {
+            if dialogue.tick(ctx, &mut self.state) {
+                self.state.special_dialogue = Some(dialogue);
+            }
+        } else {
+            self.process_input(ctx, delta);
+        }
 
         self.scene.script.run(ctx, &mut self.state);
 
         self.state.camera.clamp();
 
+        // Place automatically positioned textboxes relative to the speaking actor, once per page.
+        if let Some(actor_index) = self.state.textbox.get_source_actor_index() {
+            let actor_screen_y = self.state.actors[actor_index].pos.y - self.state.camera.pos.y;
+            self.state.textbox.update_page_position(actor_screen_y);
+        }
+
         for (index, actor) in self.state.actors.iter_mut().enumerate() {
             actor.tick(delta, &self.state.scene_map, &ctx.assets);
             let state = ctx.sprite_states.get_state_mut(index);
@@ -303,12 +335,22 @@ impl GameStateTrait for GameStateScene {
                     op,
                 );
 
-                let mut header = TextRenderable::new(format!("{}\n\n{}", text_actor, text_script), TextFont::Small, [255, 255, 255, 255], TextDrawFlags::empty(), 124);
+                // Where this actor's textbox is shown, and where it was requested.
+                let text_textbox = if self.state.textbox.get_source_actor_index() == Some(debug_actor) {
+                    format!("Textbox {:?} ({:?})", self.state.textbox.get_page_position(), self.state.textbox.get_position())
+                } else {
+                    "No textbox".to_string()
+                };
+
+                let mut header = TextRenderable::new(format!("{}\n\n{}\n\n{}", text_actor, text_script, text_textbox), TextFont::Small, [255, 255, 255, 255], TextDrawFlags::empty(), 124);
                 ctx.render.render_text(&mut header, 2, 2, TextFlags::empty());
             }
         }
 
         self.state.textbox.render(ctx, lerp);
+        if let Some(dialogue) = &mut self.state.special_dialogue {
+            dialogue.render(ctx);
+        }
     }
 
     fn get_title(&self, ctx: &Context) -> String {
@@ -489,6 +531,15 @@ impl GameStateTrait for GameStateScene {
             self.map_renderer.layer_enabled = LayerFlags::all();
         }
     }
+
+    fn language_changed(&mut self, ctx: &mut Context) {
+        let Some(address) = self.state.textbox_strings_address else {
+            return;
+        };
+        if let Err(error) = ctx.fs.read_textbox_string_table(address, ctx.l10n.get_language(), &mut self.state.textbox_strings) {
+            println!("Could not read textbox string table at 0x{:06X}: {}", address, error);
+        }
+    }
 }
 
 impl GameStateScene {
@@ -1,6 +1,7 @@
 use std::io::Cursor;
 use sdl3::event::Event;
 use sdl3::mouse::MouseButton;
+use crate::filesystem::error::FileSystemResult;
 use crate::camera::Camera;
 use crate::{Context, GameEvent};
 use crate::game_palette::GamePalette;
@@ -70,21 +71,21 @@ pub struct GameStateWorld {
 }
 
 impl GameStateWorld {
-    pub fn new(ctx: &mut Context, world_index: usize, pos: Vec2Df64, fade_in: bool) -> GameStateWorld {
+    pub fn new(ctx: &mut Context, world_index: usize, pos: Vec2Df64, fade_in: bool) -> FileSystemResult<GameStateWorld> {
         println!("Entering world {}: {}", world_index, ctx.l10n.get_indexed(IndexedType::World, world_index));
 
-        let mut world = ctx.fs.read_world(world_index);
+        let mut world = ctx.fs.read_world(world_index)?;
 
         // Initialize sprites.
-        let mut sprites = WorldSprites::new(ctx, world_index, world.sprite_graphics);
+        let mut sprites = WorldSprites::new(ctx, world_index, world.sprite_graphics)?;
         let character_ids: Vec<CharacterId> = ctx.party.get_active_party_slots().map(|x| x.character_id).collect();
         for (index, id) in character_ids.iter().enumerate() {
-            sprites.load_player_sprites(ctx, index, *id);
+            sprites.load_player_sprites(ctx, index, *id)?;
         }
 
         // Initialize world and player palettes.
         let mut palette = world.palette.clone();
-        let player_palettes = ctx.fs.backend.get_world_player_palettes();
+        let player_palettes = ctx.fs.backend.get_world_player_palettes()?;
         for (index, slot) in ctx.party.get_active_party_slots().enumerate() {
             let dest = 192 + index * 16;
             let src = slot.character_id * 16;
@@ -120,7 +121,7 @@ impl GameStateWorld {
             palette_animation: world.palette_anim.clone(),
             exits: world.exits.clone(),
             triggers: world.triggers.clone(),
-            animations: ctx.fs.read_world_animation_script(),
+            animations: ctx.fs.read_world_animation_script()?,
             sprites,
             script_data: Cursor::new(world.script_data.clone()),
         };
@@ -139,7 +140,7 @@ impl GameStateWorld {
             ctx.screen_fade.set(1.0);
         }
 
-        GameStateWorld {
+        Ok(GameStateWorld {
             world,
             state,
 
@@ -155,7 +156,7 @@ impl GameStateWorld {
             debug_box: None,
 
             next_game_event: None,
-        }
+        })
     }
 }
 
@@ -328,6 +329,8 @@ impl GameStateTrait for GameStateWorld {
             self.map_renderer.layer_enabled = LayerFlags::all();
         }
     }
+
+    fn language_changed(&mut self, _ctx: &mut Context) {}
 }
 
 impl GameStateWorld {
@@ -1,4 +1,5 @@
 pub mod gamestate_scene;
 pub mod gamestate_world;
+pub mod gamestate_error;
 
 pub mod gamestate;
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::{Context, GameEvent, UPDATE_INTERVAL};
+use crate::facing::Facing;
+use crate::gamestate::gamestate::GameStateTrait;
+use crate::gamestate::gamestate_scene::GameStateScene;
+use crate::input::InputAction;
+use crate::memory::MemoryRegion;
+use crate::util::json::JsonValue;
+use crate::util::vec2df64::Vec2Df64;
+
+/// Options for running a scene without a visible window.
+pub struct SceneRunOptions {
+    pub scene_index: usize,
+    pub ticks: usize,
+    pub input_path: Option<PathBuf>,
+    pub output_path: PathBuf,
+    pub framebuffer_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputEventKind {
+    Down,
+    Up,
+}
+
+struct InputEvent {
+    tick: usize,
+    action: InputAction,
+    kind: InputEventKind,
+}
+
+/// Loads a scene, runs it for a fixed number of ticks and writes a snapshot of the resulting state.
+pub fn run_scene_headless(ctx: &mut Context, options: &SceneRunOptions) -> Result<(), String> {
+    let events = match &options.input_path {
+        Some(path) => read_input_script(path)?,
+        None => Vec::new(),
+    };
+
+    let mut gamestate = GameStateScene::new(ctx, options.scene_index, Vec2Df64::new(128.0, 112.0), Facing::Down, true)
+        .map_err(|error| format!("Could not load scene {}: {}", options.scene_index, error))?;
+    ctx.dialogue_log.set_location(gamestate.get_title(ctx));
+
+    let mut event_index = 0;
+    let mut ticks_run = 0;
+    let mut destination = None;
+    while ticks_run < options.ticks {
+
+        // Apply scripted input for this tick.
+        while event_index < events.len() && events[event_index].tick == ticks_run {
+            let event = &events[event_index];
+            match event.kind {
+                InputEventKind::Down => ctx.input.action_down(event.action),
+                InputEventKind::Up => ctx.input.action_up(event.action),
+            }
+            event_index += 1;
+        }
+
+        ctx.screen_fade.tick(UPDATE_INTERVAL);
+        ctx.dialogue_log.tick(UPDATE_INTERVAL);
+        let game_event = gamestate.tick(ctx, UPDATE_INTERVAL);
+        ctx.input.clear();
+        ticks_run += 1;
+
+        // Stop when the scene is left, the snapshot only covers a single scene.
+        if let Some(GameEvent::GotoDestination { destination: next, .. }) = game_event {
+            println!("Scene exited to {} after {} ticks.", next.as_string(), ticks_run);
+            destination = Some(next.as_string());
+            break;
+        }
+    }
+
+    ctx.render.clear();
+    gamestate.render(ctx, 1.0);
+
+    if let Some(path) = &options.framebuffer_path {
+        ctx.render.target.write_to_bmp(path);
+    }
+
+    let snapshot = JsonValue::object()
+        .with("version", 1usize)
+        .with("scene", gamestate.get_scene_index())
+        .with("ticks", ticks_run)
+        .with("destination", destination.map_or(JsonValue::Null, JsonValue::from))
+        .with("memory", memory_snapshot(ctx))
+        .with("actors", actor_snapshot(&gamestate))
+        .with("framebuffer", JsonValue::object()
+            .with("width", ctx.render.target.width)
+            .with("height", ctx.render.target.height)
+            .with("hash", format!("{:016X}", hash_fnv1a(&ctx.render.target.data)))
+        );
+
+    fs::write(&options.output_path, snapshot.to_string_pretty())
+        .map_err(|e| format!("Could not write snapshot to {}: {}", options.output_path.display(), e))?;
+    println!("Wrote snapshot of scene {} after {} ticks to {}.", gamestate.get_scene_index(), ticks_run, options.output_path.display());
+
+    Ok(())
+}
+
+// Each line contains a tick, an input action name and optionally "down", "up" or "press" (the
+// default). A press releases the action again on the next tick. Lines starting with # are ignored.
+fn read_input_script(path: &Path) -> Result<Vec<InputEvent>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read input script {}: {}", path.display(), e))?;
+
+    let mut events = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(format!("{}:{}: expected \"<tick> <action> [down|up|press]\".", path.display(), line_index + 1));
+        }
+
+        let tick = parts[0].parse::<usize>()
+            .map_err(|_| format!("{}:{}: invalid tick \"{}\".", path.display(), line_index + 1, parts[0]))?;
+        let action = InputAction::from_name(parts[1])
+            .ok_or_else(|| format!("{}:{}: unknown input action \"{}\".", path.display(), line_index + 1, parts[1]))?;
+
+        match parts.get(2).copied().unwrap_or("press") {
+            "down" => events.push(InputEvent { tick, action, kind: InputEventKind::Down }),
+            "up" => events.push(InputEvent { tick, action, kind: InputEventKind::Up }),
+            "press" => {
+                events.push(InputEvent { tick, action, kind: InputEventKind::Down });
+                events.push(InputEvent { tick: tick + 1, action, kind: InputEventKind::Up });
+            },
+            other => return Err(format!("{}:{}: unknown input event \"{}\".", path.display(), line_index + 1, other)),
+        }
+    }
+
+    // Stable sort keeps the order of events within the same tick.
+    events.sort_by_key(|event| event.tick);
+
+    Ok(events)
+}
+
+fn memory_snapshot(ctx: &Context) -> JsonValue {
+    JsonValue::object()
+        .with("system", region_to_hex(&ctx.memory.system))
+        .with("global", region_to_hex(&ctx.memory.global))
+        .with("local", region_to_hex(&ctx.memory.local))
+        .with("extended", region_to_hex(&ctx.memory.extended))
+}
+
+fn region_to_hex(region: &MemoryRegion) -> String {
+    region.get_data().iter().map(|x| format!("{:02X}", x)).collect()
+}
+
+fn actor_snapshot(gamestate: &GameStateScene) -> JsonValue {
+    let state = gamestate.get_state();
+
+    let mut actors = Vec::new();
+    for (index, actor) in state.actors.iter().enumerate() {
+        let script_state = &state.script_states[index];
+        actors.push(JsonValue::object()
+            .with("index", index)
+            .with("class", format!("{:?}", actor.class))
+            .with("pos", vec![actor.pos.x, actor.pos.y])
+            .with("facing", format!("{:?}", actor.facing))
+            .with("draw_mode", format!("{:?}", actor.draw_mode))
+            .with("flags", actor.flags.bits())
+            .with("sprite_frame", actor.sprite_frame)
+            .with("anim_index", actor.anim_index)
+            .with("anim_frame", actor.anim_frame)
+            .with("script_address", script_state.current_address as usize)
+            .with("script_priority", script_state.current_priority)
+        );
+    }
+
+    JsonValue::Array(actors)
+}
+
+// 64 bit FNV-1a, good enough to detect framebuffer changes.
+fn hash_fnv1a(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xCBF29CE484222325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001B3);
+    }
+
+    hash
+}
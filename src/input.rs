@@ -12,6 +12,7 @@ pub enum InputAction {
     OpenMap,
     OpenSettingsMenu,
     OpenPartyMenu,
+    NextLanguage,
 
     // Menu
     MenuPrevious,
@@ -20,6 +21,8 @@ pub enum InputAction {
     MenuDown,
     MenuLeft,
     MenuRight,
+    MenuConfirm,
+    MenuCancel,
 
     // World interaction
     MoveUp,
@@ -34,6 +37,12 @@ pub enum InputAction {
     DialogueChoiceNext,
     DialogueChoiceConfirm,
 
+    // Dialogue log
+    ToggleDialogueLog,
+    DialogueLogScrollUp,
+    DialogueLogScrollDown,
+    ExportDialogueLog,
+
     // Debug mode
     ToggleDebug,
 
@@ -63,6 +72,63 @@ pub enum InputAction {
     DebugDump,
 }
 
+impl InputAction {
+    pub fn from_name(name: &str) -> Option<InputAction> {
+        match name {
+            "Exit" => Some(InputAction::Exit),
+            "TogglePause" => Some(InputAction::TogglePause),
+            "OpenMap" => Some(InputAction::OpenMap),
+            "OpenSettingsMenu" => Some(InputAction::OpenSettingsMenu),
+            "OpenPartyMenu" => Some(InputAction::OpenPartyMenu),
+            "NextLanguage" => Some(InputAction::NextLanguage),
+            "MenuPrevious" => Some(InputAction::MenuPrevious),
+            "MenuNext" => Some(InputAction::MenuNext),
+            "MenuUp" => Some(InputAction::MenuUp),
+            "MenuDown" => Some(InputAction::MenuDown),
+            "MenuLeft" => Some(InputAction::MenuLeft),
+            "MenuRight" => Some(InputAction::MenuRight),
+            "MenuConfirm" => Some(InputAction::MenuConfirm),
+            "MenuCancel" => Some(InputAction::MenuCancel),
+            "MoveUp" => Some(InputAction::MoveUp),
+            "MoveDown" => Some(InputAction::MoveDown),
+            "MoveLeft" => Some(InputAction::MoveLeft),
+            "MoveRight" => Some(InputAction::MoveRight),
+            "Activate" => Some(InputAction::Activate),
+            "Run" => Some(InputAction::Run),
+            "DialogueChoicePrevious" => Some(InputAction::DialogueChoicePrevious),
+            "DialogueChoiceNext" => Some(InputAction::DialogueChoiceNext),
+            "DialogueChoiceConfirm" => Some(InputAction::DialogueChoiceConfirm),
+            "ToggleDialogueLog" => Some(InputAction::ToggleDialogueLog),
+            "DialogueLogScrollUp" => Some(InputAction::DialogueLogScrollUp),
+            "DialogueLogScrollDown" => Some(InputAction::DialogueLogScrollDown),
+            "ExportDialogueLog" => Some(InputAction::ExportDialogueLog),
+            "ToggleDebug" => Some(InputAction::ToggleDebug),
+            "DebugCameraUp" => Some(InputAction::DebugCameraUp),
+            "DebugCameraDown" => Some(InputAction::DebugCameraDown),
+            "DebugCameraLeft" => Some(InputAction::DebugCameraLeft),
+            "DebugCameraRight" => Some(InputAction::DebugCameraRight),
+            "DebugToggleLayer1" => Some(InputAction::DebugToggleLayer1),
+            "DebugToggleLayer2" => Some(InputAction::DebugToggleLayer2),
+            "DebugToggleLayer3" => Some(InputAction::DebugToggleLayer3),
+            "DebugToggleSprites" => Some(InputAction::DebugToggleSprites),
+            "DebugTogglePalette" => Some(InputAction::DebugTogglePalette),
+            "DebugOverlaysDisable" => Some(InputAction::DebugOverlaysDisable),
+            "DebugOverlays1" => Some(InputAction::DebugOverlays1),
+            "DebugOverlays2" => Some(InputAction::DebugOverlays2),
+            "DebugOverlays3" => Some(InputAction::DebugOverlays3),
+            "DebugOverlays4" => Some(InputAction::DebugOverlays4),
+            "DebugOverlays5" => Some(InputAction::DebugOverlays5),
+            "DebugOverlays6" => Some(InputAction::DebugOverlays6),
+            "DebugOverlays7" => Some(InputAction::DebugOverlays7),
+            "DebugOverlays8" => Some(InputAction::DebugOverlays8),
+            "DebugOverlays9" => Some(InputAction::DebugOverlays9),
+            "DebugActorStep" => Some(InputAction::DebugActorStep),
+            "DebugDump" => Some(InputAction::DebugDump),
+            _ => None,
+        }
+    }
+}
+
 pub struct InputManager {
     bindings: HashMap<InputAction, Keycode>,
     keycode_to_bindings: HashMap<Keycode, HashSet<InputAction>>,
@@ -134,4 +200,15 @@ impl InputManager {
             }
         }
     }
+
+    // Press or release an action directly, without a bound key.
+    pub fn action_down(&mut self, action: InputAction) {
+        self.inputs_down.insert(action);
+        self.inputs_pressed.insert(action);
+    }
+
+    pub fn action_up(&mut self, action: InputAction) {
+        self.inputs_down.remove(&action);
+        self.inputs_released.insert(action);
+    }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::filesystem::error::FileSystemResult;
 use crate::filesystem::filesystem::FileSystem;
 
 #[repr(usize)]
@@ -7,44 +8,67 @@ pub enum IndexedType {
     Scene,
     World,
     Item,
+    Tech,
 }
 
-static INDEXED_ITER: [IndexedType; 4] = [
+static INDEXED_ITER: [IndexedType; 5] = [
     IndexedType::WorldExit,
     IndexedType::Scene,
     IndexedType::World,
     IndexedType::Item,
+    IndexedType::Tech,
 ];
 
-pub struct L10n<'a> {
-    language: &'a str,
+pub struct L10n {
+    language: String,
+    languages: Vec<String>,
     indexed_strings: Vec<Vec<String>>,
     strings: HashMap<String, String>,
 }
 
-impl L10n<'_> {
-    pub fn new<'a>(language: &'a str, fs: &FileSystem) -> L10n<'a> {
+impl L10n {
+    pub fn new(language: &str, fs: &FileSystem) -> FileSystemResult<L10n> {
+        let languages = fs.read_languages()?;
+
         let mut indexed_strings = Vec::new();
         for index_type in &INDEXED_ITER {
             indexed_strings.push(match index_type {
-                IndexedType::WorldExit => fs.read_world_exit_names(language),
-                IndexedType::Scene => fs.read_scene_names(language),
-                IndexedType::World => fs.read_world_names(language),
-                IndexedType::Item => fs.read_item_names(language),
+                IndexedType::WorldExit => fs.read_world_exit_names(language)?,
+                IndexedType::Scene => fs.read_scene_names(language)?,
+                IndexedType::World => fs.read_world_names(language)?,
+                IndexedType::Item => fs.read_item_names(language)?,
+                IndexedType::Tech => fs.read_tech_names(language)?,
             });
         }
 
-        let strings: HashMap<String, String> = HashMap::new();
+        // Keys are also stored prefixed with the name of their message file, so that keys that
+        // are used by more than one file can still be told apart. Otherwise the first file wins.
+        let mut strings: HashMap<String, String> = HashMap::new();
+        for (table, key, value) in fs.read_keyed_strings(language)? {
+            strings.insert(format!("{}:{}", table, key), value.clone());
+            strings.entry(key).or_insert(value);
+        }
 
-        L10n {
-            language,
+        Ok(L10n {
+            language: language.to_string(),
+            languages,
             indexed_strings,
             strings,
-        }
+        })
     }
 
     pub fn get_language(&self) -> &str {
-        self.language
+        &self.language
+    }
+
+    pub fn get_languages(&self) -> &[String] {
+        &self.languages
+    }
+
+    /// Returns the language that follows the current one, wrapping around.
+    pub fn get_next_language(&self) -> &str {
+        let index = self.languages.iter().position(|language| *language == self.language).unwrap_or(0);
+        self.languages.get((index + 1) % self.languages.len().max(1)).map_or(&self.language, |language| language)
     }
 
     pub fn get_indexed(&self, indexed_type: IndexedType, index: usize) -> String {
@@ -56,7 +80,18 @@ impl L10n<'_> {
         source[index].clone()
     }
 
-    pub fn get_keyed(&self, key: &String) -> String {
-        self.strings.get(key).unwrap_or_else(|| key).clone()
+    /// Returns all strings of a type, ordered by index.
+    pub fn get_indexed_all(&self, indexed_type: IndexedType) -> &[String] {
+        &self.indexed_strings[indexed_type as usize]
+    }
+
+    /// Returns the string for a key, or for "<message file>:<key>" to read it from a specific
+    /// message file. Returns the key itself if it is not found.
+    pub fn get_keyed(&self, key: &str) -> String {
+        self.strings.get(key).map_or_else(|| key.to_string(), |value| value.clone())
+    }
+
+    pub fn has_keyed(&self, key: &str) -> bool {
+        self.strings.contains_key(key)
     }
 }
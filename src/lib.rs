@@ -0,0 +1,92 @@
+#![allow(dead_code)]
+
+use crate::assets::Assets;
+use crate::destination::Destination;
+use crate::dialogue_log::DialogueLog;
+use crate::filesystem::filesystem::FileSystem;
+use crate::input::InputManager;
+use crate::l10n::L10n;
+use crate::memory::Memory;
+use crate::party::party::Party;
+use crate::renderer::Renderer;
+use crate::screen_fade::ScreenFade;
+use crate::settings::Settings;
+use crate::sprites::sprite_state_list::SpriteStateList;
+use crate::text_processor::TextProcessor;
+use crate::ui_theme::UiTheme;
+use crate::util::random::Random;
+
+pub mod camera;
+pub mod filesystem;
+pub mod game_palette;
+pub mod map_renderer;
+pub mod map;
+pub mod software_renderer;
+pub mod sprites;
+pub mod tileset;
+pub mod util;
+pub mod world;
+pub mod scene;
+pub mod l10n;
+pub mod gamestate;
+pub mod renderer;
+pub mod destination;
+pub mod scene_script;
+pub mod facing;
+pub mod ui_theme;
+pub mod text_processor;
+pub mod screen_fade;
+pub mod next_destination;
+pub mod memory;
+pub mod party;
+pub mod world_script;
+pub mod shared_op;
+pub mod music_list;
+pub mod sound_list;
+pub mod assets;
+pub mod scroll_state;
+pub mod input;
+pub mod script_coverage;
+pub mod headless;
+pub mod fuzz;
+pub mod resources_extract;
+pub mod dialogue_log;
+pub mod settings;
+pub mod settings_menu;
+pub mod save_game;
+
+pub const UPDATES_PER_SECOND: f64 = 60.0;
+pub const UPDATE_INTERVAL: f64 = 1.0 / UPDATES_PER_SECOND;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum GameMode {
+    Pc,
+    Snes,
+}
+
+#[derive(Copy, Clone)]
+pub enum GameEvent {
+    GotoDestination {
+        destination: Destination,
+        fade_in: bool,
+    },
+}
+
+pub struct Context<'a> {
+    pub fs: FileSystem,
+    pub l10n: L10n,
+    pub sprite_states: SpriteStateList,
+    pub assets: Assets,
+    pub render: Renderer<'a>,
+    pub random: Random,
+    pub ui_theme: UiTheme,
+    pub memory: Memory,
+    pub party: Party,
+    pub text_processor: TextProcessor,
+    pub dialogue_log: DialogueLog,
+    pub settings: Settings,
+    pub screen_fade: ScreenFade,
+    pub mode: GameMode,
+    pub input: InputManager,
+    pub debug_mode: bool,
+}
@@ -1,78 +1,48 @@
-#![allow(dead_code)]
-
 use std::path::Path;
-use filesystem::filesystem::FileSystem;
-use util::timer::Timer;
-use crate::filesystem::backend_pc::{FileSystemBackendPc, FileSystemBackendPcMode};
-use crate::filesystem::backend_snes::FileSystemBackendSnes;
-use crate::gamestate::gamestate::GameStateTrait;
-use crate::gamestate::gamestate_scene::GameStateScene;
-use crate::gamestate::gamestate_world::GameStateWorld;
-use crate::l10n::L10n;
-use clap::Parser;
+use ctviewer::filesystem::error::FileSystemResult;
+use ctviewer::filesystem::filesystem::FileSystem;
+use ctviewer::util::timer::Timer;
+use ctviewer::filesystem::backend_overlay::FileSystemBackendOverlay;
+use ctviewer::filesystem::backend_pc::{FileSystemBackendPc, FileSystemBackendPcMode};
+use ctviewer::filesystem::backend_snes::{read_rom, FileSystemBackendSnes};
+use ctviewer::filesystem::rom_identify::{identify_rom, print_rom_info};
+use ctviewer::filesystem::rom_patch::apply_patch_files;
+use ctviewer::gamestate::gamestate::GameStateTrait;
+use ctviewer::gamestate::gamestate_error::GameStateError;
+use ctviewer::gamestate::gamestate_scene::GameStateScene;
+use ctviewer::gamestate::gamestate_world::GameStateWorld;
+use ctviewer::l10n::{IndexedType, L10n};
+use ctviewer::{Context, GameEvent, GameMode, UPDATE_INTERVAL};
+use clap::{Parser, Subcommand};
 use sdl3::event::Event;
 use sdl3::keyboard::Keycode;
-use crate::destination::Destination;
-use crate::facing::Facing;
-use crate::memory::Memory;
-use crate::renderer::Renderer;
-use crate::screen_fade::ScreenFade;
-use assets::Assets;
-use crate::input::{InputAction, InputManager};
-use crate::party::party::Party;
-use crate::sprites::sprite_state_list::SpriteStateList;
-use crate::text_processor::TextProcessor;
-use crate::ui_theme::UiTheme;
-use crate::util::random::Random;
-use crate::util::vec2df64::Vec2Df64;
-
-mod camera;
-mod filesystem;
-mod game_palette;
-mod map_renderer;
-mod map;
-mod software_renderer;
-mod sprites;
-mod tileset;
-mod util;
-mod world;
-mod scene;
-mod l10n;
-mod gamestate;
-mod renderer;
-mod destination;
-mod scene_script;
-mod facing;
-mod ui_theme;
-mod text_processor;
-mod screen_fade;
-mod next_destination;
-mod memory;
-mod party;
-mod world_script;
-mod shared_op;
-mod music_list;
-mod sound_list;
-pub mod assets;
-mod scroll_state;
-mod input;
-
-const UPDATES_PER_SECOND: f64 = 60.0;
-const UPDATE_INTERVAL: f64 = 1.0 / UPDATES_PER_SECOND;
-
-#[derive(Copy, Clone, PartialEq)]
-pub enum GameMode {
-    Pc,
-    Snes,
-}
-
-#[derive(Copy, Clone)]
-pub enum GameEvent {
-    GotoDestination {
-        destination: Destination,
-        fade_in: bool,
-    },
-}
+use ctviewer::destination::Destination;
+use ctviewer::facing::Facing;
+use ctviewer::memory::Memory;
+use ctviewer::renderer::Renderer;
+use ctviewer::screen_fade::ScreenFade;
+use ctviewer::assets::Assets;
+use ctviewer::input::{InputAction, InputManager};
+use ctviewer::party::party::Party;
+use ctviewer::sprites::sprite_state_list::SpriteStateList;
+use ctviewer::text_processor::TextProcessor;
+use ctviewer::util::random::Random;
+use ctviewer::util::vec2di32::Vec2Di32;
+use ctviewer::world_script::world_script_export::world_script_export;
+use ctviewer::script_coverage::script_coverage_report;
+use ctviewer::scene_script::dialogue_export::dialogue_export;
+use ctviewer::headless::{run_scene_headless, SceneRunOptions};
+use ctviewer::filesystem::resourcesbin::{write_resources_bin_from_directory, ResourcesBin};
+use ctviewer::filesystem::rom_writer::{RomAsset, SnesRomWriter};
+use ctviewer::resources_extract::{resources_extract, resources_list};
+use std::path::PathBuf;
+use ctviewer::dialogue_log::DialogueLog;
+use ctviewer::settings::{Settings, TEXTBOX_ANIMATION_SPEED_DEFAULT};
+use ctviewer::settings_menu::SettingsMenu;
+
+// Where the dialogue log is exported to, and how many lines it scrolls per key press.
+const DIALOGUE_LOG_PATH: &str = "dialogue_log.txt";
+const DIALOGUE_LOG_SCROLL_LINES: usize = 4;
 
 /// Load and display Chrono Trigger game data.
 #[derive(Parser, Debug)]
@@ -112,23 +82,156 @@ struct Args {
     /// The user interface theme index, from 0 to 7.
     #[arg(short, long, default_value_t = 0, value_name = "THEME")]
     ui_theme: usize,
+
+    /// Apply an IPS or BPS patch to the SNES ROM in memory. Can be given more than once, patches
+    /// are applied in order.
+    #[arg(long, value_name = "FILE")]
+    patch: Vec<String>,
+
+    /// IPS patches are made for ROMs with a 512 byte copier header.
+    #[arg(long)]
+    ips_headered: bool,
+
+    /// Language of the game text. The available languages are listed on startup.
+    #[arg(long, value_name = "LANGUAGE")]
+    language: Option<String>,
+
+    /// Draw text with the game's own fonts instead of the bundled TTF fonts, if they are found.
+    #[arg(long, default_value_t = false)]
+    native_font: bool,
+
+    /// Text speed, from 1 (fastest) to 8 (slowest).
+    #[arg(long, default_value_t = 1, value_name = "SPEED", value_parser = clap::value_parser!(u8).range(1..=8))]
+    text_speed: u8,
+
+    /// Continue dialogue by itself this many seconds after a page is complete.
+    #[arg(long, value_name = "SECONDS")]
+    auto_advance: Option<f64>,
+
+    /// Open and close textbox windows this many times per second.
+    #[arg(long, default_value_t = TEXTBOX_ANIMATION_SPEED_DEFAULT, value_name = "SPEED")]
+    textbox_animation_speed: f64,
+
+    /// Read individual assets from loose files in this directory instead of the source data.
+    #[arg(long, value_name = "DIR")]
+    overlay: Option<String>,
+
+    /// Run a command instead of the viewer.
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-pub struct Context<'a> {
-    fs: FileSystem,
-    l10n: L10n<'a>,
-    sprite_states: SpriteStateList,
-    assets: Assets,
-    render: Renderer<'a>,
-    random: Random,
-    ui_theme: UiTheme,
-    memory: Memory,
-    party: Party,
-    text_processor: TextProcessor,
-    screen_fade: ScreenFade,
-    mode: GameMode,
-    input: InputManager,
-    debug_mode: bool,
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write annotated world script disassemblies for all worlds.
+    ExportWorldScripts {
+        /// Output directory.
+        #[arg(default_value = "world_scripts")]
+        output: String,
+    },
+
+    /// Write all scene script dialogue as JSON and as a readable script.
+    Dialogue {
+        /// Output directory.
+        #[arg(default_value = "dialogue")]
+        output: String,
+    },
+
+    /// Decode all scene and world scripts and report opcode usage as JSON.
+    Coverage {
+        /// Output JSON file.
+        #[arg(default_value = "coverage.json")]
+        output: String,
+    },
+
+    /// Run a scene without a window and write a JSON snapshot of its state.
+    RunScene {
+        /// Index of the scene to run.
+        scene: usize,
+
+        /// Number of ticks to run the scene for.
+        #[arg(long, default_value_t = 60)]
+        ticks: usize,
+
+        /// Input script with "<tick> <action> [down|up|press]" lines.
+        #[arg(long, value_name = "FILE")]
+        input: Option<String>,
+
+        /// Output JSON file.
+        #[arg(long, default_value = "snapshot.json", value_name = "FILE")]
+        output: String,
+
+        /// Also write the final framebuffer to a BMP file.
+        #[arg(long, value_name = "FILE")]
+        framebuffer: Option<String>,
+    },
+
+    /// List the files in resources.bin.
+    List {
+        /// Only list files matching these glob patterns.
+        patterns: Vec<String>,
+    },
+
+    /// Extract the files in resources.bin to a directory that can be used as source data path.
+    Extract {
+        /// Output directory.
+        output: String,
+
+        /// Only extract files matching these glob patterns.
+        patterns: Vec<String>,
+    },
+
+    /// Write a new resources.bin from a directory tree.
+    Pack {
+        /// Source directory.
+        source: String,
+
+        /// Output resources.bin file. This cannot be the resources.bin of the game data path.
+        output: String,
+    },
+
+    /// Replace a single file in resources.bin.
+    Replace {
+        /// Path of the file inside resources.bin.
+        file: String,
+
+        /// File with the new contents.
+        source: String,
+    },
+
+    /// Write a copy of the SNES ROM with replaced assets.
+    WriteRom {
+        /// Output ROM file.
+        output: String,
+
+        /// Replace a scene map with uncompressed data from a file.
+        #[arg(long, value_name = "INDEX=FILE", value_parser = parse_index_file)]
+        scene_map: Vec<(usize, String)>,
+
+        /// Replace a layer 1 and 2 tileset assembly with uncompressed data from a file.
+        #[arg(long, value_name = "INDEX=FILE", value_parser = parse_index_file)]
+        tileset_assembly: Vec<(usize, String)>,
+
+        /// Replace a layer 3 tileset assembly with uncompressed data from a file.
+        #[arg(long, value_name = "INDEX=FILE", value_parser = parse_index_file)]
+        tileset3_assembly: Vec<(usize, String)>,
+
+        /// Replace the exits of a scene with data from a file.
+        #[arg(long, value_name = "INDEX=FILE", value_parser = parse_index_file)]
+        scene_exits: Vec<(usize, String)>,
+
+        /// Replace a scene script with uncompressed data from a file.
+        #[arg(long, value_name = "INDEX=FILE", value_parser = parse_index_file)]
+        scene_script: Vec<(usize, String)>,
+
+        /// Replace the strings of a textbox string table with tagged text from a file, one string per line.
+        #[arg(long, value_name = "ADDRESS=FILE", value_parser = parse_address_file)]
+        string_table: Vec<(usize, String)>,
+
+        /// Unused ROM addresses that data can be moved to, in hexadecimal.
+        #[arg(long, value_name = "START-END", value_parser = parse_address_range)]
+        free_space: Vec<(usize, usize)>,
+    },
 }
 
 fn main() -> Result<(), String> {
@@ -137,14 +240,131 @@ fn main() -> Result<(), String> {
 
     let args = Args::parse();
 
-    let fs = create_filesystem(args.path);
-    let l10n = L10n::new("it", &fs);
+    // Commands that work on resources.bin or the ROM directly, without loading any game data.
+    match &args.command {
+        Some(Command::List { patterns }) => {
+            return resources_list(&open_resources_bin(&args.path)?, patterns)
+                .map_err(|e| format!("Could not list resources: {}", e));
+        },
+        Some(Command::Extract { output, patterns }) => {
+            return resources_extract(&open_resources_bin(&args.path)?, Path::new(output), patterns)
+                .map_err(|e| format!("Could not extract resources: {}", e));
+        },
+        Some(Command::Pack { source, output }) => {
+            if is_same_file(Path::new(output), Path::new(&args.path)) {
+                return Err(format!("Refusing to write over the game data in {}, choose another output file.", args.path));
+            }
+            let count = write_resources_bin_from_directory(Path::new(source), Path::new(output))
+                .map_err(|e| format!("Could not write {}: {}", output, e))?;
+            println!("Wrote {} files to {}.", count, output);
+            return Ok(());
+        },
+        Some(Command::Replace { file, source }) => {
+            let data = std::fs::read(source).map_err(|e| format!("Could not read {}: {}", source, e))?;
+            open_resources_bin(&args.path)?.replace_file(file, &data)
+                .map_err(|e| format!("Could not replace {}: {}", file, e))?;
+            println!("Replaced {} with {}.", file, source);
+            return Ok(());
+        },
+        Some(Command::WriteRom { output, scene_map, tileset_assembly, tileset3_assembly, scene_exits, scene_script, string_table, free_space }) => {
+            let (file_header, data) = read_rom(Path::new(&args.path))?;
+            let data = apply_patch_files(&file_header, data, &args.patch, args.ips_headered)?;
+            print_rom_info(&identify_rom(&data)?, data.len());
+
+            // Strings are encoded with the dictionary of the source ROM.
+            let mut string_tables = Vec::new();
+            if !string_table.is_empty() {
+                let backend = FileSystemBackendSnes::from_data(data.clone())?;
+                for (address, file) in string_table {
+                    let text = std::fs::read_to_string(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+                    let strings = text.lines()
+                        .map(|line| backend.encode_string(line))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| format!("Could not encode {}: {}", file, e))?;
+                    let original = backend.textbox_string_ranges(*address).map_err(|e| format!("Could not read string table 0x{:06X}: {}", address, e))?;
+                    string_tables.push((*address, original, strings));
+                }
+            }
+
+            let mut writer = SnesRomWriter::from_data(file_header, data)?;
+            for (start, end) in free_space {
+                writer.add_free_space(*start, *end).map_err(|e| e.to_string())?;
+            }
+
+            let replacements = [
+                (RomAsset::SceneMap, scene_map),
+                (RomAsset::SceneTileset12Assembly, tileset_assembly),
+                (RomAsset::SceneTileset3Assembly, tileset3_assembly),
+                (RomAsset::SceneScript, scene_script),
+            ];
+            for (asset, files) in replacements {
+                for (index, file) in files {
+                    let data = std::fs::read(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+                    writer.replace_compressed(asset, *index, &data).map_err(|e| format!("Could not replace {:?} {}: {}", asset, index, e))?;
+                }
+            }
+            for (index, file) in scene_exits {
+                let data = std::fs::read(file).map_err(|e| format!("Could not read {}: {}", file, e))?;
+                writer.replace_scene_exits(*index, &data).map_err(|e| format!("Could not replace scene exits {}: {}", index, e))?;
+            }
+            for (address, original, strings) in string_tables {
+                writer.replace_string_table(address, &original, &strings).map_err(|e| format!("Could not replace string table 0x{:06X}: {}", address, e))?;
+            }
+
+            writer.write(Path::new(output)).map_err(|e| format!("Could not write {}: {}", output, e))?;
+            println!("Wrote {}.", output);
+            return Ok(());
+        },
+        _ => {},
+    }
+
+    let mut fs = create_filesystem(args.path, &args.patch, args.ips_headered)?;
+    if let Some(overlay) = &args.overlay {
+        let overlay = Path::new(overlay);
+        if !overlay.is_dir() {
+            return Err(format!("Overlay directory {} does not exist.", overlay.display()));
+        }
+        let backend = FileSystemBackendOverlay::new(fs.backend, overlay);
+        fs = FileSystem::new(Box::new(backend), fs.mode);
+    }
+    let language = select_language(&fs, args.language.as_deref())?;
+    let l10n = L10n::new(&language, &fs).map_err(|error| format!("Could not read strings: {}", error))?;
+
+    // Scene runs need the full game context, other commands only need the filesystem.
+    let scene_run = match args.command {
+        Some(Command::RunScene { scene, ticks, input, output, framebuffer }) => Some(SceneRunOptions {
+            scene_index: scene,
+            ticks,
+            input_path: input.map(PathBuf::from),
+            output_path: PathBuf::from(output),
+            framebuffer_path: framebuffer.map(PathBuf::from),
+        }),
+        Some(command) => return run_command(command, &fs, &l10n),
+        None => None,
+    };
+
+    // Render to an offscreen window when running headless.
+    if scene_run.is_some() {
+        sdl3::hint::set("SDL_VIDEO_DRIVER", "offscreen");
+    }
+
     let sdl = sdl3::init().unwrap();
-    let render = Renderer::new(&sdl, args.scale, args.scale_linear, args.pixel_aspect_ratio, args.display_aspect_ratio, !args.no_vsync);
-    let assets = Assets::new(&fs);
+    let mut render = if scene_run.is_some() {
+        Renderer::new(&sdl, 1, false, args.pixel_aspect_ratio, args.display_aspect_ratio, false)
+    } else {
+        Renderer::new(&sdl, args.scale, args.scale_linear, args.pixel_aspect_ratio, args.display_aspect_ratio, !args.no_vsync)
+    };
+    if args.native_font {
+        match fs.read_fonts() {
+            Ok(Some(fonts)) => render.set_native_fonts(Some(fonts)),
+            Ok(None) => println!("No native fonts found in the source data, using TTF fonts."),
+            Err(error) => println!("Could not read native fonts, using TTF fonts: {}", error),
+        }
+    }
+    let assets = Assets::new(&fs).map_err(|error| format!("Could not read sprite animations: {}", error))?;
     let sprite_states = SpriteStateList::new();
     let random = Random::new();
-    let ui_theme = fs.read_ui_theme(args.ui_theme);
+    let ui_theme = fs.read_ui_theme(args.ui_theme).map_err(|error| format!("Could not read UI theme: {}", error))?;
     let screen_fade = ScreenFade::new(0.0);
     let mode = fs.mode;
 
@@ -157,12 +377,16 @@ fn main() -> Result<(), String> {
     input.bind(InputAction::OpenMap, Keycode::Tab);
     input.bind(InputAction::OpenSettingsMenu, Keycode::R);
     input.bind(InputAction::OpenPartyMenu, Keycode::C);
+    input.bind(InputAction::NextLanguage, Keycode::L);
 
     input.bind(InputAction::MenuPrevious, Keycode::Q);
     input.bind(InputAction::MenuNext, Keycode::E);
+    input.bind(InputAction::MenuUp, Keycode::W);
     input.bind(InputAction::MenuDown, Keycode::S);
     input.bind(InputAction::MenuLeft, Keycode::A);
     input.bind(InputAction::MenuRight, Keycode::D);
+    input.bind(InputAction::MenuConfirm, Keycode::F);
+    input.bind(InputAction::MenuCancel, Keycode::G);
 
     input.bind(InputAction::MoveUp, Keycode::W);
     input.bind(InputAction::MoveDown, Keycode::S);
@@ -175,6 +399,11 @@ fn main() -> Result<(), String> {
     input.bind(InputAction::DialogueChoiceNext, Keycode::S);
     input.bind(InputAction::DialogueChoiceConfirm, Keycode::F);
 
+    input.bind(InputAction::ToggleDialogueLog, Keycode::H);
+    input.bind(InputAction::DialogueLogScrollUp, Keycode::PageUp);
+    input.bind(InputAction::DialogueLogScrollDown, Keycode::PageDown);
+    input.bind(InputAction::ExportDialogueLog, Keycode::J);
+
     input.bind(InputAction::ToggleDebug, Keycode::Backspace);
     input.bind(InputAction::DebugCameraUp, Keycode::W);
     input.bind(InputAction::DebugCameraDown, Keycode::S);
@@ -205,9 +434,24 @@ fn main() -> Result<(), String> {
     memory.put_u8(0x7F0057, 4);
 
 
+    let mut settings = Settings::new();
+    settings.text_speed = args.text_speed as usize - 1;
+    if let Some(delay) = args.auto_advance {
+        if delay.is_nan() || delay < 0.0 {
+            return Err(format!("Auto-advance delay must be 0 seconds or more, not {}.", delay));
+        }
+        settings.auto_advance_delay = Some(delay);
+    }
+    if args.textbox_animation_speed.is_nan() || args.textbox_animation_speed <= 0.0 {
+        return Err(format!("Textbox animation speed must be more than 0, not {}.", args.textbox_animation_speed));
+    }
+    settings.textbox_animation_speed = args.textbox_animation_speed;
+
     let mut text_processor = TextProcessor::new();
-    let party = Party::new();
-    text_processor.update_party_names(&party);
+    text_processor.set_tech_names(l10n.get_indexed_all(IndexedType::Tech).to_vec());
+    let mut party = Party::new();
+    load_player_names(&fs, &language, &mut party, &mut text_processor)
+        .map_err(|error| format!("Could not read player names: {}", error))?;
 
     let mut ctx = Context {
         fs,
@@ -220,26 +464,33 @@ fn main() -> Result<(), String> {
         memory,
         party,
         text_processor,
+        dialogue_log: DialogueLog::new(),
+        settings,
         screen_fade,
         input,
         mode,
         debug_mode: false,
     };
 
+    if let Some(options) = scene_run {
+        return run_scene_headless(&mut ctx, &options);
+    }
 
-    let mut gamestate: Box<dyn GameStateTrait>;
-    if args.scene > -1 {
-        gamestate = Box::new(GameStateScene::new(&mut ctx, args.scene as usize, Vec2Df64::new(128.0, 112.0), Facing::Down, true));
+    let destination = if args.scene > -1 {
+        Destination::Scene { index: args.scene as usize, pos: Vec2Di32::new(128, 112), facing: Facing::Down, data: 0 }
     } else if args.world > -1 {
-        gamestate = Box::new(GameStateWorld::new(&mut ctx, args.world as usize, Vec2Df64::new(384.0, 296.0), true));
+        Destination::World { index: args.world as usize, pos: Vec2Di32::new(384, 296), data: 0 }
     } else {
         println!("No scene or world specified, loading world 0.");
-        gamestate = Box::new(GameStateWorld::new(&mut ctx, 0, Vec2Df64::new(504.0, 448.0), true));
-    }
+        Destination::World { index: 0, pos: Vec2Di32::new(504, 448), data: 0 }
+    };
+    let mut gamestate = enter_destination(&mut ctx, destination, true);
 
     let title = format!("Chrono Trigger - {}", gamestate.get_title(&ctx));
     ctx.render.set_title(title.as_str());
 
+    let mut settings_menu = SettingsMenu::new();
+
     let mut timer_loop = Timer::new();
     let mut timer_render = Timer::new();
     let mut timer_update = Timer::new();
@@ -306,7 +557,46 @@ fn main() -> Result<(), String> {
                 update_window_title(&mut ctx, &gamestate);
             }
 
+            if ctx.input.was_pressed(InputAction::NextLanguage) {
+                switch_language(&mut ctx, &mut gamestate);
+                update_window_title(&mut ctx, &gamestate);
+            }
+
+            if ctx.input.was_pressed(InputAction::OpenSettingsMenu) {
+                settings_menu.toggle_visible();
+            }
+            if ctx.input.was_pressed(InputAction::ToggleDialogueLog) {
+                ctx.dialogue_log.toggle_visible();
+            }
+            if ctx.input.was_pressed(InputAction::ExportDialogueLog) {
+                match ctx.dialogue_log.export(Path::new(DIALOGUE_LOG_PATH)) {
+                    Ok(()) => println!("Wrote dialogue log to {}.", DIALOGUE_LOG_PATH),
+                    Err(error) => println!("Could not write dialogue log to {}: {}", DIALOGUE_LOG_PATH, error),
+                }
+            }
+
+            // The game is paused while changing settings or reading the dialogue log.
+            if settings_menu.is_visible() {
+                settings_menu.tick(&mut ctx);
+
+                ctx.input.clear();
+                accumulator -= UPDATE_INTERVAL;
+                continue;
+            }
+            if ctx.dialogue_log.is_visible() {
+                if ctx.input.was_pressed(InputAction::DialogueLogScrollUp) {
+                    ctx.dialogue_log.scroll_up(DIALOGUE_LOG_SCROLL_LINES);
+                } else if ctx.input.was_pressed(InputAction::DialogueLogScrollDown) {
+                    ctx.dialogue_log.scroll_down(DIALOGUE_LOG_SCROLL_LINES);
+                }
+
+                ctx.input.clear();
+                accumulator -= UPDATE_INTERVAL;
+                continue;
+            }
+
             ctx.screen_fade.tick(UPDATE_INTERVAL);
+            ctx.dialogue_log.tick(UPDATE_INTERVAL);
 
             let game_event = gamestate.tick(&mut ctx, UPDATE_INTERVAL);
             if game_event.is_some() {
@@ -318,15 +608,9 @@ fn main() -> Result<(), String> {
                         ctx.memory.put_u16(0x7E0105, destination.get_index() as u16);
 
                         match destination {
-                            Destination::Scene { index, pos, facing, data } => {
-                                ctx.memory.put_u8(0x7E0104, data);
-                                gamestate = Box::new(GameStateScene::new(&mut ctx, index, pos.as_vec2d_f64(), facing, fade_in));
-                            },
-                            Destination::World { index, pos, data } => {
-                                ctx.memory.put_u8(0x7E0104, data);
-                                gamestate = Box::new(GameStateWorld::new(&mut ctx, index, pos.as_vec2d_f64(), fade_in));
-                            },
+                            Destination::Scene { data, .. } | Destination::World { data, .. } => ctx.memory.put_u8(0x7E0104, data),
                         };
+                        gamestate = enter_destination(&mut ctx, destination, fade_in);
                         update_window_title(&mut ctx, &gamestate);
                     },
                 }
@@ -347,6 +631,8 @@ fn main() -> Result<(), String> {
         ctx.render.clear();
         gamestate.render(&mut ctx, lerp);
         ctx.screen_fade.render(&mut ctx.render, lerp);
+        ctx.dialogue_log.render(&mut ctx.render);
+        settings_menu.render(&mut ctx);
         ctx.render.copy_to_canvas();
 
         stat_render_time += timer_render.stop();
@@ -374,6 +660,79 @@ fn main() -> Result<(), String> {
     Ok(())
 }
 
+// Creates the gamestate for a destination. If it cannot be loaded an error screen is shown instead.
+fn enter_destination(ctx: &mut Context, destination: Destination, fade_in: bool) -> Box<dyn GameStateTrait> {
+    let gamestate: FileSystemResult<Box<dyn GameStateTrait>> = match destination {
+        Destination::Scene { index, pos, facing, .. } => GameStateScene::new(ctx, index, pos.as_vec2d_f64(), facing, fade_in).map(|state| Box::new(state) as Box<dyn GameStateTrait>),
+        Destination::World { index, pos, .. } => GameStateWorld::new(ctx, index, pos.as_vec2d_f64(), fade_in).map(|state| Box::new(state) as Box<dyn GameStateTrait>),
+    };
+
+    let gamestate = match gamestate {
+        Ok(gamestate) => gamestate,
+        Err(error) => Box::new(GameStateError::new(ctx, destination, &error)),
+    };
+    ctx.dialogue_log.set_location(gamestate.get_title(ctx));
+
+    gamestate
+}
+
+// Picks the requested language, or English if none was requested and it is available.
+fn select_language(fs: &FileSystem, requested: Option<&str>) -> Result<String, String> {
+    let languages = fs.read_languages().map_err(|error| format!("Could not read languages: {}", error))?;
+    if languages.is_empty() {
+        return Err("No languages were found in the source data.".to_string());
+    }
+    println!("Available languages: {}.", languages.join(", "));
+
+    let language = match requested {
+        Some(language) => language,
+        None if languages.iter().any(|language| language == "en") => "en",
+        None => &languages[0],
+    };
+    if !languages.iter().any(|available| available == language) {
+        return Err(format!("Language '{}' is not available, use one of: {}.", language, languages.join(", ")));
+    }
+
+    Ok(language.to_string())
+}
+
+fn load_player_names(fs: &FileSystem, language: &str, party: &mut Party, text_processor: &mut TextProcessor) -> FileSystemResult<()> {
+    let names = fs.read_player_character_names(language)?;
+    party.set_character_names(&names);
+    text_processor.update_party_names(party);
+    if let Some(name) = names.get(7) {
+        text_processor.update_epoch_name(name);
+    }
+
+    Ok(())
+}
+
+// Reloads all strings in the next available language, without leaving the current gamestate.
+fn switch_language(ctx: &mut Context, gamestate: &mut Box<dyn GameStateTrait>) {
+    let language = ctx.l10n.get_next_language().to_string();
+    if language == ctx.l10n.get_language() {
+        println!("No other languages are available.");
+        return;
+    }
+
+    match L10n::new(&language, &ctx.fs) {
+        Ok(l10n) => {
+            ctx.text_processor.set_tech_names(l10n.get_indexed_all(IndexedType::Tech).to_vec());
+            ctx.l10n = l10n;
+        },
+        Err(error) => {
+            println!("Could not switch to language '{}': {}", language, error);
+            return;
+        },
+    }
+    if let Err(error) = load_player_names(&ctx.fs, &language, &mut ctx.party, &mut ctx.text_processor) {
+        println!("Could not read player names for language '{}': {}", language, error);
+    }
+    gamestate.language_changed(ctx);
+
+    println!("Language: {}.", language);
+}
+
 fn update_window_title(ctx: &mut Context, gamestate: &Box<dyn GameStateTrait>) {
     let title = if ctx.debug_mode {
         format!("Chrono Trigger - DEBUG - {}", gamestate.get_title(&ctx))
@@ -383,21 +742,96 @@ fn update_window_title(ctx: &mut Context, gamestate: &Box<dyn GameStateTrait>) {
     ctx.render.set_title(title.as_str());
 }
 
-fn create_filesystem(path: String) -> FileSystem {
+fn run_command(command: Command, fs: &FileSystem, l10n: &L10n) -> Result<(), String> {
+    match command {
+        Command::ExportWorldScripts { output } => {
+            world_script_export(fs, l10n, Path::new(&output)).map_err(|e| format!("Could not export world scripts: {}", e))
+        },
+        Command::Dialogue { output } => {
+            let mut party = Party::new();
+            let mut text_processor = TextProcessor::new();
+            text_processor.set_tech_names(l10n.get_indexed_all(IndexedType::Tech).to_vec());
+            load_player_names(fs, l10n.get_language(), &mut party, &mut text_processor).map_err(|e| format!("Could not read player names: {}", e))?;
+            dialogue_export(fs, l10n, &text_processor, Path::new(&output)).map_err(|e| format!("Could not export dialogue: {}", e))
+        },
+        Command::Coverage { output } => {
+            script_coverage_report(fs, Path::new(&output)).map_err(|e| format!("Could not write coverage report: {}", e))
+        },
+        Command::RunScene { .. } => unreachable!("Scene runs are handled after the game context is created."),
+        Command::List { .. } | Command::Extract { .. } | Command::Pack { .. } | Command::Replace { .. } | Command::WriteRom { .. } => {
+            unreachable!("Commands that write game data are handled before the filesystem is created.")
+        },
+    }
+}
+
+fn parse_index_file(value: &str) -> Result<(usize, String), String> {
+    let (index, file) = value.split_once('=').ok_or("expected INDEX=FILE")?;
+    let index = index.parse::<usize>().map_err(|_| format!("invalid index \"{}\"", index))?;
+
+    Ok((index, file.to_string()))
+}
+
+fn parse_address_file(value: &str) -> Result<(usize, String), String> {
+    let (address, file) = value.split_once('=').ok_or("expected ADDRESS=FILE")?;
+    let address = usize::from_str_radix(address.trim_start_matches("0x"), 16).map_err(|_| format!("invalid address \"{}\"", address))?;
+
+    Ok((address, file.to_string()))
+}
+
+fn parse_address_range(value: &str) -> Result<(usize, usize), String> {
+    let (start, end) = value.split_once('-').ok_or("expected START-END")?;
+    let parse = |address: &str| usize::from_str_radix(address.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("invalid address \"{}\"", address));
+
+    Ok((parse(start)?, parse(end)?))
+}
+
+// Returns true if both paths exist and refer to the same file.
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn open_resources_bin(path: &str) -> Result<ResourcesBin, String> {
+    let src = Path::new(path);
+    if !src.is_file() || src.file_name().unwrap().to_ascii_lowercase() != "resources.bin" {
+        return Err(format!("{} is not a resources.bin file.", path));
+    }
+
+    Ok(ResourcesBin::new(src))
+}
+
+fn create_filesystem(path: String, patches: &[String], ips_headered: bool) -> Result<FileSystem, String> {
     let src = Path::new(&path);
+    if !patches.is_empty() && (src.is_dir() || src.file_name().unwrap().to_ascii_lowercase() == "resources.bin") {
+        return Err("Patches can only be applied to SNES ROMs.".to_string());
+    }
 
     // A directory is assumed to be the extracted version of the Steam resources.bin file.
     if src.is_dir() {
         let backend = FileSystemBackendPc::new(&src.into(), FileSystemBackendPcMode::FileSystem);
-        return FileSystem::new(Box::new(backend), GameMode::Pc);
+        return Ok(FileSystem::new(Box::new(backend), GameMode::Pc));
 
     // Steam version resources.bin.
     } else if src.file_name().unwrap().to_ascii_lowercase() == "resources.bin" {
         let backend = FileSystemBackendPc::new(&src.into(), FileSystemBackendPcMode::ResourcesBin);
-        return FileSystem::new(Box::new(backend), GameMode::Pc);
+        return Ok(FileSystem::new(Box::new(backend), GameMode::Pc));
     }
 
     // Any other file is assumed to be an SNES ROM image.
-    let backend = FileSystemBackendSnes::new(&src);
-    FileSystem::new(Box::new(backend), GameMode::Snes)
+    if patches.is_empty() {
+        let backend = FileSystemBackendSnes::new(src)?;
+        let mode = GameMode::Snes;
+        return Ok(FileSystem::new(Box::new(backend), mode));
+    }
+
+    // Patches are applied in memory only, the ROM file is never written to.
+    let (file_header, data) = read_rom(src)?;
+    let data = apply_patch_files(&file_header, data, patches, ips_headered)?;
+    println!("Using SNES data backend from {} with {} patches.", src.display(), patches.len());
+    let backend = FileSystemBackendSnes::from_data(data)?;
+    let mode = GameMode::Snes;
+    Ok(FileSystem::new(Box::new(backend), mode))
 }
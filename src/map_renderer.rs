@@ -352,3 +352,60 @@ fn render_sprites(target: &mut Surface, pixel_source: &mut Bitmap, sprite_states
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::software_renderer::blit::{blit_surface_to_surface, SurfaceBlendOps};
+    use crate::software_renderer::golden::assert_golden;
+
+    const WIDTH: u32 = 64;
+    const HEIGHT: u32 = 16;
+
+    // Blends a main screen color ramp with striped subscreen colors. Columns alternate between no
+    // main screen pixels, a blend enabled layer and a layer without blending. The bottom half has
+    // no subscreen pixels.
+    fn blend(mode: LayerBlendMode) -> Surface {
+        let mut map_renderer = MapRenderer::new(WIDTH, HEIGHT);
+        map_renderer.layer_blend_mode = mode;
+        map_renderer.layer_blend_enable = LayerFlags::Layer1 | LayerFlags::Sprites;
+        map_renderer.layer_blend_color = [32, 0, 64, 255];
+
+        let mut main = Surface::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let index = (y * WIDTH + x) as usize;
+                main.data[index * 4..index * 4 + 4].copy_from_slice(&[(x * 4) as u8, 160, (255 - x * 4) as u8, 255]);
+                map_renderer.screen_sub.data[index * 4..index * 4 + 4].copy_from_slice(match (x / 4) % 3 {
+                    0 => &[255, 64, 0, 255],
+                    1 => &[64, 255, 64, 255],
+                    _ => &[128, 128, 128, 255],
+                });
+
+                map_renderer.pixels_main.data[index] = match x {
+                    0..16 => 0,
+                    16..40 => LayerFlags::Layer1.bits(),
+                    _ => LayerFlags::Layer2.bits(),
+                };
+                map_renderer.pixels_sub.data[index] = if y < HEIGHT / 2 { LayerFlags::Layer3.bits() } else { 0 };
+            }
+        }
+
+        map_renderer.blend_surfaces(&mut main);
+
+        main
+    }
+
+    #[test]
+    fn layer_blend_modes() {
+        let modes = [LayerBlendMode::Add, LayerBlendMode::AddHalf, LayerBlendMode::Sub, LayerBlendMode::SubHalf];
+
+        let mut surface = Surface::new(WIDTH, HEIGHT * modes.len() as u32);
+        for (index, mode) in modes.iter().enumerate() {
+            let blended = blend(*mode);
+            blit_surface_to_surface(&blended, &mut surface, 0, 0, WIDTH as i32, HEIGHT as i32, 0, index as i32 * HEIGHT as i32, SurfaceBlendOps::Copy);
+        }
+
+        assert_golden("map_renderer/layer_blend_modes", &surface);
+    }
+}
@@ -23,6 +23,16 @@ impl MemoryRegion {
         self.data.fill(0);
     }
 
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Copies data into the start of this region, such as when restoring a saved game.
+    pub fn set_data(&mut self, data: &[u8]) {
+        let len = data.len().min(self.data.len());
+        self.data[..len].copy_from_slice(&data[..len]);
+    }
+
     pub fn put_u8(&mut self, address: usize, value: u8) {
         self.data[address] = value;
     }
@@ -9,12 +9,15 @@ pub struct PartySlot {
 }
 
 impl PartySlot {
-    fn new(character_id: CharacterId, disabled: bool) -> Self {
+    pub fn new(character_id: CharacterId, disabled: bool) -> Self {
         Self { character_id, disabled }
     }
 }
 
-const PARTY_LEN: usize = 3;
+pub const PARTY_LEN: usize = 3;
+
+/// Number of party slots, active and in reserve. Every character has a slot.
+pub const PARTY_SLOT_COUNT: usize = 7;
 
 pub struct Party {
 
@@ -360,6 +363,75 @@ impl Party {
         self.gold -= amount;
     }
 
+    pub fn get_gold(&self) -> u32 {
+        self.gold
+    }
+
+    pub fn set_gold(&mut self, amount: u32) {
+        self.gold = amount;
+    }
+
+    pub fn item_give(&mut self, item_id: ItemId, amount: u32) {
+        *self.inventory.entry(item_id).or_insert(0) += amount;
+    }
+
+    /// Takes up to amount items, removing the item from the inventory if none are left.
+    pub fn item_take(&mut self, item_id: ItemId, amount: u32) {
+        let Some(held) = self.inventory.get_mut(&item_id) else {
+            return;
+        };
+        *held = held.saturating_sub(amount);
+        if *held == 0 {
+            self.inventory.remove(&item_id);
+        }
+    }
+
+    pub fn get_item_amount(&self, item_id: ItemId) -> u32 {
+        self.inventory.get(&item_id).copied().unwrap_or(0)
+    }
+
+    /// Returns all held items and their amounts, ordered by item id.
+    pub fn get_inventory(&self) -> Vec<(ItemId, u32)> {
+        let mut inventory: Vec<(ItemId, u32)> = self.inventory.iter().map(|(item_id, amount)| (*item_id, *amount)).collect();
+        inventory.sort();
+        inventory
+    }
+
+    /// Buys an item for price gold. Returns false if there is not enough gold.
+    pub fn item_buy(&mut self, item_id: ItemId, price: u32) -> bool {
+        if self.gold < price {
+            return false;
+        }
+        self.gold_take(price);
+        self.item_give(item_id, 1);
+        true
+    }
+
+    /// Sells an item for price gold. Returns false if the item is not held.
+    pub fn item_sell(&mut self, item_id: ItemId, price: u32) -> bool {
+        if self.get_item_amount(item_id) == 0 {
+            return false;
+        }
+        self.item_take(item_id, 1);
+        self.gold_give(price);
+        true
+    }
+
+    pub fn set_character_name(&mut self, character_id: CharacterId, name: String) {
+        if let Some(character) = self.characters.get_mut(&character_id) {
+            character.name = name;
+        }
+    }
+
+    /// Sets character names from a list ordered by character id.
+    pub fn set_character_names(&mut self, names: &[String]) {
+        for (character_id, name) in names.iter().enumerate() {
+            if let Some(character) = self.characters.get_mut(&character_id) {
+                character.name = name.clone();
+            }
+        }
+    }
+
     pub fn get_characters_iter(&self) -> impl Iterator<Item = &Character> + '_ {
         self.characters.values()
     }
@@ -376,4 +448,46 @@ impl Party {
         self.party_slots[0..PARTY_LEN].iter()
     }
 
+    /// Replaces all party slots, such as when loading a saved game.
+    pub fn set_party_slots(&mut self, party_slots: Vec<PartySlot>) {
+        self.party_slots = party_slots;
+    }
+
+    pub fn swap_party_slots(&mut self, index_a: usize, index_b: usize) {
+        self.party_slots.swap(index_a, index_b);
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn party_inventory_and_slots() {
+        let mut party = Party::new();
+        party.gold_give(100);
+        assert!(party.item_buy(1, 60));
+        assert!(!party.item_buy(1, 60));
+        assert_eq!(party.get_gold(), 40);
+        assert_eq!(party.get_item_amount(1), 1);
+
+        party.item_give(3, 2);
+        assert_eq!(party.get_inventory(), vec![(1, 1), (3, 2)]);
+        assert!(party.item_sell(1, 30));
+        assert!(!party.item_sell(1, 30));
+        assert_eq!(party.get_gold(), 70);
+        assert_eq!(party.get_inventory(), vec![(3, 2)]);
+
+        party.item_take(3, 5);
+        assert!(party.get_inventory().is_empty());
+
+        party.add_character_to_reserve(3);
+        party.swap_party_slots(0, 3);
+        let active: Vec<CharacterId> = party.get_active_party_slots().map(|slot| slot.character_id).collect();
+        assert_eq!(active, vec![3, 1, 2]);
+
+        party.set_character_name(0, "Chrono".to_string());
+        assert_eq!(party.get_character(0).unwrap().name, "Chrono");
+    }
 }
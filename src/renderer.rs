@@ -4,6 +4,7 @@ use sdl3::render::{BlendMode, ScaleMode, Texture, TextureCreator, WindowCanvas};
 use sdl3::{sys, Sdl};
 use sdl3::ttf::Font;
 use sdl3::video::WindowContext;
+use crate::software_renderer::bitmap_font::NativeFonts;
 use crate::software_renderer::blit::{blit_surface_to_surface, SurfaceBlendOps};
 use crate::util::rect::Rect;
 use crate::software_renderer::draw::draw_box_filled;
@@ -32,6 +33,9 @@ pub struct Renderer<'a> {
     pub font: Font<'a>,
     pub font_small: Font<'a>,
 
+    // The game's own fonts, used instead of the TTF fonts if set.
+    native_fonts: Option<NativeFonts>,
+
     fade_color: SDLColor,
 
     pub target: Surface,
@@ -185,12 +189,18 @@ impl<'a> Renderer<'a> {
             font,
             font_small,
 
+            native_fonts: None,
+
             target,
             canvas,
             fade_color: SDLColor::RGBA(0, 0, 0, 0)
         }
     }
 
+    pub fn set_native_fonts(&mut self, native_fonts: Option<NativeFonts>) {
+        self.native_fonts = native_fonts;
+    }
+
     pub fn set_title(&mut self, title: &str) {
         self.canvas.window_mut().set_title(title).unwrap();
     }
@@ -254,14 +264,20 @@ impl<'a> Renderer<'a> {
             return;
         }
 
-        let font_data = match renderable.font {
-            TextFont::Regular => &self.font,
-            TextFont::Small => &self.font_small,
-        };
-
         if renderable.surface.is_none() {
             let text = &renderable.text.chars().take(renderable.char_count_show + 1).collect::<String>();
-            if renderable.wrap_width > 0 {
+            let font_data = match renderable.font {
+                TextFont::Regular => &self.font,
+                TextFont::Small => &self.font_small,
+            };
+
+            if let Some(native_fonts) = &self.native_fonts {
+                let native_font = match renderable.font {
+                    TextFont::Regular => &native_fonts.regular,
+                    TextFont::Small => &native_fonts.small,
+                };
+                renderable.surface = Some(native_font.draw_to_surface(text, renderable.color, renderable.flags, renderable.wrap_width));
+            } else if renderable.wrap_width > 0 {
                 renderable.surface = Some(text_draw_to_surface_wrapped(text, font_data, renderable.color, renderable.flags, renderable.wrap_width));
             } else {
                 renderable.surface = Some(text_draw_to_surface(text, font_data, renderable.color, renderable.flags));
@@ -296,6 +312,13 @@ impl<'a> Renderer<'a> {
     }
 
     pub fn measure_text(&self, text: &str, font: TextFont) -> (u32, u32) {
+        if let Some(native_fonts) = &self.native_fonts {
+            return match font {
+                TextFont::Regular => native_fonts.regular.measure(text),
+                TextFont::Small => native_fonts.small.measure(text),
+            };
+        }
+
         let font_data = match font {
             TextFont::Regular => &self.font,
             TextFont::Small => &self.font_small,
@@ -0,0 +1,123 @@
+use std::fs::{create_dir_all, File};
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Component, Path};
+use crate::filesystem::backend_pc::{FileSystemBackendPc, FileSystemBackendPcMode};
+use crate::filesystem::resourcesbin::ResourcesBin;
+use crate::util::glob::glob_match;
+
+/// Prints the paths and sizes of all files in resources.bin that match any of the glob patterns,
+/// or all files if there are none.
+pub fn resources_list(resources: &ResourcesBin, patterns: &[String]) -> std::io::Result<()> {
+    let paths = matching_paths(resources, patterns);
+
+    let mut total_size = 0;
+    let mut total_packed_size = 0;
+    for path in &paths {
+        let size = resources.file_size(path)?;
+        let packed_size = resources.files[*path].size;
+        println!("{:>10} {:>10}  {}", size, packed_size, path);
+
+        total_size += size;
+        total_packed_size += packed_size;
+    }
+
+    println!("{:>10} {:>10}  {} of {} files", total_size, total_packed_size, paths.len(), resources.files.len());
+
+    Ok(())
+}
+
+/// Extracts all files in resources.bin that match any of the glob patterns, or all files if there
+/// are none, to their original directory tree in the output path. Each extracted file is read back
+/// the same way the filesystem data backend does to verify it.
+pub fn resources_extract(resources: &ResourcesBin, output_path: &Path, patterns: &[String]) -> std::io::Result<()> {
+    let paths = matching_paths(resources, patterns);
+    if paths.is_empty() {
+        return Err(Error::new(ErrorKind::NotFound, "No files match the given patterns"));
+    }
+
+    println!("Extracting {} of {} files to {}...", paths.len(), resources.files.len(), output_path.display());
+    let backend = FileSystemBackendPc::new(&output_path.into(), FileSystemBackendPcMode::FileSystem);
+    let mut total_size = 0;
+    for path in &paths {
+        let file_path = output_path.join(relative_path(path)?);
+        if let Some(parent) = file_path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        let data = resources.file_get(path).into_inner();
+        let mut file = File::create(&file_path)?;
+        file.write_all(&data)?;
+        drop(file);
+
+        let extracted = backend.file_get(path)?.into_inner();
+        if extracted != data {
+            return Err(Error::new(ErrorKind::InvalidData, format!("Extracted file {} does not match resources.bin", path)));
+        }
+
+        total_size += data.len();
+    }
+    println!("Extracted and verified {} files, {} bytes.", paths.len(), total_size);
+
+    Ok(())
+}
+
+fn matching_paths<'a>(resources: &'a ResourcesBin, patterns: &[String]) -> Vec<&'a String> {
+    resources.file_paths()
+        .into_iter()
+        .filter(|path| patterns.is_empty() || patterns.iter().any(|pattern| glob_match(pattern, path)))
+        .collect()
+}
+
+// Entry paths use forward slashes. Refuse anything that would end up outside of the output path.
+fn relative_path(path: &str) -> std::io::Result<&Path> {
+    let relative = Path::new(path);
+    if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Refusing to extract file with unsafe path {}", path)));
+    }
+
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs::{read, remove_dir_all};
+    use crate::filesystem::resourcesbin::write_resources_bin;
+    use super::*;
+
+    #[test]
+    fn extract_matching() {
+        let dir = env::temp_dir().join(format!("ctviewer_resources_extract_{}", std::process::id()));
+        if dir.exists() {
+            remove_dir_all(&dir).unwrap();
+        }
+        create_dir_all(&dir).unwrap();
+
+        let files = vec![
+            ("Game/field/Mapinfo/mapinfo_0.dat".to_string(), vec![1, 2, 3, 4]),
+            ("Localize/it/msg/w_map.txt".to_string(), "0,Zeal\r\n".as_bytes().to_vec()),
+        ];
+        write_resources_bin(&dir.join("resources.bin"), &files).unwrap();
+        let resources = ResourcesBin::new(&dir.join("resources.bin"));
+
+        let output = dir.join("out");
+        resources_extract(&resources, &output, &["**/*.dat".to_string()]).unwrap();
+        assert_eq!(read(output.join("Game/field/Mapinfo/mapinfo_0.dat")).unwrap(), files[0].1);
+        assert!(!output.join("Localize/it/msg/w_map.txt").exists());
+
+        let error = resources_extract(&resources, &output, &["*.png".to_string()]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+
+        resources_list(&resources, &[]).unwrap();
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuse_unsafe_paths() {
+        assert!(relative_path("Game/field/empty.dat").is_ok());
+        assert!(relative_path("../outside.dat").is_err());
+        assert!(relative_path("/absolute.dat").is_err());
+        assert!(relative_path("Game/../../outside.dat").is_err());
+    }
+}
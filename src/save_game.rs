@@ -0,0 +1,315 @@
+use std::fs::{create_dir_all, File};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use crate::Context;
+use crate::destination::Destination;
+use crate::facing::Facing;
+use crate::l10n::IndexedType;
+use crate::party::character::CharacterId;
+use crate::party::items::ItemId;
+use crate::party::party::{PartySlot, PARTY_SLOT_COUNT};
+use crate::util::vec2di32::Vec2Di32;
+
+/// Number of save slots, as in the game.
+pub const SAVE_SLOT_COUNT: usize = 3;
+
+const SAVE_DIRECTORY: &str = "saves";
+const SAVE_MAGIC: &[u8; 4] = b"CTVS";
+const SAVE_VERSION: u8 = 1;
+
+/// The party, story progress and location of a saved game.
+pub struct SaveGame {
+    pub scene_index: usize,
+    pub pos: Vec2Di32,
+    pub facing: Facing,
+
+    pub gold: u32,
+    pub party_slots: Vec<PartySlot>,
+    pub character_names: Vec<(CharacterId, String)>,
+    pub inventory: Vec<(ItemId, u32)>,
+
+    /// Global memory, which holds story progress flags.
+    pub global_memory: Vec<u8>,
+}
+
+impl SaveGame {
+
+    /// Saves the current game state, with the party at a position in a scene.
+    pub fn from_state(ctx: &Context, scene_index: usize, pos: Vec2Di32, facing: Facing) -> SaveGame {
+        let mut character_names: Vec<(CharacterId, String)> = ctx.party.get_characters_iter()
+            .map(|character| (character.id, character.name.clone()))
+            .collect();
+        character_names.sort();
+
+        SaveGame {
+            scene_index,
+            pos,
+            facing,
+
+            gold: ctx.party.get_gold(),
+            party_slots: ctx.party.get_party_slots().copied().collect(),
+            character_names,
+            inventory: ctx.party.get_inventory(),
+
+            global_memory: ctx.memory.global.get_data().to_vec(),
+        }
+    }
+
+    /// Restores the party and story progress. Moving to the saved location is left to the caller.
+    /// Nothing is restored if the save refers to characters that the party does not have.
+    pub fn apply(&self, ctx: &mut Context) -> std::io::Result<()> {
+        validate_party_slots(&self.party_slots)?;
+        let character_ids = self.party_slots.iter().map(|slot| slot.character_id)
+            .chain(self.character_names.iter().map(|(character_id, _)| *character_id));
+        for character_id in character_ids {
+            if ctx.party.get_character(character_id).is_none() {
+                return Err(Error::new(ErrorKind::InvalidData, format!("Unknown character {}", character_id)));
+            }
+        }
+
+        ctx.party.set_gold(self.gold);
+        ctx.party.set_party_slots(self.party_slots.clone());
+        for (character_id, name) in self.character_names.iter() {
+            ctx.party.set_character_name(*character_id, name.clone());
+        }
+        for (item_id, amount) in ctx.party.get_inventory() {
+            ctx.party.item_take(item_id, amount);
+        }
+        for (item_id, amount) in self.inventory.iter() {
+            ctx.party.item_give(*item_id, *amount);
+        }
+
+        ctx.memory.global.set_data(&self.global_memory);
+        ctx.text_processor.update_party_names(&ctx.party);
+
+        Ok(())
+    }
+
+    pub fn destination(&self) -> Destination {
+        Destination::Scene {
+            index: self.scene_index,
+            pos: self.pos,
+            facing: self.facing,
+            data: 0,
+        }
+    }
+
+    /// A single line describing this save, for listing save slots.
+    pub fn summary(&self, ctx: &Context) -> String {
+        let leader = self.party_slots.first()
+            .and_then(|slot| self.character_names.iter().find(|(character_id, _)| *character_id == slot.character_id))
+            .map(|(_, name)| name.as_str())
+            .unwrap_or("");
+
+        format!("{} - {}, {}G", leader, ctx.l10n.get_indexed(IndexedType::Scene, self.scene_index), self.gold)
+    }
+
+    pub fn slot_path(slot: usize) -> PathBuf {
+        PathBuf::from(SAVE_DIRECTORY).join(format!("slot{}.sav", slot + 1))
+    }
+
+    pub fn save(&self, slot: usize) -> std::io::Result<()> {
+        create_dir_all(SAVE_DIRECTORY)?;
+        let mut out = BufWriter::new(File::create(SaveGame::slot_path(slot))?);
+        self.write(&mut out)
+    }
+
+    pub fn load(slot: usize) -> std::io::Result<SaveGame> {
+        let mut data = BufReader::new(File::open(SaveGame::slot_path(slot))?);
+        SaveGame::read(&mut data)
+    }
+
+    pub fn write(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        out.write_all(SAVE_MAGIC)?;
+        out.write_u8(SAVE_VERSION)?;
+
+        out.write_u16::<LittleEndian>(self.scene_index as u16)?;
+        out.write_i32::<LittleEndian>(self.pos.x)?;
+        out.write_i32::<LittleEndian>(self.pos.y)?;
+        out.write_u8(self.facing.to_index() as u8)?;
+
+        out.write_u32::<LittleEndian>(self.gold)?;
+
+        out.write_u8(self.party_slots.len() as u8)?;
+        for slot in self.party_slots.iter() {
+            out.write_u8(slot.character_id as u8)?;
+            out.write_u8(slot.disabled as u8)?;
+        }
+
+        out.write_u8(self.character_names.len() as u8)?;
+        for (character_id, name) in self.character_names.iter() {
+            let name_len = u8::try_from(name.len()).map_err(|_| Error::new(ErrorKind::InvalidData, format!("Name of character {} is too long", character_id)))?;
+            out.write_u8(*character_id as u8)?;
+            out.write_u8(name_len)?;
+            out.write_all(name.as_bytes())?;
+        }
+
+        out.write_u16::<LittleEndian>(self.inventory.len() as u16)?;
+        for (item_id, amount) in self.inventory.iter() {
+            let amount = u16::try_from(*amount).map_err(|_| Error::new(ErrorKind::InvalidData, format!("Amount of item {} is too large", item_id)))?;
+            out.write_u16::<LittleEndian>(*item_id as u16)?;
+            out.write_u16::<LittleEndian>(amount)?;
+        }
+
+        out.write_u16::<LittleEndian>(self.global_memory.len() as u16)?;
+        out.write_all(&self.global_memory)?;
+
+        Ok(())
+    }
+
+    pub fn read(data: &mut dyn Read) -> std::io::Result<SaveGame> {
+        let mut magic = [0u8; 4];
+        data.read_exact(&mut magic)?;
+        if &magic != SAVE_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "Not a saved game"));
+        }
+        let version = data.read_u8()?;
+        if version != SAVE_VERSION {
+            return Err(Error::new(ErrorKind::InvalidData, format!("Unsupported saved game version {}", version)));
+        }
+
+        let scene_index = data.read_u16::<LittleEndian>()? as usize;
+        let pos = Vec2Di32::new(data.read_i32::<LittleEndian>()?, data.read_i32::<LittleEndian>()?);
+        let facing = Facing::from_data(data.read_u8()?);
+
+        let gold = data.read_u32::<LittleEndian>()?;
+
+        let mut party_slots = Vec::new();
+        for _ in 0..data.read_u8()? {
+            let character_id = data.read_u8()? as CharacterId;
+            let disabled = data.read_u8()? != 0;
+            party_slots.push(PartySlot::new(character_id, disabled));
+        }
+        validate_party_slots(&party_slots)?;
+
+        let mut character_names = Vec::new();
+        for _ in 0..data.read_u8()? {
+            let character_id = data.read_u8()? as CharacterId;
+            if character_id >= PARTY_SLOT_COUNT {
+                return Err(Error::new(ErrorKind::InvalidData, format!("Unknown character {}", character_id)));
+            }
+            let mut name = vec![0u8; data.read_u8()? as usize];
+            data.read_exact(&mut name)?;
+            let name = String::from_utf8(name).map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid character name"))?;
+            character_names.push((character_id, name));
+        }
+
+        let mut inventory = Vec::new();
+        for _ in 0..data.read_u16::<LittleEndian>()? {
+            let item_id = data.read_u16::<LittleEndian>()? as ItemId;
+            let amount = data.read_u16::<LittleEndian>()? as u32;
+            inventory.push((item_id, amount));
+        }
+
+        let mut global_memory = vec![0u8; data.read_u16::<LittleEndian>()? as usize];
+        data.read_exact(&mut global_memory)?;
+
+        Ok(SaveGame {
+            scene_index,
+            pos,
+            facing,
+
+            gold,
+            party_slots,
+            character_names,
+            inventory,
+
+            global_memory,
+        })
+    }
+}
+
+// There is one slot per character, so a valid save has each character id below the slot count.
+fn validate_party_slots(party_slots: &[PartySlot]) -> std::io::Result<()> {
+    if party_slots.len() != PARTY_SLOT_COUNT {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Expected {} party slots, not {}", PARTY_SLOT_COUNT, party_slots.len())));
+    }
+    if let Some(slot) = party_slots.iter().find(|slot| slot.character_id >= PARTY_SLOT_COUNT) {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Unknown character {}", slot.character_id)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn party_slots() -> Vec<PartySlot> {
+        vec![
+            PartySlot::new(1, false),
+            PartySlot::new(0, false),
+            PartySlot::new(2, true),
+            PartySlot::new(3, true),
+            PartySlot::new(4, true),
+            PartySlot::new(5, true),
+            PartySlot::new(6, true),
+        ]
+    }
+
+    fn save_game() -> SaveGame {
+        SaveGame {
+            scene_index: 0x1A5,
+            pos: Vec2Di32::new(136, 207),
+            facing: Facing::Left,
+
+            gold: 1200,
+            party_slots: party_slots(),
+            character_names: vec![(0, "Chrono".to_string()), (1, "Nadia".to_string())],
+            inventory: vec![(1, 3), (200, 1)],
+
+            global_memory: vec![0x12, 0x00, 0x80],
+        }
+    }
+
+    #[test]
+    fn save_game_round_trip() {
+        let save = save_game();
+
+        let mut out = Vec::new();
+        save.write(&mut out).unwrap();
+        let loaded = SaveGame::read(&mut out.as_slice()).unwrap();
+
+        assert_eq!(loaded.scene_index, 0x1A5);
+        assert_eq!(loaded.pos, Vec2Di32::new(136, 207));
+        assert_eq!(loaded.facing, Facing::Left);
+        assert_eq!(loaded.gold, 1200);
+        let slot_states = |slots: &[PartySlot]| slots.iter().map(|slot| (slot.character_id, slot.disabled)).collect::<Vec<_>>();
+        assert_eq!(slot_states(&loaded.party_slots), slot_states(&save.party_slots));
+        assert_eq!(loaded.character_names, save.character_names);
+        assert_eq!(loaded.inventory, save.inventory);
+        assert_eq!(loaded.global_memory, save.global_memory);
+
+        assert!(SaveGame::read(&mut &b"CTVS\x02"[..]).is_err());
+    }
+
+    #[test]
+    fn save_game_invalid() {
+        let mut save = save_game();
+        save.party_slots.pop();
+        let mut out = Vec::new();
+        save.write(&mut out).unwrap();
+        assert_eq!(SaveGame::read(&mut out.as_slice()).err().map(|error| error.kind()), Some(ErrorKind::InvalidData));
+
+        let mut save = save_game();
+        save.party_slots[6].character_id = 9;
+        let mut out = Vec::new();
+        save.write(&mut out).unwrap();
+        assert_eq!(SaveGame::read(&mut out.as_slice()).err().map(|error| error.kind()), Some(ErrorKind::InvalidData));
+
+        let mut save = save_game();
+        save.character_names.push((9, "Nobody".to_string()));
+        let mut out = Vec::new();
+        save.write(&mut out).unwrap();
+        assert_eq!(SaveGame::read(&mut out.as_slice()).err().map(|error| error.kind()), Some(ErrorKind::InvalidData));
+
+        let mut save = save_game();
+        save.character_names[0].1 = "A".repeat(256);
+        assert_eq!(save.write(&mut Vec::new()).err().map(|error| error.kind()), Some(ErrorKind::InvalidData));
+
+        let mut save = save_game();
+        save.inventory[0].1 = u16::MAX as u32 + 1;
+        assert_eq!(save.write(&mut Vec::new()).err().map(|error| error.kind()), Some(ErrorKind::InvalidData));
+    }
+}
@@ -94,6 +94,9 @@ bitflags! {
 
         /// Actor is outputting lines into a textbox.
         const TEXTBOX_ACTIVE = 0x1000;
+
+        /// Actor has opened a special dialogue, such as a shop.
+        const SPECIAL_DIALOGUE_ACTIVE = 0x2000;
     }
 }
 
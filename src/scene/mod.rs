@@ -3,6 +3,7 @@ pub mod scene_map;
 pub mod scene_renderer;
 pub mod textbox;
 pub mod textbox_layout;
+pub mod special_dialogue;
 pub mod actor;
 pub mod treasure;
 pub mod scene_exit;
@@ -10,6 +10,9 @@ use crate::scene::treasure::Treasure;
 use crate::scene_script::scene_script::SceneScript;
 use crate::tileset::TileSet;
 
+// Number of scenes in the game.
+pub const SCENE_COUNT: usize = 512;
+
 pub struct ScrollMask {
     pub left: isize,
     pub top: isize,
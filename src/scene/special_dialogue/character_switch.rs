@@ -0,0 +1,79 @@
+use crate::Context;
+use crate::gamestate::gamestate_scene::SceneState;
+use crate::input::InputAction;
+use crate::party::party::PARTY_LEN;
+use crate::scene::special_dialogue::{render_list, select_next, select_previous, ListRow, SpecialDialogueTrait};
+
+const CHARACTER_SWITCH_CHIP_WIDTH: i32 = 24;
+
+/// Lists the active party and the recruited reserve characters. Confirming two entries swaps them.
+pub struct CharacterSwitchDialogue {
+
+    /// Party slot indices that are listed: all active party slots, and the enabled reserve slots.
+    slots: Vec<usize>,
+
+    selected: usize,
+
+    /// The first entry of a swap, once confirmed.
+    swap_from: Option<usize>,
+}
+
+impl CharacterSwitchDialogue {
+    pub fn new(ctx: &mut Context) -> CharacterSwitchDialogue {
+        let slots = ctx.party.get_party_slots().enumerate()
+            .filter(|(index, slot)| *index < PARTY_LEN || !slot.disabled)
+            .map(|(index, _)| index)
+            .collect();
+
+        CharacterSwitchDialogue {
+            slots,
+            selected: 0,
+            swap_from: None,
+        }
+    }
+}
+
+impl SpecialDialogueTrait for CharacterSwitchDialogue {
+    fn tick(&mut self, ctx: &mut Context, _scene_state: &mut SceneState) -> bool {
+        // Cancel undoes the first entry of a swap, or closes the dialogue.
+        if ctx.input.was_pressed(InputAction::MenuCancel) && self.swap_from.take().is_none() {
+            ctx.text_processor.update_party_names(&ctx.party);
+            return false;
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuUp) {
+            self.selected = select_previous(self.selected, self.slots.len());
+        } else if ctx.input.was_pressed(InputAction::MenuDown) {
+            self.selected = select_next(self.selected, self.slots.len());
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuConfirm) {
+            match self.swap_from.take() {
+                Some(swap_from) => ctx.party.swap_party_slots(self.slots[swap_from], self.slots[self.selected]),
+                None => self.swap_from = Some(self.selected),
+            }
+        }
+
+        true
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        let slots: Vec<_> = ctx.party.get_party_slots().copied().collect();
+        let rows: Vec<ListRow> = self.slots.iter().enumerate()
+            .map(|(index, slot_index)| {
+                let slot = slots[*slot_index];
+                let name = match ctx.party.get_character(slot.character_id) {
+                    Some(character) if !slot.disabled => character.name.clone(),
+                    _ => "-".to_string(),
+                };
+                let label = if *slot_index < PARTY_LEN { "Party" } else { "Reserve" };
+                let row = ListRow::new(name, label.to_string());
+                if self.swap_from == Some(index) { row.disabled() } else { row }
+            })
+            .collect();
+
+        let x = ctx.render.target.width as i32 / 2 - CHARACTER_SWITCH_CHIP_WIDTH * 4;
+        let y = ctx.render.target.height as i32 / 2 - (self.slots.len() as i32 * 2 + 2) * 4;
+        render_list(ctx, x, y, CHARACTER_SWITCH_CHIP_WIDTH, self.slots.len(), &rows, Some(self.selected));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::Context;
+use crate::gamestate::gamestate_scene::SceneState;
+use crate::renderer::{TextFlags, TextFont, TextRenderable};
+use crate::scene::special_dialogue::character_switch::CharacterSwitchDialogue;
+use crate::scene::special_dialogue::rename::RenameDialogue;
+use crate::scene::special_dialogue::save_load::SaveLoadDialogue;
+use crate::scene::special_dialogue::shop::ShopDialogue;
+use crate::scene_script::decoder::ops_textbox::UiType;
+use crate::software_renderer::blit::{blit_bitmap_to_surface, BitmapBlitFlags};
+use crate::software_renderer::text::TextDrawFlags;
+
+pub mod character_switch;
+pub mod rename;
+pub mod save_load;
+pub mod shop;
+
+const SPECIAL_DIALOGUE_TEXT_COLOR: [u8; 4] = [231, 231, 231, 255];
+const SPECIAL_DIALOGUE_DISABLED_COLOR: [u8; 4] = [140, 140, 140, 255];
+const SPECIAL_DIALOGUE_LINE_HEIGHT: i32 = 16;
+
+/// A dialogue opened by a scene script, such as a shop. The script waits until it is closed.
+pub trait SpecialDialogueTrait {
+
+    /// Handles input. Returns false once the dialogue is closed.
+    fn tick(&mut self, ctx: &mut Context, scene_state: &mut SceneState) -> bool;
+
+    fn render(&mut self, ctx: &mut Context);
+}
+
+pub fn special_dialogue_create(ctx: &mut Context, ui: UiType) -> Box<dyn SpecialDialogueTrait> {
+    match ui {
+        UiType::Shop(shop_index) => Box::new(ShopDialogue::new(ctx, shop_index)),
+        UiType::RenamePC(character_id) => Box::new(RenameDialogue::new(ctx, character_id)),
+        UiType::Save(_) => Box::new(SaveLoadDialogue::new(ctx, true)),
+        UiType::Load(_) => Box::new(SaveLoadDialogue::new(ctx, false)),
+        UiType::CharacterSwitch => Box::new(CharacterSwitchDialogue::new(ctx)),
+    }
+}
+
+/// A line of a list window, with a label on the left and an optional value on the right.
+pub struct ListRow {
+    pub label: String,
+    pub value: String,
+    pub enabled: bool,
+}
+
+impl ListRow {
+    pub fn new(label: String, value: String) -> ListRow {
+        ListRow {
+            label,
+            value,
+            enabled: true,
+        }
+    }
+
+    pub fn disabled(mut self) -> ListRow {
+        self.enabled = false;
+        self
+    }
+}
+
+pub fn render_text(ctx: &mut Context, text: &str, x: i32, y: i32, flags: TextFlags) {
+    render_text_color(ctx, text, x, y, flags, SPECIAL_DIALOGUE_TEXT_COLOR);
+}
+
+fn render_text_color(ctx: &mut Context, text: &str, x: i32, y: i32, flags: TextFlags, color: [u8; 4]) {
+    let mut renderable = TextRenderable::new(text.to_string(), TextFont::Regular, color, TextDrawFlags::SHADOW, 0);
+    ctx.render.render_text(&mut renderable, x, y, flags);
+}
+
+pub fn render_cursor(ctx: &mut Context, x: i32, y: i32) {
+    blit_bitmap_to_surface(&ctx.ui_theme.cursor_bitmap, &mut ctx.render.target, 0, 0, 16, 16, x, y, &ctx.ui_theme.cursor_palette, 0, BitmapBlitFlags::SKIP_0);
+}
+
+/// Renders a window with a row per line, and the cursor before the selected row. The window is
+/// sized to fit line_count rows so that it does not change size with the number of rows.
+pub fn render_list(ctx: &mut Context, x: i32, y: i32, chip_width: i32, line_count: usize, rows: &[ListRow], selected: Option<usize>) {
+    let chip_height = line_count as i32 * 2 + 2;
+    ctx.ui_theme.render_window(&mut ctx.render.target, x, y, chip_width, chip_height);
+
+    for (index, row) in rows.iter().enumerate().take(line_count) {
+        let row_y = y + 8 + index as i32 * SPECIAL_DIALOGUE_LINE_HEIGHT;
+        let color = if row.enabled { SPECIAL_DIALOGUE_TEXT_COLOR } else { SPECIAL_DIALOGUE_DISABLED_COLOR };
+        render_text_color(ctx, &row.label, x + 24, row_y, TextFlags::empty(), color);
+        if !row.value.is_empty() {
+            render_text_color(ctx, &row.value, x + chip_width * 8 - 16, row_y, TextFlags::AlignHEnd, color);
+        }
+
+        if selected == Some(index) {
+            render_cursor(ctx, x + 5, row_y + 1);
+        }
+    }
+}
+
+/// Moves a selection up or down by one, wrapping around at the ends.
+pub fn select_previous(selected: usize, count: usize) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    (selected + count - 1) % count
+}
+
+pub fn select_next(selected: usize, count: usize) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    (selected + 1) % count
+}
+
+/// Returns the first row to show so that the selected row is visible in a list of line_count rows.
+pub fn list_scroll(scroll: usize, selected: usize, line_count: usize) -> usize {
+    if selected < scroll {
+        selected
+    } else if selected >= scroll + line_count {
+        selected + 1 - line_count
+    } else {
+        scroll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_dialogue_selection() {
+        assert_eq!(select_previous(0, 3), 2);
+        assert_eq!(select_next(2, 3), 0);
+        assert_eq!(select_next(0, 0), 0);
+
+        assert_eq!(list_scroll(0, 7, 8), 0);
+        assert_eq!(list_scroll(0, 8, 8), 1);
+        assert_eq!(list_scroll(5, 2, 8), 2);
+    }
+}
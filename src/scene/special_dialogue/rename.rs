@@ -0,0 +1,141 @@
+use crate::Context;
+use crate::gamestate::gamestate_scene::SceneState;
+use crate::input::InputAction;
+use crate::party::character::CharacterId;
+use crate::renderer::TextFlags;
+use crate::scene::special_dialogue::{render_cursor, render_text, SpecialDialogueTrait};
+
+/// Maximum length of a name, as in the game.
+const RENAME_MAX_LENGTH: usize = 5;
+
+// Character id that the game uses to rename the Epoch.
+const RENAME_EPOCH_ID: CharacterId = 7;
+
+const RENAME_CHIP_WIDTH: i32 = 30;
+const RENAME_CELL_WIDTH: i32 = 16;
+const RENAME_CELL_HEIGHT: i32 = 16;
+
+// Characters that can be entered. The row after these holds only the end entry.
+const RENAME_GRID: [&str; 5] = [
+    "ABCDEFGHIJKLM",
+    "NOPQRSTUVWXYZ",
+    "abcdefghijklm",
+    "nopqrstuvwxyz",
+    "0123456789.-'",
+];
+const RENAME_END_ROW: usize = RENAME_GRID.len();
+
+/// Lets the player enter a new name for a character or the Epoch. Confirm adds the selected
+/// character or ends, cancel removes the last character.
+pub struct RenameDialogue {
+    character_id: CharacterId,
+    default_name: String,
+    name: String,
+
+    column: usize,
+    row: usize,
+}
+
+impl RenameDialogue {
+    pub fn new(ctx: &mut Context, character_id: CharacterId) -> RenameDialogue {
+        let default_name = if character_id == RENAME_EPOCH_ID {
+            ctx.text_processor.get_epoch_name()
+        } else {
+            match ctx.party.get_character(character_id) {
+                Some(character) => character.name.clone(),
+                None => {
+                    println!("Cannot rename unknown character {}.", character_id);
+                    String::new()
+                },
+            }
+        };
+
+        RenameDialogue {
+            character_id,
+            name: default_name.clone(),
+            default_name,
+
+            column: 0,
+            row: 0,
+        }
+    }
+
+    fn selected_char(&self) -> Option<char> {
+        RENAME_GRID.get(self.row)?.chars().nth(self.column)
+    }
+
+    fn apply(&self, ctx: &mut Context) {
+        let name = if self.name.is_empty() { self.default_name.clone() } else { self.name.clone() };
+        if self.character_id == RENAME_EPOCH_ID {
+            ctx.text_processor.update_epoch_name(&name);
+        } else {
+            ctx.party.set_character_name(self.character_id, name);
+            ctx.text_processor.update_party_names(&ctx.party);
+        }
+    }
+}
+
+impl SpecialDialogueTrait for RenameDialogue {
+    fn tick(&mut self, ctx: &mut Context, _scene_state: &mut SceneState) -> bool {
+        if self.character_id != RENAME_EPOCH_ID && ctx.party.get_character(self.character_id).is_none() {
+            return false;
+        }
+
+        let row_count = RENAME_GRID.len() + 1;
+        let column_count = RENAME_GRID[0].len();
+        if ctx.input.was_pressed(InputAction::MenuUp) {
+            self.row = (self.row + row_count - 1) % row_count;
+        } else if ctx.input.was_pressed(InputAction::MenuDown) {
+            self.row = (self.row + 1) % row_count;
+        } else if ctx.input.was_pressed(InputAction::MenuLeft) {
+            self.column = (self.column + column_count - 1) % column_count;
+        } else if ctx.input.was_pressed(InputAction::MenuRight) {
+            self.column = (self.column + 1) % column_count;
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuCancel) {
+            self.name.pop();
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuConfirm) {
+            if self.row == RENAME_END_ROW {
+                self.apply(ctx);
+                return false;
+            }
+            if let Some(c) = self.selected_char() && self.name.chars().count() < RENAME_MAX_LENGTH {
+                self.name.push(c);
+            }
+        }
+
+        true
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        let x = ctx.render.target.width as i32 / 2 - RENAME_CHIP_WIDTH * 4;
+
+        // Name being entered, with a marker for the next character.
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, 8, RENAME_CHIP_WIDTH, 4);
+        let mut name = self.name.clone();
+        if name.chars().count() < RENAME_MAX_LENGTH {
+            name.push('_');
+        }
+        render_text(ctx, &name, x + 24, 16, TextFlags::empty());
+
+        let chip_height = (RENAME_GRID.len() as i32 + 1) * 2 + 2;
+        let grid_y = 48;
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, grid_y, RENAME_CHIP_WIDTH, chip_height);
+        for (row, chars) in RENAME_GRID.iter().enumerate() {
+            for (column, c) in chars.chars().enumerate() {
+                let cell_x = x + 24 + column as i32 * RENAME_CELL_WIDTH;
+                let cell_y = grid_y + 8 + row as i32 * RENAME_CELL_HEIGHT;
+                render_text(ctx, &c.to_string(), cell_x, cell_y, TextFlags::empty());
+            }
+        }
+        render_text(ctx, "End", x + 24, grid_y + 8 + RENAME_END_ROW as i32 * RENAME_CELL_HEIGHT, TextFlags::empty());
+
+        let column = if self.row == RENAME_END_ROW { 0 } else { self.column };
+        let cursor_x = x + 24 + column as i32 * RENAME_CELL_WIDTH - 15;
+        let cursor_y = grid_y + 8 + self.row as i32 * RENAME_CELL_HEIGHT + 1;
+        render_cursor(ctx, cursor_x, cursor_y);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::Context;
+use crate::gamestate::gamestate_scene::SceneState;
+use crate::input::InputAction;
+use crate::renderer::TextFlags;
+use crate::save_game::{SaveGame, SAVE_SLOT_COUNT};
+use crate::scene::special_dialogue::{render_list, render_text, select_next, select_previous, ListRow, SpecialDialogueTrait};
+
+const SAVE_LOAD_CHIP_WIDTH: i32 = 30;
+
+/// Lists the save slots to save the game to, or to load a game from.
+pub struct SaveLoadDialogue {
+    save: bool,
+    selected: usize,
+
+    /// Summary of the game in each slot, or None if the slot is empty or unreadable.
+    summaries: Vec<Option<String>>,
+
+    /// Result of the last save or load attempt.
+    message: Option<String>,
+}
+
+impl SaveLoadDialogue {
+    pub fn new(ctx: &mut Context, save: bool) -> SaveLoadDialogue {
+        let mut dialogue = SaveLoadDialogue {
+            save,
+            selected: 0,
+            summaries: Vec::new(),
+            message: None,
+        };
+        dialogue.update_summaries(ctx);
+
+        dialogue
+    }
+
+    fn update_summaries(&mut self, ctx: &Context) {
+        self.summaries = (0..SAVE_SLOT_COUNT)
+            .map(|slot| SaveGame::load(slot).ok().map(|save| save.summary(ctx)))
+            .collect();
+    }
+
+    fn save(&mut self, ctx: &mut Context, scene_state: &SceneState) {
+
+        // Save at the position of the party leader.
+        let leader = ctx.party.get_active_party_slots().next().map(|slot| slot.character_id);
+        let (pos, facing) = match leader.and_then(|character_id| scene_state.player_actors.get(&character_id)) {
+            Some(actor_index) => {
+                let actor = &scene_state.actors[*actor_index];
+                (actor.pos.as_vec2d_i32(), actor.facing)
+            },
+            None => (scene_state.enter_position.as_vec2d_i32(), scene_state.enter_facing),
+        };
+
+        let save = SaveGame::from_state(ctx, scene_state.scene_index, pos, facing);
+        match save.save(self.selected) {
+            Ok(()) => {
+                println!("Saved game to {}.", SaveGame::slot_path(self.selected).display());
+                self.message = Some("Saved.".to_string());
+                self.update_summaries(ctx);
+            },
+            Err(error) => {
+                println!("Could not save game to {}: {}", SaveGame::slot_path(self.selected).display(), error);
+                self.message = Some("Could not save.".to_string());
+            },
+        }
+    }
+
+    // Returns true if a game was loaded.
+    fn load(&mut self, ctx: &mut Context, scene_state: &mut SceneState) -> bool {
+        let save = match SaveGame::load(self.selected) {
+            Ok(save) => save,
+            Err(error) => {
+                println!("Could not load game from {}: {}", SaveGame::slot_path(self.selected).display(), error);
+                self.message = Some("Could not load.".to_string());
+                return false;
+            },
+        };
+
+        if let Err(error) = save.apply(ctx) {
+            println!("Could not load game from {}: {}", SaveGame::slot_path(self.selected).display(), error);
+            self.message = Some("Could not load.".to_string());
+            return false;
+        }
+        println!("Loaded game from {}.", SaveGame::slot_path(self.selected).display());
+        ctx.screen_fade.start(0.0, 2);
+        scene_state.next_destination.set(save.destination(), true);
+
+        true
+    }
+}
+
+impl SpecialDialogueTrait for SaveLoadDialogue {
+    fn tick(&mut self, ctx: &mut Context, scene_state: &mut SceneState) -> bool {
+        if ctx.input.was_pressed(InputAction::MenuCancel) {
+            return false;
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuUp) {
+            self.selected = select_previous(self.selected, SAVE_SLOT_COUNT);
+            self.message = None;
+        } else if ctx.input.was_pressed(InputAction::MenuDown) {
+            self.selected = select_next(self.selected, SAVE_SLOT_COUNT);
+            self.message = None;
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuConfirm) {
+            if self.save {
+                self.save(ctx, scene_state);
+            } else if self.load(ctx, scene_state) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        let x = ctx.render.target.width as i32 / 2 - SAVE_LOAD_CHIP_WIDTH * 4;
+
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, 8, SAVE_LOAD_CHIP_WIDTH, 4);
+        let title = match &self.message {
+            Some(message) => message.as_str(),
+            None => if self.save { "Save to which slot?" } else { "Load which slot?" },
+        };
+        render_text(ctx, title, x + 16, 16, TextFlags::empty());
+
+        let rows: Vec<ListRow> = self.summaries.iter().enumerate()
+            .map(|(slot, summary)| match summary {
+                Some(summary) => ListRow::new(format!("{}: {}", slot + 1, summary), String::new()),
+                None => ListRow::new(format!("{}: Empty", slot + 1), String::new()).disabled(),
+            })
+            .collect();
+        render_list(ctx, x, 48, SAVE_LOAD_CHIP_WIDTH, SAVE_SLOT_COUNT, &rows, Some(self.selected));
+    }
+}
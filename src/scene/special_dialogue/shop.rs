@@ -0,0 +1,184 @@
+use crate::Context;
+use crate::gamestate::gamestate_scene::SceneState;
+use crate::input::InputAction;
+use crate::l10n::IndexedType;
+use crate::party::items::ItemId;
+use crate::renderer::TextFlags;
+use crate::scene::special_dialogue::{list_scroll, render_cursor, render_list, render_text, select_next, select_previous, ListRow, SpecialDialogueTrait};
+
+const SHOP_CHIP_WIDTH: i32 = 30;
+const SHOP_LINE_COUNT: usize = 8;
+
+#[derive(Clone, Copy, PartialEq)]
+enum ShopMode {
+    Buy,
+    Sell,
+}
+
+/// Lists the items of a shop to buy, or the items in the inventory to sell. Left and right switch
+/// between buying and selling.
+pub struct ShopDialogue {
+    shop_index: usize,
+
+    /// Items sold in this shop, if known.
+    items: Option<Vec<ItemId>>,
+
+    /// Prices of all items, if known.
+    prices: Option<Vec<u32>>,
+
+    mode: ShopMode,
+    selected: usize,
+    scroll: usize,
+}
+
+impl ShopDialogue {
+    pub fn new(ctx: &mut Context, shop_index: usize) -> ShopDialogue {
+        let items = ctx.fs.read_shop_items(shop_index).unwrap_or_else(|error| {
+            println!("Could not read items of shop {}: {}", shop_index, error);
+            None
+        });
+        let prices = ctx.fs.read_item_prices().unwrap_or_else(|error| {
+            println!("Could not read item prices: {}", error);
+            None
+        });
+        if items.is_none() || prices.is_none() {
+            println!("Shop {} items or prices are not available for this game data.", shop_index);
+        }
+
+        ShopDialogue {
+            shop_index,
+            items,
+            prices,
+
+            mode: ShopMode::Buy,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    fn price(&self, item_id: ItemId) -> Option<u32> {
+        self.prices.as_ref()?.get(item_id).copied()
+    }
+
+    // Items are sold for half their price, as in the game.
+    fn sell_price(&self, item_id: ItemId) -> Option<u32> {
+        self.price(item_id).map(|price| price / 2)
+    }
+
+    // Items that can be selected in the current mode.
+    fn list_items(&self, ctx: &Context) -> Vec<(ItemId, u32)> {
+        match self.mode {
+            ShopMode::Buy => self.items.iter().flatten()
+                .map(|item_id| (*item_id, ctx.party.get_item_amount(*item_id)))
+                .collect(),
+            ShopMode::Sell => ctx.party.get_inventory(),
+        }
+    }
+
+    fn set_mode(&mut self, mode: ShopMode) {
+        self.mode = mode;
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    fn confirm(&mut self, ctx: &mut Context) {
+        let items = self.list_items(ctx);
+        let Some((item_id, _)) = items.get(self.selected).copied() else {
+            return;
+        };
+        let item_name = ctx.l10n.get_indexed(IndexedType::Item, item_id);
+
+        match self.mode {
+            ShopMode::Buy => {
+                let Some(price) = self.price(item_id) else {
+                    return;
+                };
+                if !ctx.party.item_buy(item_id, price) {
+                    println!("Not enough gold to buy {}.", item_name);
+                }
+            },
+            ShopMode::Sell => {
+                let Some(price) = self.sell_price(item_id) else {
+                    return;
+                };
+                ctx.party.item_sell(item_id, price);
+
+                // Keep the selection in the list if the last of an item was sold.
+                let count = self.list_items(ctx).len();
+                self.selected = self.selected.min(count.saturating_sub(1));
+            },
+        }
+    }
+}
+
+impl SpecialDialogueTrait for ShopDialogue {
+    fn tick(&mut self, ctx: &mut Context, _scene_state: &mut SceneState) -> bool {
+        if ctx.input.was_pressed(InputAction::MenuCancel) {
+            return false;
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuLeft) {
+            self.set_mode(ShopMode::Buy);
+        } else if ctx.input.was_pressed(InputAction::MenuRight) {
+            self.set_mode(ShopMode::Sell);
+        }
+
+        let count = self.list_items(ctx).len();
+        if ctx.input.was_pressed(InputAction::MenuUp) {
+            self.selected = select_previous(self.selected, count);
+        } else if ctx.input.was_pressed(InputAction::MenuDown) {
+            self.selected = select_next(self.selected, count);
+        }
+
+        if ctx.input.was_pressed(InputAction::MenuConfirm) {
+            self.confirm(ctx);
+        }
+        self.scroll = list_scroll(self.scroll, self.selected, SHOP_LINE_COUNT);
+
+        true
+    }
+
+    fn render(&mut self, ctx: &mut Context) {
+        let x = ctx.render.target.width as i32 / 2 - SHOP_CHIP_WIDTH * 4;
+
+        // Mode and gold.
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, 8, SHOP_CHIP_WIDTH, 4);
+        render_text(ctx, "Buy", x + 24, 16, TextFlags::empty());
+        render_text(ctx, "Sell", x + 80, 16, TextFlags::empty());
+        render_cursor(ctx, x + if self.mode == ShopMode::Buy { 5 } else { 61 }, 17);
+        let gold = format!("{}G", ctx.party.get_gold());
+        render_text(ctx, &gold, x + SHOP_CHIP_WIDTH * 8 - 16, 16, TextFlags::AlignHEnd);
+
+        let items = self.list_items(ctx);
+        let mut rows: Vec<ListRow> = Vec::new();
+        if items.is_empty() {
+            let message = if self.mode == ShopMode::Buy && self.items.is_none() {
+                format!("Shop {} data is not available", self.shop_index)
+            } else {
+                "No items".to_string()
+            };
+            rows.push(ListRow::new(message, String::new()).disabled());
+        }
+
+        for (item_id, amount) in items.iter().skip(self.scroll) {
+            let name = ctx.l10n.get_indexed(IndexedType::Item, *item_id);
+            let row = match self.mode {
+                ShopMode::Buy => match self.price(*item_id) {
+                    Some(price) => {
+                        let row = ListRow::new(format!("{} ({})", name, amount), format!("{}G", price));
+                        if price > ctx.party.get_gold() { row.disabled() } else { row }
+                    },
+                    None => ListRow::new(name, "-".to_string()).disabled(),
+                },
+                ShopMode::Sell => match self.sell_price(*item_id) {
+                    Some(price) => ListRow::new(format!("{} ({})", name, amount), format!("{}G", price)),
+                    None => ListRow::new(format!("{} ({})", name, amount), "-".to_string()).disabled(),
+                },
+            };
+            rows.push(row);
+        }
+
+        let selected = if items.is_empty() { None } else { Some(self.selected - self.scroll) };
+        render_list(ctx, x, 48, SHOP_CHIP_WIDTH, SHOP_LINE_COUNT, &rows, selected);
+    }
+}
@@ -9,9 +9,14 @@ const TEXTBOX_CHIP_WIDTH_SNES: i32 = 32;
 const TEXTBOX_CHIP_WIDTH_PC: i32 = 44;
 const TEXTBOX_CHIP_HEIGHT: i32 = 10;
 const TEXTBOX_LINE_HEIGHT: i32 = 16;
-const TEXTBOX_ANIMATE_PIXELS_PER_SECOND: f64 = 6.0;
+
+// Characters shown per second at the fastest text speed. Slower speeds divide this.
 const TEXTBOX_SHOW_CHARS_PER_SECOND: f64 = 60.0;
 
+// Automatically positioned textboxes are shown at the bottom if the speaking actor is above this
+// screen position, and at the top otherwise.
+const TEXTBOX_AUTO_POSITION_SPLIT_Y: f64 = 130.0;
+
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub enum TextBoxPosition {
     Top,
@@ -44,10 +49,17 @@ pub struct TextBox {
     current_page: usize,
     layout: TextBoxLayout,
 
+    // The requested position, and where the current page is actually shown.
     position: TextBoxPosition,
+    page_position: TextBoxPosition,
+    page_position_pending: bool,
+
     source_actor_index: Option<usize>,
     wait: u32,
 
+    // Seconds that the current page has been complete, for auto-advance.
+    complete_time: f64,
+
     choice_lines: Option<[usize; 2]>,
     current_choice: usize,
 
@@ -70,9 +82,14 @@ impl TextBox {
             layout: TextBoxLayout::empty(),
 
             position: TextBoxPosition::Bottom,
+            page_position: TextBoxPosition::Bottom,
+            page_position_pending: false,
+
             source_actor_index: None,
             wait: 0,
 
+            complete_time: 0.0,
+
             choice_lines: None,
             current_choice: 0,
 
@@ -85,9 +102,10 @@ impl TextBox {
             return;
         }
 
+        // Pause typing, or wait before automatically advancing to the next page.
         if self.wait > 0 {
             self.wait -= 1;
-            if self.wait == 0 {
+            if self.wait == 0 && self.state == TextBoxState::Waiting && self.layout.auto_advance {
                 self.advance(ctx);
             }
             return;
@@ -98,7 +116,7 @@ impl TextBox {
             // Expand the window while showing.
             TextBoxState::Showing { visibility, last_visibility } => {
                 *last_visibility = *visibility;
-                *visibility += delta * TEXTBOX_ANIMATE_PIXELS_PER_SECOND;
+                *visibility += delta * ctx.settings.textbox_animation_speed;
                 if *visibility >= 1.0 {
                     *visibility = 1.0;
                     self.state = TextBoxState::Typing {
@@ -111,7 +129,7 @@ impl TextBox {
             // Type out one character per tick.
             TextBoxState::Typing { current_item, current_character } => {
                 let item = &mut self.layout.items[*current_item];
-                *current_character += delta * TEXTBOX_SHOW_CHARS_PER_SECOND;
+                *current_character += delta * chars_per_second(ctx.settings.text_speed);
 
                 // End of item.
                 if *current_character as usize >= item.renderable.get_char_count() {
@@ -124,6 +142,19 @@ impl TextBox {
                         *current_character = 0.0;
                     } else {
                         self.state = TextBoxState::Waiting;
+                        if self.wait == 0 && self.layout.auto_advance {
+                            self.advance(ctx);
+                        }
+                    }
+                }
+            },
+
+            // Continue by itself after a delay if auto-advance is enabled. Choices still need input.
+            TextBoxState::Waiting => {
+                if let Some(delay) = ctx.settings.auto_advance_delay && self.layout.choices.is_empty() {
+                    self.complete_time += delta;
+                    if self.complete_time >= delay {
+                        self.advance(ctx);
                     }
                 }
             },
@@ -131,7 +162,7 @@ impl TextBox {
             // Contract the window while hiding.
             TextBoxState::Hiding { visibility, last_visibility } => {
                 *last_visibility = *visibility;
-                *visibility -= delta * TEXTBOX_ANIMATE_PIXELS_PER_SECOND;
+                *visibility -= delta * ctx.settings.textbox_animation_speed;
                 if *visibility <= 0.0 {
                     self.source_actor_index = None;
                     self.state = TextBoxState::Disabled;
@@ -152,13 +183,39 @@ impl TextBox {
         self.source_actor_index
     }
 
+    pub fn get_position(&self) -> TextBoxPosition {
+        self.position
+    }
+
+    /// Where the current page is shown. This is never Auto.
+    pub fn get_page_position(&self) -> TextBoxPosition {
+        self.page_position
+    }
+
+    /// Places an automatically positioned textbox relative to the speaking actor's screen position.
+    /// This is done once at the start of each page, so the camera can move between pages.
+    pub fn update_page_position(&mut self, actor_screen_y: f64) {
+        if !self.page_position_pending {
+            return;
+        }
+        self.page_position_pending = false;
+
+        if self.position == TextBoxPosition::Auto {
+            self.page_position = auto_position(actor_screen_y);
+        }
+    }
+
     pub fn show(&mut self, ctx: &mut Context, text: String, position: TextBoxPosition, actor_index: usize, choice_lines: Option<[usize; 2]>, result_value: u32, result_item: String) {
         self.state = TextBoxState::Showing {
             visibility: 0.0,
             last_visibility: 0.0,
         };
         self.position = position;
+        self.page_position = if position == TextBoxPosition::Auto { TextBoxPosition::Bottom } else { position };
+        self.page_position_pending = true;
 
+        // Names of the active party can change between textboxes.
+        ctx.text_processor.update_party_names(&ctx.party);
         self.pages = ctx.text_processor.process_dialog_text(text.as_str(), result_value, result_item);
         self.current_page = 0;
 
@@ -166,8 +223,10 @@ impl TextBox {
 
         self.current_choice = 0;
         self.choice_lines = choice_lines;
+        self.complete_time = 0.0;
 
         self.layout_current_page(ctx);
+        ctx.dialogue_log.add_page(self.source_actor_index, &self.pages[self.current_page]);
     }
 
     pub fn choice_previous(&mut self) {
@@ -205,12 +264,15 @@ impl TextBox {
     }
 
     fn advance(&mut self, ctx: &mut Context) {
+        self.complete_time = 0.0;
 
         // Advance to next page.
         if self.current_page < self.pages.len() - 1 {
             self.current_page += 1;
             self.current_choice = 0;
+            self.page_position_pending = true;
             self.layout_current_page(ctx);
+            ctx.dialogue_log.add_page(self.source_actor_index, &self.pages[self.current_page]);
 
             self.state = TextBoxState::Typing {
                 current_item: 0,
@@ -236,10 +298,9 @@ impl TextBox {
         let mut window_height = self.window_surface.height as i32;
         let mut src_y = 0;
         let dest_x = ctx.render.target.width as i32 / 2 - window_width / 2;
-        let mut dest_y = match self.position {
-            TextBoxPosition::Top => 0,
+        let mut dest_y = match self.page_position {
+            TextBoxPosition::Top | TextBoxPosition::Auto => 0,
             TextBoxPosition::Bottom => ctx.render.target.height as i32 - window_height,
-            TextBoxPosition::Auto => 0,
         };
         match self.state {
             TextBoxState::Showing { visibility, last_visibility } |
@@ -305,3 +366,35 @@ impl TextBox {
         self.layout = TextBoxLayout::from_page(ctx, &self.pages[self.current_page], self.window_surface.width as i32 - 16, TEXTBOX_LINE_HEIGHT, choice_lines);
     }
 }
+
+fn chars_per_second(text_speed: usize) -> f64 {
+    TEXTBOX_SHOW_CHARS_PER_SECOND / (text_speed + 1) as f64
+}
+
+fn auto_position(actor_screen_y: f64) -> TextBoxPosition {
+    if actor_screen_y < TEXTBOX_AUTO_POSITION_SPLIT_Y {
+        TextBoxPosition::Bottom
+    } else {
+        TextBoxPosition::Top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textbox_auto_position() {
+        assert_eq!(auto_position(-20.0), TextBoxPosition::Bottom);
+        assert_eq!(auto_position(129.0), TextBoxPosition::Bottom);
+        assert_eq!(auto_position(130.0), TextBoxPosition::Top);
+        assert_eq!(auto_position(220.0), TextBoxPosition::Top);
+    }
+
+    #[test]
+    fn textbox_text_speed() {
+        assert_eq!(chars_per_second(0), TEXTBOX_SHOW_CHARS_PER_SECOND);
+        assert_eq!(chars_per_second(3), TEXTBOX_SHOW_CHARS_PER_SECOND / 4.0);
+        assert_eq!(chars_per_second(7), TEXTBOX_SHOW_CHARS_PER_SECOND / 8.0);
+    }
+}
@@ -23,6 +23,9 @@ pub struct TextBoxChoice {
 pub struct TextBoxLayout {
     pub items: Vec<TextBoxLayoutItem>,
     pub choices: Vec<TextBoxChoice>,
+
+    /// Continue to the next page once this one is complete, without waiting for input.
+    pub auto_advance: bool,
 }
 
 impl TextBoxLayout {
@@ -30,6 +33,7 @@ impl TextBoxLayout {
         TextBoxLayout {
             items: Vec::new(),
             choices: Vec::new(),
+            auto_advance: false,
         }
     }
 
@@ -76,11 +80,16 @@ impl TextBoxLayout {
                     add_choice_line_choice(choice_lines, line, &mut layout, x, y);
                 },
                 TextPart::Delay { ticks } => {
-                    layout.items.last_mut().unwrap().wait = *ticks;
+                    if let Some(item) = layout.items.last_mut() {
+                        item.wait = *ticks;
+                    }
                 },
                 TextPart::CenterNextLine => {
                     center_start = Some(layout.items.len());
-                }
+                },
+                TextPart::AutoAdvance => {
+                    layout.auto_advance = true;
+                },
             };
         }
 
@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::scene::actor::{SceneActorFlags, DrawMode};
 use crate::scene_script::scene_script_ops::Op;
@@ -6,8 +6,8 @@ use crate::scene_script::scene_script_decoder::ActorRef;
 use crate::memory::{DataDest, DataSource};
 use crate::sprites::sprite_renderer::SpritePriority;
 
-pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
-    match op {
+pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Result<Op, Error> {
+    let op = match op {
 
         // Enable/disable function calls on this actor.
         // "lock"
@@ -27,30 +27,30 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // "switch"
         0x19 => Op::ActorSetResult8 {
             actor: ActorRef::This,
-            result: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            result: DataSource::for_local_memory(data.read_u8()? as usize * 2),
         },
         // Set 16 bit actor result from 0x7F0000.
         // "gswitch"
         0x1C => Op::ActorSetResult16 {
             actor: ActorRef::This,
-            result: DataSource::for_global_memory(data.read_u8().unwrap() as usize),
+            result: DataSource::for_global_memory(data.read_u8()? as usize),
         },
 
         // Disable script processing and hide.
         // "kill"
         0x0A => Op::ActorRemove {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
         },
 
         // Set drawing mode.
         // "dshow"
         0x7C => Op::ActorSetDrawMode {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             draw_mode: DrawMode::Draw,
         },
         // "dhide"
         0x7D => Op::ActorSetDrawMode {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             draw_mode: DrawMode::Hidden,
         },
         // "battlehide"
@@ -73,7 +73,7 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Sprite priority.
         // "pri"
         0x8E => {
-            let bits = data.read_u8().unwrap();
+            let bits = data.read_u8()?;
             let set_from_map = bits & 0x80 != 0;
             let top = bits & 0x3;
             let bottom = (bits >> 4) & 0x3;
@@ -99,7 +99,7 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Set actor solidity.
         // "hitcheck"
         0x84 => {
-            let bits = data.read_u8().unwrap();
+            let bits = data.read_u8()?;
             let mut flags_set = SceneActorFlags::empty();
             let mut flags_remove = SceneActorFlags::empty();
 
@@ -119,7 +119,7 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Set actor collision properties.
         // "movecheck"
         0x0D => {
-            let flags = data.read_u8().unwrap();
+            let flags = data.read_u8()?;
             let mut flags_set = SceneActorFlags::empty();
             let mut flags_remove = SceneActorFlags::empty();
 
@@ -139,7 +139,7 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Set actor movement destination properties.
         // "moveadjust"
         0x0E => {
-            let flags = data.read_u8().unwrap();
+            let flags = data.read_u8()?;
             let mut flags_set = SceneActorFlags::empty();
             let mut flags_remove = SceneActorFlags::empty();
 
@@ -159,48 +159,48 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // "mspeed"
         0x89 => Op::ActorSetSpeed {
             actor: ActorRef::This,
-            speed: DataSource::Immediate(data.read_u8().unwrap() as i32),
+            speed: DataSource::Immediate(data.read_u8()? as i32),
         },
         // "vmspeed"
         0x8A => Op::ActorSetSpeed {
             actor: ActorRef::This,
-            speed: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            speed: DataSource::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         // Coordinates from actor.
         // "where"
         0x21 => Op::ActorCoordinatesGet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
-            tile_x: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
-            tile_y: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
+            tile_x: DataDest::for_local_memory(data.read_u8()? as usize * 2),
+            tile_y: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         // Coordinates from party member actor.
         // "pwhere"
         0x22 => Op::ActorCoordinatesGet {
-            actor: ActorRef::ActivePartyIndex(data.read_u8().unwrap() as usize),
-            tile_x: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
-            tile_y: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            actor: ActorRef::ActivePartyIndex(data.read_u8()? as usize),
+            tile_x: DataDest::for_local_memory(data.read_u8()? as usize * 2),
+            tile_y: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         // Set coordinates.
         // "xy"
         0x8B => Op::ActorCoordinatesSet {
             actor: ActorRef::This,
-            tile_x: DataSource::Immediate(data.read_u8().unwrap() as i32),
-            tile_y: DataSource::Immediate(data.read_u8().unwrap() as i32),
+            tile_x: DataSource::Immediate(data.read_u8()? as i32),
+            tile_y: DataSource::Immediate(data.read_u8()? as i32),
         },
         // "vxy"
         0x8C => Op::ActorCoordinatesSet {
             actor: ActorRef::This,
-            tile_x: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
-            tile_y: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            tile_x: DataSource::for_local_memory(data.read_u8()? as usize * 2),
+            tile_y: DataSource::for_local_memory(data.read_u8()? as usize * 2),
         },
         // "dotxy"
         0x8D => Op::ActorCoordinatesSetPrecise {
             actor: ActorRef::This,
-            x: DataSource::Immediate(data.read_u16::<LittleEndian>().unwrap() as i32 >> 4),
-            y: DataSource::Immediate(data.read_u16::<LittleEndian>().unwrap() as i32 >> 4),
+            x: DataSource::Immediate(data.read_u16::<LittleEndian>()? as i32 >> 4),
+            y: DataSource::Immediate(data.read_u16::<LittleEndian>()? as i32 >> 4),
         },
 
         // "hpmpfull"
@@ -223,5 +223,7 @@ pub fn op_decode_actor_props(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
 
         _ => panic!("Unknown actor property op."),
-    }
+    };
+
+    Ok(op)
 }
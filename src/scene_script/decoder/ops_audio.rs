@@ -1,30 +1,30 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::ReadBytesExt;
 use crate::scene_script::scene_script_ops::Op;
 
-pub fn op_decode_audio(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
-    match op {
+pub fn op_decode_audio(op: u8, data: &mut Cursor<Vec<u8>>) -> Result<Op, Error> {
+    let op = match op {
         // "sound"
         0xE8 => Op::SoundPlay {
-            sound: data.read_u8().unwrap() as usize,
+            sound: data.read_u8()? as usize,
             panning: 0.5,
         },
         // "music"
         0xEA => Op::MusicPlay {
-            music: data.read_u8().unwrap() as usize,
+            music: data.read_u8()? as usize,
             interrupt: true,
         },
         // "volume"
         0xEB => Op::SoundVolumeSlide {
-            left: data.read_u8().unwrap() as f64 * (1.0 / 255.0),
-            right: data.read_u8().unwrap() as f64 * (1.0 / 255.0),
+            left: data.read_u8()? as f64 * (1.0 / 255.0),
+            right: data.read_u8()? as f64 * (1.0 / 255.0),
             duration: 0.0,
         },
         // "akao"
         0xEC => {
-            let mode = data.read_u8().unwrap();
-            let data1 = data.read_u8().unwrap();
-            let data2 = data.read_u8().unwrap();
+            let mode = data.read_u8()?;
+            let data1 = data.read_u8()?;
+            let data2 = data.read_u8()?;
             if mode == 0x11 {
                 Op::MusicPlay {
                     music: data1 as usize,
@@ -74,5 +74,7 @@ pub fn op_decode_audio(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         0xEE => Op::MusicWaitEnd,
 
         _ => panic!("Unknown audio op."),
-    }
+    };
+
+    Ok(op)
 }
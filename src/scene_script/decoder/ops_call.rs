@@ -1,10 +1,10 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::ReadBytesExt;
 use crate::scene_script::scene_script_ops::Op;
 use crate::scene_script::scene_script_decoder::ActorRef;
 
-pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
-    match op {
+pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Result<Op, Error> {
+    let op = match op {
 
         // Call function on actor.
         // If current function priority < priority
@@ -18,8 +18,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Note that higher priority number == less urgent.
         // "req"
         0x02 => {
-            let actor_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let actor_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::Call {
                 actor: ActorRef::ScriptActor(actor_index),
                 function: (bits & 0x0F) as usize,
@@ -29,8 +29,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Wait until the other actor completes a more urgent task, then call as in 0x02.
         // "reqsw"
         0x03 => {
-            let actor_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let actor_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::CallWaitCompletion {
                 actor: ActorRef::ScriptActor(actor_index),
                 function: (bits & 0x0F) as usize,
@@ -41,8 +41,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // then wait until that function completes.
         // "reqew"
         0x04 => {
-            let actor_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let actor_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::CallWaitReturn {
                 actor: ActorRef::ScriptActor(actor_index),
                 function: (bits & 0x0F) as usize,
@@ -53,8 +53,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Same as 0x02, 0x03 and 0x04, but calls the actor of a specific party member.
         // "preq"
         0x05 => {
-            let party_member_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let party_member_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::Call {
                 actor: ActorRef::ActivePartyIndex(party_member_index),
                 function: (bits & 0x0F) as usize,
@@ -63,8 +63,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "preqsw"
         0x06 => {
-            let party_member_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let party_member_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::CallWaitCompletion {
                 actor: ActorRef::ActivePartyIndex(party_member_index),
                 function: (bits & 0x0F) as usize,
@@ -73,8 +73,8 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "preqew"
         0x07 => {
-            let party_member_index = data.read_u8().unwrap() as usize / 2;
-            let bits = data.read_u8().unwrap();
+            let party_member_index = data.read_u8()? as usize / 2;
+            let bits = data.read_u8()?;
             Op::CallWaitReturn {
                 actor: ActorRef::ActivePartyIndex(party_member_index),
                 function: (bits & 0x0F) as usize,
@@ -83,5 +83,7 @@ pub fn op_decode_call(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
 
         _ => panic!("Unknown call op."),
-    }
+    };
+
+    Ok(op)
 }
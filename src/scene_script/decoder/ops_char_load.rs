@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::GameMode;
 use crate::scene_script::scene_script_ops::Op;
@@ -11,8 +11,8 @@ pub enum CharacterType {
     Enemy,
 }
 
-pub fn op_decode_char_load(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Op {
-    match op {
+pub fn op_decode_char_load(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
+    let op = match op {
         // "autobindChrono"
         0x57 => Op::LoadCharacterPlayer {
             character_index: 0,
@@ -58,31 +58,31 @@ pub fn op_decode_char_load(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
 
         // "autobind"
         0x80 => Op::LoadCharacterPlayer {
-            character_index: data.read_u8().unwrap() as usize,
+            character_index: data.read_u8()? as usize,
             must_be_active: true,
             battle_index: 0,
         },
         // "char"
         0x81 => Op::LoadCharacter {
             char_type: CharacterType::PCAsNPC,
-            index: data.read_u8().unwrap() as usize,
+            index: data.read_u8()? as usize,
             is_static: false,
             battle_index: 0,
         },
         // "people"
         0x82 => Op::LoadCharacter {
             char_type: CharacterType::NPC,
-            index: data.read_u8().unwrap() as usize,
+            index: data.read_u8()? as usize,
             is_static: false,
             battle_index: 0,
         },
         // "monster"
         0x83 => {
             let index = match mode {
-                GameMode::Snes => data.read_u8().unwrap() as usize,
-                GameMode::Pc => data.read_u16::<LittleEndian>().unwrap() as usize,
+                GameMode::Snes => data.read_u8()? as usize,
+                GameMode::Pc => data.read_u16::<LittleEndian>()? as usize,
             };
-            let bits = data.read_u8().unwrap();
+            let bits = data.read_u8()?;
             Op::LoadCharacter {
                 char_type: CharacterType::Enemy,
                 index: match mode {
@@ -95,5 +95,7 @@ pub fn op_decode_char_load(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
 
         _ => panic!("Unknown character load op."),
-    }
+    };
+
+    Ok(op)
 }
@@ -1,11 +1,11 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::ReadBytesExt;
 use crate::scene_script::scene_script_ops::Op;
 use crate::scene_script::scene_script_decoder::ActorRef;
 use crate::memory::{DataDest, DataSource};
 
-pub fn op_decode_facing(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
-    match op {
+pub fn op_decode_facing(op: u8, data: &mut Cursor<Vec<u8>>) -> Result<Op, Error> {
+    let op = match op {
         // "turnup"
         0x0F => Op::ActorFacingSet {
             actor: ActorRef::This,
@@ -29,57 +29,59 @@ pub fn op_decode_facing(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "dturnup"
         0x1E => Op::ActorFacingSet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             facing: DataSource::Immediate(0),
         },
         // "dturndown"
         0x1F => Op::ActorFacingSet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             facing: DataSource::Immediate(1),
         },
         // "dturnleft"
         0x25 => Op::ActorFacingSet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             facing: DataSource::Immediate(2),
         },
         // "dturnright"
         0x26 => Op::ActorFacingSet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             facing: DataSource::Immediate(3),
         },
 
         // "getdir"
         0x23 => Op::ActorFacingGet {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
-            dest: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
+            dest: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
         // "pgetdir"
         0x24 => Op::ActorFacingGet {
-            actor: ActorRef::ActivePartyIndex(data.read_u8().unwrap() as usize),
-            dest: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            actor: ActorRef::ActivePartyIndex(data.read_u8()? as usize),
+            dest: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         // "turn"
         0xA6 => Op::ActorFacingSet {
             actor: ActorRef::This,
-            facing: DataSource::Immediate(data.read_u8().unwrap() as i32),
+            facing: DataSource::Immediate(data.read_u8()? as i32),
         },
         // "vturn"
         0xA7 => Op::ActorFacingSet {
             actor: ActorRef::This,
-            facing: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            facing: DataSource::for_local_memory(data.read_u8()? as usize * 2),
         },
         // "tura"
         0xA8 => Op::ActorSetFacingTowards {
             actor: ActorRef::This,
-            to: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            to: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
         },
         // "ptura"
         0xA9 => Op::ActorSetFacingTowards {
             actor: ActorRef::This,
-            to: ActorRef::ActivePartyIndex(data.read_u8().unwrap() as usize),
+            to: ActorRef::ActivePartyIndex(data.read_u8()? as usize),
         },
 
         _ => panic!("Unknown facing op."),
-    }
+    };
+
+    Ok(op)
 }
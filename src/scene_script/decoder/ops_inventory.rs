@@ -1,68 +1,70 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::GameMode;
 use crate::scene_script::scene_script_ops::Op;
 use crate::scene_script::scene_script_decoder::ActorRef;
-use crate::memory::DataSource;
+use crate::memory::{DataDest, DataSource};
 
-pub fn op_decode_inventory(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Op {
-    match op {
+pub fn op_decode_inventory(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
+    let op = match op {
         // "vitemP"
         0xC7 => Op::ItemGive {
             actor: ActorRef::This,
-            item: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            item: DataSource::for_local_memory(data.read_u8()? as usize * 2),
             category: match mode {
-                GameMode::Pc => data.read_u8().unwrap() as usize,
+                GameMode::Pc => data.read_u8()? as usize,
                 GameMode::Snes => 0,
             }
         },
         // "itemP"
         0xCA => Op::ItemGive {
             actor: ActorRef::This,
-            item: DataSource::Immediate(data.read_u8().unwrap() as i32),
+            item: DataSource::Immediate(data.read_u8()? as i32),
             category: match mode {
-                GameMode::Pc => data.read_u8().unwrap() as usize,
+                GameMode::Pc => data.read_u8()? as usize,
                 GameMode::Snes => 0,
             }
         },
         // "itemM"
         0xCB => Op::ItemTake {
             actor: ActorRef::This,
-            item: DataSource::Immediate(data.read_u8().unwrap() as i32),
+            item: DataSource::Immediate(data.read_u8()? as i32),
             category: match mode {
-                GameMode::Pc => data.read_u8().unwrap() as usize,
+                GameMode::Pc => data.read_u8()? as usize,
                 GameMode::Snes => 0,
             }
         },
         // "goldP"
         0xCD => Op::GoldGive {
             actor: ActorRef::This,
-            amount: DataSource::Immediate(data.read_u16::<LittleEndian>().unwrap() as i32),
+            amount: DataSource::Immediate(data.read_u16::<LittleEndian>()? as i32),
         },
         // "goldM"
         0xCE => Op::GoldTake {
             actor: ActorRef::This,
-            amount: DataSource::Immediate(data.read_u16::<LittleEndian>().unwrap() as i32),
+            amount: DataSource::Immediate(data.read_u16::<LittleEndian>()? as i32),
         },
         // "equip"
         0xD5 => Op::Equip {
-            pc: data.read_u8().unwrap() as usize,
-            item: data.read_u8().unwrap() as usize,
+            pc: data.read_u8()? as usize,
+            item: data.read_u8()? as usize,
             category: match mode {
-                GameMode::Pc => data.read_u8().unwrap() as usize,
+                GameMode::Pc => data.read_u8()? as usize,
                 GameMode::Snes => 0,
             }
         },
         // "itemN"
         0xD7 => Op::ItemGetAmount {
-            item: data.read_u8().unwrap() as usize,
+            item: data.read_u8()? as usize,
             category: match mode {
-                GameMode::Pc => data.read_u8().unwrap() as usize,
+                GameMode::Pc => data.read_u8()? as usize,
                 GameMode::Snes => 0,
             },
-            dest: DataSource::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            dest: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         _ => panic!("Unknown inventory op."),
-    }
+    };
+
+    Ok(op)
 }
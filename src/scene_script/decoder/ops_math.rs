@@ -1,17 +1,17 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error, ErrorKind};
 use byteorder::ReadBytesExt;
 use crate::scene_script::scene_script_ops::Op;
 use crate::memory::{DataDest, DataSource};
 use crate::shared_op::{BitMathOp, ByteMathOp};
 
-pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
-    match op {
+pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Result<Op, Error> {
+    let op = match op {
 
         // Byte math.
         // "plus"
         0x5B => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -21,8 +21,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "vplus"
         0x5D => {
-            let rhs = data.read_u8().unwrap() as usize * 2;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -32,8 +32,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "vplus2"
         0x5E => {
-            let rhs = data.read_u8().unwrap() as usize * 2;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath16 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -44,8 +44,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "minus"
         0x5F => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -55,8 +55,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "minus2"
         0x60 => {
-            let rhs = data.read_u8().unwrap() as usize * 2;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath16 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -66,8 +66,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "vminus"
         0x61 => {
-            let rhs = data.read_u8().unwrap() as usize * 2;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -78,7 +78,7 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "inc"
         0x71 => {
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -88,7 +88,7 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "inc2"
         0x72 => {
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath16 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -99,7 +99,7 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "dec"
         0x73 => {
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let lhs = data.read_u8()? as usize * 2;
             Op::ByteMath8 {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -111,8 +111,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // Bit math.
         // "biton"
         0x63 => {
-            let rhs = 1 << data.read_u8().unwrap() as u32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = 1 << read_bit(data.read_u8()?)?;
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -122,8 +122,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "bitoff"
         0x64 => {
-            let rhs = !(1 << data.read_u8().unwrap() as u32);
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = !(1 << read_bit(data.read_u8()?)?);
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -134,8 +134,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "gbiton"
         0x65 => {
-            let bit = data.read_u8().unwrap() as u32;
-            let mut lhs = data.read_u8().unwrap() as usize;
+            let bit = data.read_u8()? as u32;
+            let mut lhs = data.read_u8()? as usize;
             if bit & 0x80 > 0 {
                 lhs += 0x100;
             }
@@ -148,8 +148,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "gbitoff"
         0x66 => {
-            let bit = data.read_u8().unwrap() as u32;
-            let mut lhs = data.read_u8().unwrap() as usize;
+            let bit = data.read_u8()? as u32;
+            let mut lhs = data.read_u8()? as usize;
             if bit & 0x80 > 0 {
                 lhs += 0x100;
             }
@@ -163,8 +163,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
 
         // "and"
         0x67 => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -174,8 +174,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "or"
         0x69 => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -185,8 +185,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "xor"
         0x6B => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -196,8 +196,8 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         // "shiftR"
         0x6F => {
-            let rhs = data.read_u8().unwrap() as i32;
-            let lhs = data.read_u8().unwrap() as usize * 2;
+            let rhs = data.read_u8()? as i32;
+            let lhs = data.read_u8()? as usize * 2;
             Op::BitMath {
                 dest: DataDest::for_local_memory(lhs),
                 lhs: DataSource::for_local_memory(lhs),
@@ -209,19 +209,19 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         // PC specific ops.
         // "exbiton"
         0x45 => {
-            let bit = data.read_u8().unwrap();
-            let lhs = data.read_u8().unwrap() as usize;
+            let bit = data.read_u8()?;
+            let lhs = data.read_u8()? as usize;
             Op::BitMath {
                 dest: DataDest::for_extended_memory(lhs),
-                rhs: DataSource::Immediate(1 >> (bit & 0x7F) as u32),
+                rhs: DataSource::Immediate(1 >> read_bit(bit & 0x7F)?),
                 lhs: DataSource::for_extended_memory(lhs),
                 op: BitMathOp::Or,
             }
         },
         // "exbitoff"
         0x46 => {
-            let rhs = !(1 << data.read_u8().unwrap() as u32);
-            let lhs = data.read_u8().unwrap() as usize;
+            let rhs = !(1 << read_bit(data.read_u8()?)?);
+            let lhs = data.read_u8()? as usize;
             Op::BitMath {
                 dest: DataDest::for_extended_memory(lhs),
                 lhs: DataSource::for_extended_memory(lhs),
@@ -231,5 +231,15 @@ pub fn op_decode_math(op: u8, data: &mut Cursor<Vec<u8>>) -> Op {
         },
         
         _ => panic!("Unknown math op."),
+    };
+
+    Ok(op)
+}
+
+fn read_bit(bit: u8) -> Result<u32, Error> {
+    if bit >= 32 {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Bit {} is out of range", bit)));
     }
+
+    Ok(bit as u32)
 }
@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error, ErrorKind};
 use byteorder::ReadBytesExt;
 use crate::GameMode;
 use crate::scene_script::scene_script_ops::Op;
@@ -16,27 +16,27 @@ pub enum ColorMathMode {
     Subtractive,
 }
 
-pub fn op_decode_palette(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Op {
-    match op {
+pub fn op_decode_palette(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
+    let op = match op {
 
         // Dual mode palette command.
         // "bgcolor"
         0x2E => {
-            let cmd_mode = data.read_u8().unwrap();
+            let cmd_mode = data.read_u8()?;
             if cmd_mode & 0x40 > 0 {
                 let b = ((cmd_mode & 0x4) >> 2) > 0;
                 let g = ((cmd_mode & 0x2) >> 1) > 0;
                 let r = ((cmd_mode & 0x1) >> 0) > 0;
 
-                let color_start = data.read_u8().unwrap();
-                let color_count = data.read_u8().unwrap();
+                let color_start = data.read_u8()?;
+                let color_count = data.read_u8()?;
 
-                let intensity_bits = data.read_u8().unwrap();
+                let intensity_bits = data.read_u8()?;
                 let intensity_end: f64 = (intensity_bits & 0xF) as f64 * (1.0 / 15.0);
                 let intensity_start: f64 = ((intensity_bits & 0xF0) >> 4) as f64 * (1.0 / 15.0);
 
                 // todo what unit is this in? Assuming 60 Hz frames for now.
-                let duration = data.read_u8().unwrap() as f64 * (1.0 / 60.0);
+                let duration = data.read_u8()? as f64 * (1.0 / 60.0);
 
                 Op::ColorMathPalette {
                     mode: if cmd_mode & 0x50 > 0 { ColorMathMode::Additive } else { ColorMathMode::Subtractive },
@@ -49,10 +49,10 @@ pub fn op_decode_palette(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
             } else if cmd_mode & 0x80 > 0 {
                 match mode {
                     GameMode::Snes => {
-                        let bits = data.read_u8().unwrap() as usize;
+                        let bits = data.read_u8()? as usize;
                         let color_index = bits & 0xF;
                         let sub_palette = (bits & 0xF0) >> 4;
-                        let (blob, length) = read_script_blob(data);
+                        let (blob, length) = read_script_blob(data)?;
 
                         Op::PaletteSetImmediate {
                             sub_palette: SubPalette::Index(sub_palette),
@@ -62,53 +62,53 @@ pub fn op_decode_palette(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
                         }
                     },
                     GameMode::Pc => {
-                        let bits = data.read_u8().unwrap() as usize;
+                        let bits = data.read_u8()? as usize;
                         let color_index = bits & 0xF;
                         let sub_palette = (bits & 0xF0) >> 4;
 
                         Op::PaletteSetImmediateIndex {
                             color_index,
                             sub_palette: SubPalette::Index(sub_palette),
-                            palette_index: data.read_u8().unwrap() as usize,
+                            palette_index: data.read_u8()? as usize,
                         }
                     },
                 }
 
             } else {
-                panic!("Mode for op 0x2E is unknown.");
+                return Err(Error::new(ErrorKind::InvalidData, format!("Unknown mode 0x{:02X} for op 0x2E", cmd_mode)));
             }
         },
 
         // "fixpal", restore palette?
         0x33 => Op::PaletteSetIndex {
-            palette_index: data.read_u8().unwrap() as usize,
+            palette_index: data.read_u8()? as usize,
         },
 
         // 0x88 sub ops.
         // "color"
         0x88 => {
-            let cmd = data.read_u8().unwrap();
+            let cmd = data.read_u8()?;
             if cmd == 0 {
                 Op::PaletteRestore
             } else if cmd == 0x20 {
                 Op::Unknown {
                     code: 0x88,
-                    data: [cmd, data.read_u8().unwrap(), data.read_u8().unwrap(), 0],
+                    data: [cmd, data.read_u8()?, data.read_u8()?, 0],
                 }
             } else if cmd == 0x30 {
                 Op::Unknown {
                     code: 0x88,
-                    data: [cmd, data.read_u8().unwrap(), data.read_u8().unwrap(), 0],
+                    data: [cmd, data.read_u8()?, data.read_u8()?, 0],
                 }
             } else if cmd >= 0x40 && cmd < 0x60 {
                 Op::Unknown {
                     code: 0x88,
-                    data: [cmd, data.read_u8().unwrap(), data.read_u8().unwrap(), data.read_u8().unwrap()],
+                    data: [cmd, data.read_u8()?, data.read_u8()?, data.read_u8()?],
                 }
             } else if cmd >= 0x80 && cmd < 0x90 {
                 match mode {
                     GameMode::Snes => {
-                        let (blob, length) = read_script_blob(data);
+                        let (blob, length) = read_script_blob(data)?;
                         Op::PaletteSetImmediate {
                             color_index: cmd as usize & 0x0F,
                             sub_palette: SubPalette::This,
@@ -120,16 +120,18 @@ pub fn op_decode_palette(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
                         Op::PaletteSetImmediateIndex {
                             color_index: cmd as usize & 0x0F,
                             sub_palette: SubPalette::This,
-                            palette_index: data.read_u8().unwrap() as usize,
+                            palette_index: data.read_u8()? as usize,
                         }
                     },
                 }
 
             } else {
-                panic!("Unknown 0x88 command {}.", cmd);
+                return Err(Error::new(ErrorKind::InvalidData, format!("Unknown 0x88 command 0x{:02X}", cmd)));
             }
         },
 
         _ => panic!("Unknown palette op."),
-    }
+    };
+
+    Ok(op)
 }
@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error, ErrorKind};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::GameMode;
 use crate::scene::textbox::TextBoxPosition;
@@ -15,35 +15,36 @@ pub enum UiType {
 }
 
 impl UiType {
-    pub fn from_value(value: u8) -> UiType {
+    pub fn from_value(value: u8) -> Option<UiType> {
         if value >= 0x80 && value <= 0xBF {
-            UiType::Shop(value as usize - 0x80)
+            Some(UiType::Shop(value as usize - 0x80))
         } else if value >= 0xC0 && value <= 0xC7 {
-            UiType::RenamePC(value as usize - 0xC0)
+            Some(UiType::RenamePC(value as usize - 0xC0))
         } else if value == 0 {
-            UiType::CharacterSwitch
+            Some(UiType::CharacterSwitch)
         } else if value == 1 || value == 0x41 {
-            UiType::Load(value & 0x40 > 0)
+            Some(UiType::Load(value & 0x40 > 0))
         } else if value == 2 || value == 0x40 {
-            UiType::Save(value & 0x40 > 0)
+            Some(UiType::Save(value & 0x40 > 0))
         } else {
-            panic!("Cannot determine special dialogue type from 0x{:02X}.", value)
+            None
         }
     }
 }
 
-pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Op {
-    match op {
+pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
+    let op = match op {
 
         // Set string table address.
         // "msegg"
         0xB8 => {
             match mode {
                 GameMode::Snes => Op::TextSetTable {
-                    address: read_24_bit_address(data) - 0xC00000,
+                    address: read_24_bit_address(data)?.checked_sub(0xC00000)
+                        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "String table address is outside of ROM"))?,
                 },
                 GameMode::Pc => Op::TextSetTable {
-                    address: data.read_u8().unwrap() as usize,
+                    address: data.read_u8()? as usize,
                 },
             }
         },
@@ -52,9 +53,9 @@ pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
         // "mes"
         0xBB => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Auto,
             choice_lines: None,
@@ -62,19 +63,19 @@ pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
         // "query"
         0xC0 => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Auto,
-            choice_lines: get_textbox_choice_lines(data.read_u8().unwrap()),
+            choice_lines: get_textbox_choice_lines(data.read_u8()?),
         },
         // "mesu"
         0xC1 => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Top,
             choice_lines: None,
@@ -82,9 +83,9 @@ pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
         // "mesl"
         0xC2 => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Bottom,
             choice_lines: None,
@@ -92,32 +93,38 @@ pub fn op_decode_textbox(op: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) ->
         // "queryu"
         0xC3 => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Top,
-            choice_lines: get_textbox_choice_lines(data.read_u8().unwrap()),
+            choice_lines: get_textbox_choice_lines(data.read_u8()?),
         },
         // "queryl"
         0xC4 => Op::TextBoxShow {
             index: if matches!(mode, GameMode::Snes) {
-                data.read_u8().unwrap() as usize
+                data.read_u8()? as usize
             } else {
-                data.read_u16::<LittleEndian>().unwrap() as usize
+                data.read_u16::<LittleEndian>()? as usize
             },
             position: TextBoxPosition::Bottom,
-            choice_lines: get_textbox_choice_lines(data.read_u8().unwrap()),
+            choice_lines: get_textbox_choice_lines(data.read_u8()?),
         },
 
         // Special UI.
         // "menu"
-        0xC8 => Op::OpenUi {
-            ui: UiType::from_value(data.read_u8().unwrap()),
+        0xC8 => {
+            let value = data.read_u8()?;
+            Op::OpenUi {
+                ui: UiType::from_value(value)
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Cannot determine special dialogue type from 0x{:02X}", value)))?,
+            }
         },
 
         _ => panic!("Unknown textbox op."),
-    }
+    };
+
+    Ok(op)
 }
 
 fn get_textbox_choice_lines(value: u8) -> Option<[usize; 2]> {
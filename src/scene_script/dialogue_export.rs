@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, Cursor, ErrorKind, Write};
+use std::path::Path;
+use byteorder::ReadBytesExt;
+use crate::filesystem::filesystem::FileSystem;
+use crate::l10n::{IndexedType, L10n};
+use crate::scene::scene::SCENE_COUNT;
+use crate::scene::textbox::TextBoxPosition;
+use crate::scene_script::scene_script::SceneScript;
+use crate::scene_script::scene_script_decoder;
+use crate::scene_script::scene_script_ops::Op;
+use crate::text_processor::{page_lines, TextProcessor};
+use crate::util::json::JsonValue;
+
+// Text used for the item name and number result variables, which are only known at runtime.
+const RESULT_VALUE: u32 = 0;
+const RESULT_ITEM: &str = "[item]";
+
+/// A textbox shown by a scene script.
+struct DialogueEntry {
+    address: u64,
+    actor_index: usize,
+    function: String,
+    string_table: usize,
+    string_index: usize,
+    position: TextBoxPosition,
+    choice_lines: Option<[usize; 2]>,
+
+    /// Lines of each processed page.
+    pages: Vec<Vec<String>>,
+
+    /// Tags that could not be processed.
+    warnings: Vec<String>,
+}
+
+impl DialogueEntry {
+    fn to_json(&self, scene_index: usize, scene_name: &str, script_index: usize) -> JsonValue {
+        let choice_lines = match self.choice_lines {
+            Some(lines) => JsonValue::from(lines.to_vec()),
+            None => JsonValue::Null,
+        };
+
+        JsonValue::object()
+            .with("scene", scene_index)
+            .with("scene_name", scene_name)
+            .with("script", script_index)
+            .with("address", format!("0x{:04X}", self.address))
+            .with("actor", self.actor_index)
+            .with("function", self.function.as_str())
+            .with("string_table", format!("0x{:06X}", self.string_table))
+            .with("string_index", self.string_index)
+            .with("position", format!("{:?}", self.position))
+            .with("choice_lines", choice_lines)
+            .with("choices", self.choices())
+            .with("pages", self.pages.clone())
+            .with("warnings", self.warnings.clone())
+    }
+
+    // Choice lines are the lines of the last page that can be selected.
+    fn choices(&self) -> Vec<String> {
+        let (Some([first, last]), Some(page)) = (self.choice_lines, self.pages.last()) else {
+            return Vec::new();
+        };
+
+        page.iter().enumerate()
+            .filter(|(line, _)| *line >= first && *line <= last)
+            .map(|(_, text)| text.clone())
+            .collect()
+    }
+
+    fn write(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(out, "[actor {} {} @ {:04X}] string {} of table {:06X}, {:?}", self.actor_index, self.function, self.address, self.string_index, self.string_table, self.position)?;
+        for warning in self.warnings.iter() {
+            writeln!(out, "    ! {}", warning)?;
+        }
+        for (page_index, page) in self.pages.iter().enumerate() {
+            if page_index > 0 {
+                writeln!(out, "    ---")?;
+            }
+
+            // Choice lines are marked so that they stand out from the rest of the text.
+            let is_last_page = page_index == self.pages.len() - 1;
+            for (line_index, line) in page.iter().enumerate() {
+                let is_choice = is_last_page && self.choice_lines.is_some_and(|[first, last]| line_index >= first && line_index <= last);
+                writeln!(out, "    {}{}", if is_choice { "> " } else { "" }, line)?;
+            }
+        }
+        writeln!(out)?;
+
+        Ok(())
+    }
+}
+
+/// Writes all text shown by scene scripts to dialogue.json and dialogue.txt in the output path.
+/// Text is processed in the same way as it is for textboxes, using the default party names.
+pub fn dialogue_export(fs: &FileSystem, l10n: &L10n, text_processor: &TextProcessor, output_path: &Path) -> std::io::Result<()> {
+    create_dir_all(output_path)?;
+
+    // Scenes can share scripts, so collect the dialogue of each script only once.
+    let mut script_dialogue: HashMap<usize, Vec<DialogueEntry>> = HashMap::new();
+    let mut string_tables: HashMap<usize, Vec<String>> = HashMap::new();
+
+    let mut entries: Vec<JsonValue> = Vec::new();
+    let filename = output_path.join("dialogue.txt");
+    println!("Writing {}...", filename.display());
+    let mut out = BufWriter::new(File::create(&filename)?);
+
+    for scene_index in 0..SCENE_COUNT {
+        let script_index = match fs.read_scene_script_index(scene_index) {
+            Ok(script_index) => script_index,
+            Err(error) => {
+                println!("Skipping scene {}: {}", scene_index, error);
+                continue;
+            },
+        };
+
+        let dialogue = script_dialogue.entry(script_index).or_insert_with(|| {
+            match fs.read_scene_script(script_index) {
+                Ok(script) => script_dialogue_entries(fs, l10n, text_processor, script_index, &script, &mut string_tables),
+                Err(error) => {
+                    println!("Skipping scene script {}: {}", script_index, error);
+                    Vec::new()
+                },
+            }
+        });
+        if dialogue.is_empty() {
+            continue;
+        }
+
+        let scene_name = l10n.get_indexed(IndexedType::Scene, scene_index);
+        writeln!(out, "== Scene {} - {} (script {})", scene_index, scene_name, script_index)?;
+        writeln!(out)?;
+        for entry in dialogue.iter() {
+            entry.write(&mut out)?;
+            entries.push(entry.to_json(scene_index, &scene_name, script_index));
+        }
+    }
+
+    let filename = output_path.join("dialogue.json");
+    println!("Writing {}...", filename.display());
+    let report = JsonValue::object()
+        .with("language", l10n.get_language())
+        .with("entries", entries);
+    let mut file = File::create(&filename)?;
+    file.write_all(report.to_string_pretty().as_bytes())?;
+
+    Ok(())
+}
+
+// Walks the ops of each actor function from its entry point up to the start of the next function,
+// pairing each textbox with the string table that was set last before it.
+fn script_dialogue_entries(fs: &FileSystem, l10n: &L10n, text_processor: &TextProcessor, script_index: usize, script: &SceneScript, string_tables: &mut HashMap<usize, Vec<String>>) -> Vec<DialogueEntry> {
+    let functions = function_starts(script);
+
+    let mut entries = Vec::new();
+    let mut string_table = None;
+    let mut data = Cursor::new(script.get_data().clone());
+    let data_len = data.get_ref().len() as u64;
+
+    for (start, end, actor_index, function) in function_ranges(&functions, data_len) {
+        data.set_position(start);
+        while data.position() < end {
+            let address = data.position();
+            let op_byte = data.read_u8().unwrap();
+            let op = match scene_script_decoder::op_decode_opcode(op_byte, &mut data, fs.mode) {
+                Ok(op) => op,
+                Err(error) => {
+                    if error.kind() == ErrorKind::Unsupported {
+                        println!("Unsupported op 0x{:02X} at 0x{:04X} in scene script {}, skipping the rest of actor {} {}.", op_byte, address, script_index, actor_index, function_name(function));
+                    } else {
+                        println!("Could not decode op 0x{:02X} at 0x{:04X} in scene script {}, skipping the rest of actor {} {}: {}", op_byte, address, script_index, actor_index, function_name(function), error);
+                    }
+                    break;
+                },
+            };
+
+            match op {
+                Op::TextSetTable { address: table_address } => {
+                    string_tables.entry(table_address).or_insert_with(|| {
+                        let mut strings = Vec::new();
+                        if let Err(error) = fs.read_textbox_string_table(table_address, l10n.get_language(), &mut strings) {
+                            println!("Could not read textbox string table at 0x{:06X}: {}", table_address, error);
+                        }
+                        strings
+                    });
+                    string_table = Some(table_address);
+                },
+                Op::TextBoxShow { index, position, choice_lines } => {
+                    let Some(table_address) = string_table else {
+                        println!("Textbox at 0x{:04X} in scene script {} has no string table.", address, script_index);
+                        continue;
+                    };
+                    let text = match string_tables[&table_address].get(index) {
+                        Some(text) => text.clone(),
+                        None => format!("MISSING STRING INDEX {}", index),
+                    };
+
+                    let (pages, warnings) = text_processor.process_dialog_text_checked(&text, RESULT_VALUE, RESULT_ITEM.to_string());
+                    entries.push(DialogueEntry {
+                        address,
+                        actor_index,
+                        function: function_name(function),
+                        string_table: table_address,
+                        string_index: index,
+                        position,
+                        choice_lines,
+                        pages: pages.iter().map(page_lines).collect(),
+                        warnings,
+                    });
+                },
+                _ => {},
+            }
+        }
+    }
+
+    entries
+}
+
+// Returns the start address, actor index and function index of all actor functions, sorted by
+// start address.
+fn function_starts(script: &SceneScript) -> Vec<(u64, usize, usize)> {
+    let mut functions: Vec<(u64, usize, usize)> = Vec::new();
+    for (actor_index, actor_script) in script.get_actor_scripts().iter().enumerate() {
+        for (function, ptr) in actor_script.get_function_ptrs().iter().enumerate() {
+            functions.push((*ptr, actor_index, function));
+        }
+    }
+    functions.sort();
+
+    functions
+}
+
+// Returns the start and end address, actor index and function index of each distinct function.
+// A function ends where the next one starts. Unused functions often share their address with
+// another function, in which case only the one of the lowest actor and function index is kept.
+fn function_ranges(functions: &[(u64, usize, usize)], data_len: u64) -> Vec<(u64, u64, usize, usize)> {
+    let mut ranges: Vec<(u64, u64, usize, usize)> = Vec::new();
+    for (index, (start, actor_index, function)) in functions.iter().enumerate() {
+        if *start >= data_len || (index > 0 && functions[index - 1].0 == *start) {
+            continue;
+        }
+        let end = functions[index..].iter()
+            .map(|(ptr, _, _)| *ptr)
+            .find(|ptr| ptr > start)
+            .unwrap_or(data_len)
+            .min(data_len);
+        ranges.push((*start, end, *actor_index, *function));
+    }
+
+    ranges
+}
+
+fn function_name(function: usize) -> String {
+    match function {
+        0 => "startup".to_string(),
+        1 => "activate".to_string(),
+        2 => "touch".to_string(),
+        _ => format!("function {}", function),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_processor::TextPart;
+
+    #[test]
+    fn dialogue_lines_and_functions() {
+        let page = vec![
+            TextPart::Text { text: "Crono: ".to_string() },
+            TextPart::Text { text: "Yes.".to_string() },
+            TextPart::Delay { ticks: 16 },
+            TextPart::LineBreak,
+            TextPart::CenterNextLine,
+            TextPart::Text { text: "No.".to_string() },
+        ];
+        assert_eq!(page_lines(&page), vec!["Crono: Yes.".to_string(), "No.".to_string()]);
+
+        let functions = vec![(0x10, 0, 0), (0x20, 0, 1), (0x20, 1, 0), (0x20, 1, 2), (0x40, 1, 1)];
+        assert_eq!(function_ranges(&functions, 0x50), vec![(0x10, 0x20, 0, 0), (0x20, 0x40, 0, 1), (0x40, 0x50, 1, 1)]);
+        assert_eq!(function_ranges(&functions, 0x30), vec![(0x10, 0x20, 0, 0), (0x20, 0x30, 0, 1)]);
+        assert_eq!(function_name(2), "touch");
+        assert_eq!(function_name(5), "function 5");
+    }
+}
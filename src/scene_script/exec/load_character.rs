@@ -36,8 +36,16 @@ pub fn exec_load_character(ctx: &mut Context, scene_state: &mut SceneState, acto
         actor.flags |= SceneActorFlags::BATTLE_STATIC;
     }
 
-    // Sprite and animation defaults.
-    let sprite_info_key = ctx.assets.load_sprite_info(&ctx.fs, sprite_index);
+    // Sprite and animation defaults. Actors whose sprite cannot be loaded are removed.
+    let sprite_info_key = match ctx.assets.load_sprite_info(&ctx.fs, sprite_index) {
+        Ok(key) => key,
+        Err(error) => {
+            println!("Actor {} could not load sprite {}: {}", actor_index, sprite_index, error);
+            actor.flags.insert(SceneActorFlags::DEAD);
+            actor.draw_mode = DrawMode::Removed;
+            return OpResult::COMPLETE;
+        },
+    };
     let sprite_info = ctx.assets.get_sprite_info(sprite_info_key);
     actor.sprite_info_key = Some(sprite_info_key);
 
@@ -81,7 +89,15 @@ pub fn exec_load_character_player(ctx: &mut Context, scene_state: &mut SceneStat
     }
 
     // Sprite and animation defaults.
-    let sprite_info_key = ctx.assets.load_sprite_info(&ctx.fs, character_index);
+    let sprite_info_key = match ctx.assets.load_sprite_info(&ctx.fs, character_index) {
+        Ok(key) => key,
+        Err(error) => {
+            println!("Actor {} could not load sprite {}: {}", actor_index, character_index, error);
+            actor.flags.insert(SceneActorFlags::DEAD);
+            actor.draw_mode = DrawMode::Removed;
+            return OpResult::COMPLETE;
+        },
+    };
     let sprite_info = ctx.assets.get_sprite_info(sprite_info_key);
     actor.sprite_info_key = Some(sprite_info_key);
 
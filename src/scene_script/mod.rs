@@ -1,6 +1,7 @@
 pub mod scene_script;
 pub mod scene_script_decoder;
-mod scene_script_ops;
-mod scene_script_exec;
-mod decoder;
+pub mod scene_script_ops;
+pub mod scene_script_exec;
+pub mod dialogue_export;
+pub mod decoder;
 mod exec;
@@ -28,6 +28,10 @@ impl SceneActorScript {
         }
     }
 
+    pub fn get_function_ptrs(&self) -> &[u64; 16] {
+        &self.ptrs
+    }
+
     pub fn get_initial_state(&self) -> ActorScriptState {
         ActorScriptState {
             delay: 4,
@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{Cursor, Error, ErrorKind};
 use bitflags::bitflags;
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::GameMode;
@@ -118,65 +118,81 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         }
     };
 
+    match op_decode_opcode(op_byte, data, mode) {
+        Ok(op) => Some(op),
+        Err(error) if error.kind() == ErrorKind::Unsupported => {
+            println!("Decoding unimplemented opcode 0x{:02X} as NOP", op_byte);
+            Some(Op::NOP)
+        },
+        Err(error) => {
+            println!("{} at 0x{:04X}.", error, data.position());
+            None
+        },
+    }
+}
+
+/// Decodes the op for an already read opcode byte. Unknown opcodes result in an unsupported
+/// error, truncated or invalid data in an unexpected end of file or invalid data error.
+pub fn op_decode_opcode(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
     let op = match op_byte {
 
         // Function calls.
-        0x02 | 0x03 | 0x04 | 0x05 | 0x06 | 0x07 => op_decode_call(op_byte, data),
+        0x02 | 0x03 | 0x04 | 0x05 | 0x06 | 0x07 => op_decode_call(op_byte, data)?,
 
         // Actor properties.
         0x08 | 0x09 | 0x0A | 0x19 | 0x1C | 0x7C | 0x7D | 0x90 | 0x91 | 0x7E | 0x8E | 0x84 | 0x0D |
-        0x0E | 0x89 | 0x8A | 0x21 | 0x22 | 0x8B | 0x8C | 0x8D | 0xF8 | 0xF9 | 0xFA => op_decode_actor_props(op_byte, data),
+        0x0E | 0x89 | 0x8A | 0x21 | 0x22 | 0x8B | 0x8C | 0x8D | 0xF8 | 0xF9 | 0xFA => op_decode_actor_props(op_byte, data)?,
 
         // Actor movement.
         0x8F | 0x92 | 0x94 | 0x95 | 0x96 | 0x97 | 0x98 | 0x99 | 0x9A | 0x9C | 0x9D | 0x9E | 0x9F |
-        0xA0 | 0xA1 | 0x7A | 0x7B | 0xB5 | 0xD9 | 0xDA | 0xB6 => ops_decode_movement(op_byte, data),
+        0xA0 | 0xA1 | 0x7A | 0x7B | 0xB5 | 0xD9 | 0xDA | 0xB6 => ops_decode_movement(op_byte, data)?,
 
         // Data copy.
         0x20 | 0x48 | 0x49 | 0x4A | 0x4B | 0x4C | 0x4D | 0x4E | 0x4F | 0x50 | 0x51 | 0x52 | 0x53 |
         0x54 | 0x55 | 0x56 | 0x58 | 0x59 | 0x5A | 0x75 | 0x76 | 0x77 | 0x3A | 0x3D | 0x3E | 0x70 |
-        0x74 | 0x78 => op_decode_copy(op_byte, data, mode),
+        0x74 | 0x78 => op_decode_copy(op_byte, data, mode)?,
 
         // Byte math.
         0x5B | 0x5D | 0x5E | 0x5F | 0x60 | 0x61 | 0x71 | 0x72 | 0x73 | 0x63 | 0x64 | 0x65 | 0x66 |
-        0x67 | 0x69 | 0x6B | 0x6F | 0x45 | 0x46 => op_decode_math(op_byte, data),
+        0x67 | 0x69 | 0x6B | 0x6F | 0x45 | 0x46 => op_decode_math(op_byte, data)?,
 
         // Load character.
-        0x57 | 0x5C | 0x62 | 0x68 | 0x6A | 0x6C | 0x6D | 0x80 | 0x81 | 0x82 | 0x83 => op_decode_char_load(op_byte, data, mode),
+        0x57 | 0x5C | 0x62 | 0x68 | 0x6A | 0x6C | 0x6D | 0x80 | 0x81 | 0x82 | 0x83 => op_decode_char_load(op_byte, data, mode)?,
 
         // Actor facing.
         0x0F | 0x17 | 0x1B | 0x1D | 0x1E | 0x1F | 0x25 | 0x26 | 0x23 | 0x24 | 0xA6 | 0xA7 | 0xA8 |
-        0xA9 => op_decode_facing(op_byte, data),
+        0xA9 => op_decode_facing(op_byte, data)?,
 
         // Code jumps.
         0x10 | 0x11 | 0x12 | 0x13 | 0x14 | 0x15 | 0x16 | 0x18 | 0x1A | 0x27 | 0x28 | 0x2D | 0x30 |
         0x31 | 0x34 | 0x35 | 0x36 | 0x37 | 0x38 | 0x39 | 0x3B | 0x3C | 0x3F | 0x40 | 0x41 | 0x42 |
-        0x43 | 0x44 | 0xC9 | 0xCC | 0xCF | 0xD2 | 0x6E => op_decode_jump(op_byte, data, mode),
+        0x43 | 0x44 | 0xC9 | 0xCC | 0xCF | 0xD2 | 0x6E => op_decode_jump(op_byte, data, mode)?,
 
         // Textboxes.
-        0xB8 | 0xBB | 0xC0 | 0xC1 | 0xC2 | 0xC3 | 0xC4 | 0xC8 => op_decode_textbox(op_byte, data, mode),
+        0xB8 | 0xBB | 0xC0 | 0xC1 | 0xC2 | 0xC3 | 0xC4 | 0xC8 => op_decode_textbox(op_byte, data, mode)?,
 
         // Animation.
-        0xAA | 0xAB | 0xAC | 0xAE | 0xB3 | 0xB4 | 0xB7 | 0x47 => op_decode_animation(op_byte, data),
+        0xAA | 0xAB | 0xAC | 0xAE | 0xB3 | 0xB4 | 0xB7 | 0x47 => op_decode_animation(op_byte, data)?,
 
         // Party management.
-        0xD0 | 0xD1 | 0xD3 | 0xD4 | 0xD6 | 0xE3 => op_decode_party(op_byte, data),
+        0xD0 | 0xD1 | 0xD3 | 0xD4 | 0xD6 | 0xE3 => op_decode_party(op_byte, data)?,
 
         // Palettes.
-        0x2E | 0x33 | 0x88 => op_decode_palette(op_byte, data, mode),
+        0x2E | 0x33 | 0x88 => op_decode_palette(op_byte, data, mode)?,
 
         // Move to another location.
-        0xDC | 0xDD | 0xDE | 0xDF | 0xE0 | 0xE1 | 0xE2 => op_decode_location(op_byte, data, mode),
+        0xDC | 0xDD | 0xDE | 0xDF | 0xE0 | 0xE1 | 0xE2 => op_decode_location(op_byte, data, mode)?,
 
         // Inventory.
-        0xC7 | 0xCA | 0xCB | 0xCD | 0xCE | 0xD5 | 0xD7 => op_decode_inventory(op_byte, data, mode),
+        0xC7 | 0xCA | 0xCB | 0xCD | 0xCE | 0xD5 | 0xD7 => op_decode_inventory(op_byte, data, mode)?,
 
         // Sound and music.
-        0xE8 | 0xEA | 0xEB | 0xEC | 0xED | 0xEE => op_decode_audio(op_byte, data),
+        0xE8 | 0xEA | 0xEB | 0xEC | 0xED | 0xEE => op_decode_audio(op_byte, data)?,
 
         // Screen effects.
         // "fade"
         0xF0 => {
-            let value = data.read_u8().unwrap();
+            let value = data.read_u8()?;
             Op::ScreenFade {
                 target: ((value & 0xF0) >> 4) as f64 * (1.0 / 15.0),
                 delay: value as usize & 0x0F,
@@ -184,7 +200,7 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         },
         // "flash"
         0xF1 => {
-            let bits = data.read_u8().unwrap();
+            let bits = data.read_u8()?;
             if bits == 0 {
                 Op::ColorMathScreen {
                     r: 0,
@@ -200,7 +216,7 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
                 let r = if bits & 0x20 > 0 { 255 } else { 0 };
                 let intensity = (bits & 0x1F) as f64 * (1.0 / 32.0);
 
-                let params = data.read_u8().unwrap();
+                let params = data.read_u8()?;
                 let mode = if params & 0x80 > 0 { ColorMathMode::Additive } else { ColorMathMode::Subtractive };
                 let duration = (params & 0x7F) as f64 * (1.0 / 60.0);
 
@@ -220,43 +236,43 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         0xF3 => Op::WaitForColorMath,
         // "shake"
         0xF4 => Op::ScreenShake {
-            enabled: data.read_u8().unwrap() == 1,
+            enabled: data.read_u8()? == 1,
         },
 
         // "rect"
         0xFE => Op::ColorMathGeometry {
-            unknown: data.read_u8().unwrap(),
+            unknown: data.read_u8()?,
 
-            x1_src: data.read_u8().unwrap(),
-            x1_dest: data.read_u8().unwrap(),
-            y1_src: data.read_u8().unwrap(),
-            y1_dest: data.read_u8().unwrap(),
+            x1_src: data.read_u8()?,
+            x1_dest: data.read_u8()?,
+            y1_src: data.read_u8()?,
+            y1_dest: data.read_u8()?,
 
-            x2_src: data.read_u8().unwrap(),
-            x2_dest: data.read_u8().unwrap(),
-            y2_src: data.read_u8().unwrap(),
-            y2_dest: data.read_u8().unwrap(),
+            x2_src: data.read_u8()?,
+            x2_dest: data.read_u8()?,
+            y2_src: data.read_u8()?,
+            y2_dest: data.read_u8()?,
 
-            x3_src: data.read_u8().unwrap(),
-            x3_dest: data.read_u8().unwrap(),
-            y3_src: data.read_u8().unwrap(),
-            y3_dest: data.read_u8().unwrap(),
+            x3_src: data.read_u8()?,
+            x3_dest: data.read_u8()?,
+            y3_src: data.read_u8()?,
+            y3_dest: data.read_u8()?,
 
-            x4_src: data.read_u8().unwrap(),
-            x4_dest: data.read_u8().unwrap(),
-            y4_src: data.read_u8().unwrap(),
-            y4_dest: data.read_u8().unwrap(),
+            x4_src: data.read_u8()?,
+            x4_dest: data.read_u8()?,
+            y4_src: data.read_u8()?,
+            y4_dest: data.read_u8()?,
         },
 
         // "random"
         0x7F => Op::Random {
-            dest: DataDest::for_local_memory(data.read_u8().unwrap() as usize * 2),
+            dest: DataDest::for_local_memory(data.read_u8()? as usize * 2),
         },
 
         // Special effects or scenes.
         // "special"
         0xFF => {
-            let mode = data.read_u8().unwrap();
+            let mode = data.read_u8()?;
             if mode <= 0x8F {
                 Op::SpecialScene {
                     scene: mode as usize & 0x0F,
@@ -264,9 +280,9 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
                 }
             } else if mode == 0x90 {
                 Op::SpecialOpenPortal {
-                    value1: data.read_u8().unwrap(),
-                    value2: data.read_u8().unwrap(),
-                    value3: data.read_u8().unwrap(),
+                    value1: data.read_u8()?,
+                    value2: data.read_u8()?,
+                    value3: data.read_u8()?,
                 }
             } else if mode == 0x92 {
                 Op::SpecialEffect { effect: SpecialEffect::ScreenOpenLeftRight }
@@ -282,9 +298,9 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
                 Op::SpecialEffect {
                     effect: SpecialEffect::Unknown(
                         mode,
-                        [data.read_u8().unwrap(),
-                        data.read_u8().unwrap(),
-                        data.read_u8().unwrap()],
+                        [data.read_u8()?,
+                        data.read_u8()?,
+                        data.read_u8()?],
                     ),
                 }
             } else if mode == 0x9B {
@@ -308,41 +324,41 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // Copy tiles from somewhere else in the map.
         // "mapcopy"
         0xE4 => Op::CopyTiles {
-            left: data.read_u8().unwrap() as u32,
-            top: data.read_u8().unwrap() as u32,
-            right: data.read_u8().unwrap() as u32 + 1,
-            bottom: data.read_u8().unwrap() as u32 + 1,
-            dest_x: data.read_u8().unwrap() as u32,
-            dest_y: data.read_u8().unwrap() as u32,
-            flags: CopyTilesFlags::from_bits_truncate(data.read_u8().unwrap() as u32),
+            left: data.read_u8()? as u32,
+            top: data.read_u8()? as u32,
+            right: data.read_u8()? as u32 + 1,
+            bottom: data.read_u8()? as u32 + 1,
+            dest_x: data.read_u8()? as u32,
+            dest_y: data.read_u8()? as u32,
+            flags: CopyTilesFlags::from_bits_truncate(data.read_u8()? as u32),
             delayed: false,
         },
         // "mapcopyd", same as mapcopy but delayed?
         0xE5 => Op::CopyTiles {
-            left: data.read_u8().unwrap() as u32,
-            top: data.read_u8().unwrap() as u32,
-            right: data.read_u8().unwrap() as u32 + 1,
-            bottom: data.read_u8().unwrap() as u32 + 1,
-            dest_x: data.read_u8().unwrap() as u32,
-            dest_y: data.read_u8().unwrap() as u32,
-            flags: CopyTilesFlags::from_bits_truncate(data.read_u8().unwrap() as u32),
+            left: data.read_u8()? as u32,
+            top: data.read_u8()? as u32,
+            right: data.read_u8()? as u32 + 1,
+            bottom: data.read_u8()? as u32 + 1,
+            dest_x: data.read_u8()? as u32,
+            dest_y: data.read_u8()? as u32,
+            flags: CopyTilesFlags::from_bits_truncate(data.read_u8()? as u32),
             delayed: true,
         },
 
         // Scroll map layers.
         // "scroll"
         0xE6 => Op::ScrollLayers {
-            x: (data.read_i8().unwrap() as f64) / 16.0,
-            y: (data.read_i8().unwrap() as f64) / 16.0,
-            flags: ScrollLayerFlags::from_bits_truncate(data.read_u8().unwrap() as u32),
-            cycles: data.read_u8().unwrap() as u32,
+            x: (data.read_i8()? as f64) / 16.0,
+            y: (data.read_i8()? as f64) / 16.0,
+            flags: ScrollLayerFlags::from_bits_truncate(data.read_u8()? as u32),
+            cycles: data.read_u8()? as u32,
         },
 
         // Move camera.
         // "fscroll"
         0xE7 => Op::MoveCameraTo {
-            x: data.read_i8().unwrap() as i32,
-            y: data.read_i8().unwrap() as i32,
+            x: data.read_i8()? as i32,
+            y: data.read_i8()? as i32,
         },
 
         // Yield to the function with the next higher priority number.
@@ -363,7 +379,7 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // Wait durations are 1/16th of a second for NPCs, 1/64th for PCs?
         // "wait"
         0xAD => Op::Wait {
-            ticks: data.read_u8().unwrap() as u32,
+            ticks: data.read_u8()? as u32,
         },
         // "wait04h"
         0xB9 => Op::Wait {
@@ -385,19 +401,19 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // Disable/enable script processing.
         // "sleep"
         0x0B => Op::SetScriptProcessing {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             enabled: false,
         },
         // "wakeup"
         0x0C => Op::SetScriptProcessing {
-            actor: ActorRef::ScriptActor(data.read_u8().unwrap() as usize / 2),
+            actor: ActorRef::ScriptActor(data.read_u8()? as usize / 2),
             enabled: true,
         },
 
         // Script execution delay in ticks.
         // "think"
         0x87 => Op::SetScriptDelay {
-            delay: data.read_u8().unwrap() as u32,
+            delay: data.read_u8()? as u32,
         },
 
         // Handle player character controls.
@@ -415,14 +431,14 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // Start battle.
         // "battle"
         0xD8 => Op::Battle {
-            flags: BattleFlags::from_bits_truncate(data.read_u16::<LittleEndian>().unwrap() as u32),
+            flags: BattleFlags::from_bits_truncate(data.read_u16::<LittleEndian>()? as u32),
         },
 
         // "Staff roll" ops, for handling credits.
         // "setstaffroll", load staff roll text index?
         0x29 => Op::Unknown {
             code: 0x29,
-            data: [data.read_u8().unwrap(), 0, 0, 0],
+            data: [data.read_u8()?, 0, 0, 0],
         },
         // "staffrollclear0", clear staff roll in some way, animation maybe?
         0x2A => Op::BitMath {
@@ -440,8 +456,8 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         },
         // "staffrollxy"
         0x2F => Op::ScrollLayers {
-            x: (data.read_i8().unwrap() as f64) / 16.0,
-            y: (data.read_i8().unwrap() as f64) / 16.0,
+            x: (data.read_i8()? as f64) / 16.0,
+            y: (data.read_i8()? as f64) / 16.0,
             flags: ScrollLayerFlags::SCROLL_L1_SPRITES | ScrollLayerFlags::SCROLL_L2 | ScrollLayerFlags::SCROLL_L3,
             cycles: 0,
         },
@@ -456,13 +472,13 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // "bg3offset"
         0x2C => Op::Unknown {
             code: 0x2C,
-            data: [data.read_u8().unwrap(), data.read_u8().unwrap(), 0, 0],
+            data: [data.read_u8()?, data.read_u8()?, 0, 0],
         },
 
         // "playMovie"
         // TODO: verify argument
         0xC5 => Op::PlayMovie {
-            movie: data.read_u8().unwrap(),
+            movie: data.read_u8()?,
         },
 
         // Unknown PC/DS version ops.
@@ -479,7 +495,7 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         // "DS_Link"
         0xFD => Op::Unknown {
             code: 0xFD,
-            data: [data.read_u8().unwrap(), 0, 0, 0],
+            data: [data.read_u8()?, 0, 0, 0],
         },
         // "minimapcopy"
         0xA2 => Op::Unknown {
@@ -517,11 +533,8 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
             data: [0, 0, 0, 0],
         },
 
-        _ => {
-            println!("Decoding unimplemented opcode 0x{:02X} as NOP", op_byte);
-            Op::NOP
-        },
+        _ => return Err(Error::new(ErrorKind::Unsupported, format!("Unimplemented opcode 0x{:02X}", op_byte))),
     };
 
-    Some(op)
+    Ok(op)
 }
@@ -5,10 +5,10 @@ use crate::Context;
 use crate::facing::Facing;
 use crate::gamestate::gamestate_scene::SceneState;
 use crate::l10n::IndexedType;
-use crate::scene::textbox::TextBoxPosition;
 use crate::scene_script::exec::animation::{exec_animation, exec_animation_loop_count, exec_animation_reset, exec_animation_static_frame};
 use crate::scene_script::exec::call::{exec_call, exec_call_return, exec_call_wait_completion, exec_call_wait_return};
 use crate::scene_script::exec::movement::{exec_movement_to_tile, exec_movement_by_vector, exec_movement_to_actor};
+use crate::scene::special_dialogue::special_dialogue_create;
 use crate::scene_script::scene_script_ops::Op;
 use crate::scene_script::decoder::ops_palette::SubPalette;
 use crate::scene_script::exec::load_character::{exec_load_character, exec_load_character_player};
@@ -26,6 +26,11 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
         None => return OpResult::YIELD | OpResult::COMPLETE,
     };
 
+    if let Some((description, result)) = op_unimplemented(&op) {
+        println!("Unimplemented: {}", description);
+        return result;
+    }
+
     match op {
         Op::NOP => OpResult::COMPLETE,
 
@@ -375,11 +380,6 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
 
             exec_movement_to_tile(scene_state, state, this_actor, Vec2Di32::new(dest_tile_x, dest_tile_y), steps, update_facing, animated)
         }
-        Op::MovePartyTo { .. } => {
-            println!("Unimplemented: move party");
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-
         Op::CopyTiles { left, top, right, bottom, dest_x, dest_y, flags, delayed } => {
             exec_tile_copy(scene_state, left, top, right, bottom, dest_x, dest_y, flags, delayed)
         },
@@ -477,7 +477,10 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
 
         // Textbox ops.
         Op::TextSetTable { address } => {
-            ctx.fs.read_textbox_string_table(address, ctx.l10n.get_language(), &mut scene_state.textbox_strings);
+            scene_state.textbox_strings_address = Some(address);
+            if let Err(error) = ctx.fs.read_textbox_string_table(address, ctx.l10n.get_language(), &mut scene_state.textbox_strings) {
+                println!("Could not read textbox string table at 0x{:06X}: {}", address, error);
+            }
 
             OpResult::COMPLETE
         },
@@ -501,18 +504,6 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
             }
             actor.flags.insert(SceneActorFlags::TEXTBOX_ACTIVE);
 
-            // Determine position of player character vs camera top or bottom half to position
-            // the textbox in auto mode.
-            let real_position = if position == TextBoxPosition::Auto {
-                if ((actor.pos.y - scene_state.camera.pos.y) as i32) < 130 {
-                    TextBoxPosition::Bottom
-                } else {
-                    TextBoxPosition::Top
-                }
-            } else {
-                position
-            };
-
             let text = if index < scene_state.textbox_strings.len() {
                 scene_state.textbox_strings[index].clone()
             } else {
@@ -530,13 +521,26 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
             let result_item = ctx.memory.get_scene_u8(0x7F0200, scene_state) as usize;
             let result_item_name = ctx.l10n.get_indexed(IndexedType::Item, result_item);
 
-            scene_state.textbox.show(ctx, text, real_position, this_actor, choice_lines, result_value, result_item_name);
+            scene_state.textbox.show(ctx, text, position, this_actor, choice_lines, result_value, result_item_name);
 
             OpResult::YIELD
         },
-        Op::OpenUi { ui: dialogue_type } => {
-            println!("Unimplemented: show special dialogue {:?}", dialogue_type);
-            OpResult::COMPLETE
+        Op::OpenUi { ui } => {
+            if scene_state.special_dialogue.is_some() {
+                return OpResult::YIELD;
+            }
+
+            // End op if the dialogue was closed.
+            let actor = &mut scene_state.actors[this_actor];
+            if actor.flags.contains(SceneActorFlags::SPECIAL_DIALOGUE_ACTIVE) {
+                actor.flags.remove(SceneActorFlags::SPECIAL_DIALOGUE_ACTIVE);
+                return OpResult::COMPLETE;
+            }
+            actor.flags.insert(SceneActorFlags::SPECIAL_DIALOGUE_ACTIVE);
+
+            scene_state.special_dialogue = Some(special_dialogue_create(ctx, ui));
+
+            OpResult::YIELD
         },
 
         // Screen effects.
@@ -555,26 +559,6 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
               OpResult::COMPLETE | OpResult::YIELD
           }
         },
-        Op::ScreenShake { enabled } => {
-            println!("Unimplemented: screen shake enabled? {}", enabled);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::ColorMathScreen { r, g, b, duration, intensity, mode } => {
-            println!("Unimplemented: screen {:?} color math with {} {} {}, for {} seconds at {} intensity", mode, r, g, b, duration, intensity);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::ColorMathGeometry { .. } => {
-            println!("Unimplemented: screen color math geometry");
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::ColorMathPalette { mode, r, g, b, duration, color_start, color_count, intensity_start, intensity_end } => {
-            println!("Unimplemented: palette colors {} count {} {:?} color math with {} {} {}, for {} seconds from {} to {} instensity", color_start, color_count, mode, r, g, b, duration, intensity_start, intensity_end);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::WaitForColorMath => {
-            println!("Unimplemented: wait for color math");
-            OpResult::YIELD | OpResult::COMPLETE
-        },
 
         // Layer/camera ops.
         Op::ScrollLayers { x, y, cycles, flags } => {
@@ -624,58 +608,48 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
 
             OpResult::YIELD | OpResult::COMPLETE
         },
-        Op::ChangeLocationFromMemory { byte1, byte2, byte3, byte4 } => {
-            println!("Unimplemented: location change from memory {:?} {:?} {:?} {:?}", byte1, byte2, byte3, byte4);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
 
-        // Dummied inventory ops.
-        Op::ItemGive { actor, item, category } => {
-            println!("Unimplemented: give item {:?}, category {} to {:?}", item, category, actor);
+        // Inventory. Items and amounts in local memory are read from the actor the op refers to.
+        Op::ItemGive { actor, item, .. } => {
+            let actor_index = actor.deref(scene_state, this_actor);
+            let item_id = item.get_scene_u8(ctx, scene_state, actor_index) as usize;
+            ctx.party.item_give(item_id, 1);
             OpResult::YIELD | OpResult::COMPLETE
         },
-        Op::ItemTake { actor, item, category } => {
-            println!("Unimplemented: take item {:?}, category {} from {:?}", item, category, actor);
+        Op::ItemTake { actor, item, .. } => {
+            let actor_index = actor.deref(scene_state, this_actor);
+            let item_id = item.get_scene_u8(ctx, scene_state, actor_index) as usize;
+            ctx.party.item_take(item_id, 1);
             OpResult::YIELD | OpResult::COMPLETE
         },
-        Op::ItemGetAmount { item, category, dest } => {
-            println!("Unimplemented: get item {:?}, category {} amount, to {:?}", item, category, dest);
+        Op::ItemGetAmount { item, dest, .. } => {
+            let amount = ctx.party.get_item_amount(item).min(u8::MAX as u32) as u8;
+            dest.put_scene_u8(ctx, scene_state, amount);
             OpResult::YIELD | OpResult::COMPLETE
         },
         Op::GoldGive { actor, amount } => {
-            println!("Unimplemented: give {:?} gold to {:?}", amount, actor);
+            let actor_index = actor.deref(scene_state, this_actor);
+            let amount = amount.get_u16(ctx, scene_state, actor_index) as u32;
+            ctx.party.gold_give(amount);
             OpResult::YIELD | OpResult::COMPLETE
         },
         Op::GoldTake { actor, amount } => {
-            println!("Unimplemented: take {:?} gold from {:?}", amount, actor);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::ActorHeal { actor, hp, mp } => {
-            println!("Unimplemented: restore {} HP, {} MP to {:?}", hp, mp, actor);
+            let actor_index = actor.deref(scene_state, this_actor);
+            let amount = amount.get_u16(ctx, scene_state, actor_index) as u32;
+            ctx.party.gold_take(amount.min(ctx.party.get_gold()));
             OpResult::YIELD | OpResult::COMPLETE
         },
 
         // Palette changes.
-        Op::PaletteSetImmediate { sub_palette, color_index, data, length } => {
-            match sub_palette {
-                SubPalette::This => {
-                    let actor = scene_state.actors.get_mut(this_actor).unwrap();
-                    for i in 0..length / 2 {
-                        let color = (data[i * 2 + 1] as u16) << 8 | data[i * 2] as u16;
-                        actor.local_palette.colors[color_index + i] = Palette::decode_snes_color(color);
-                    }
-                },
-                SubPalette::Index(sub_palette) => {
-                    println!("Unimplemented: set subpalette {:?}, color {} to {:?}", sub_palette, color_index, data[0..length].to_vec());
-                },
+        Op::PaletteSetImmediate { sub_palette: SubPalette::This, color_index, data, length } => {
+            let actor = scene_state.actors.get_mut(this_actor).unwrap();
+            for i in 0..length / 2 {
+                let color = (data[i * 2 + 1] as u16) << 8 | data[i * 2] as u16;
+                actor.local_palette.colors[color_index + i] = Palette::decode_snes_color(color);
             }
 
             OpResult::COMPLETE
         },
-        Op::PaletteSetImmediateIndex { sub_palette, color_index, palette_index } => {
-            println!("Unimplemented: set palette immediate index {:?}, color {} to {}", sub_palette, color_index, palette_index);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
         Op::PaletteRestore => {
             let actor = scene_state.actors.get_mut(this_actor).unwrap();
             if let Some(sprite_info_key) = actor.sprite_info_key {
@@ -688,26 +662,15 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
         },
         Op::PaletteSetIndex { palette_index } => {
             let actor = scene_state.actors.get_mut(this_actor).unwrap();
-            let palette_key = ctx.assets.load_sprite_palette(&ctx.fs, palette_index);
-            let palette = ctx.assets.get_palette(palette_key);
-            actor.local_palette.clone_from(&palette);
+            match ctx.assets.load_sprite_palette(&ctx.fs, palette_index) {
+                Ok(palette_key) => actor.local_palette.clone_from(ctx.assets.get_palette(palette_key)),
+                Err(error) => println!("Actor {} could not load palette {}: {}", this_actor, palette_index, error),
+            }
 
             OpResult::COMPLETE
         },
 
         // Party management.
-        Op::PartyExploreMode { value } => {
-            println!("Unimplemented: party explore mode {}", value);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::PartyFollow => {
-            println!("Unimplemented: party follow");
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::Equip { pc, item, category } => {
-            println!("Unimplemented: party member {} equip item {} category {}", pc, item, category);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
         Op::PartyMemberAddToReserve { pc } => {
             ctx.party.add_character_to_reserve(pc);
             OpResult::YIELD | OpResult::COMPLETE
@@ -732,47 +695,7 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
             OpResult::YIELD | OpResult::COMPLETE
         },
 
-        // Sound playback.
-        Op::SoundPlay { sound, panning } => {
-            println!("Unimplemented: play sound {}, pan {:.2}", sound, panning);
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-        Op::SoundVolumeSlide { left, right, duration } => {
-            println!("Unimplemented: sound volume slide left {:.2}, right {:.2}, in {} seconds", left, right, duration);
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-        Op::SoundWaitEnd => {
-            println!("Unimplemented: wait for sound to end.");
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-
-        // Music playback.
-        Op::MusicPlay { music, interrupt } => {
-            println!("Unimplemented: play music {}, interrupt? {}", music, interrupt);
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-        Op::MusicTempoSlide { tempo, duration } => {
-            println!("Unimplemented: music tempo slide to {:.2} in {} seconds", tempo, duration);
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-        Op::MusicVolumeSlide { volume, duration } => {
-            println!("Unimplemented: music volume slide to {:.2} in {} seconds", volume, duration);
-            OpResult::YIELD | OpResult::COMPLETE
-        }
-        Op::MusicWaitEnd => {
-            println!("Unimplemented: wait for music to end.");
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-
-        // Special scenes and effects.
-        Op::SpecialScene { scene, flags } => {
-            println!("Unimplemented: special scene {} with flags {:?}", scene, flags);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
-        Op::SpecialOpenPortal { value1, value2, value3 } => {
-            println!("Unimplemented: open portal: {} {} {}", value1, value2, value3);
-            OpResult::YIELD | OpResult::COMPLETE
-        },
+        // Special effects.
         Op::SpecialEffect { effect } => {
             match effect {
                 SpecialEffect::ScreenCloseLeftRight => {
@@ -844,3 +767,87 @@ pub fn op_execute(ctx: &mut Context, scene_state: &mut SceneState, this_actor: u
         },
     }
 }
+
+/// Describes an op that is not implemented yet, and the result to skip it with. op_execute prints
+/// the description instead of executing these, and the script coverage report lists them.
+pub fn op_unimplemented(op: &Op) -> Option<(String, OpResult)> {
+    let description = match op {
+        Op::MovePartyTo { .. } => "move party".to_string(),
+        Op::ScreenShake { enabled } => format!("screen shake enabled? {}", enabled),
+        Op::ColorMathScreen { r, g, b, duration, intensity, mode } => format!("screen {:?} color math with {} {} {}, for {} seconds at {} intensity", mode, r, g, b, duration, intensity),
+        Op::ColorMathGeometry { .. } => "screen color math geometry".to_string(),
+        Op::ColorMathPalette { mode, r, g, b, duration, color_start, color_count, intensity_start, intensity_end } => format!("palette colors {} count {} {:?} color math with {} {} {}, for {} seconds from {} to {} instensity", color_start, color_count, mode, r, g, b, duration, intensity_start, intensity_end),
+        Op::WaitForColorMath => "wait for color math".to_string(),
+        Op::ChangeLocationFromMemory { byte1, byte2, byte3, byte4 } => format!("location change from memory {:?} {:?} {:?} {:?}", byte1, byte2, byte3, byte4),
+
+
+        // Item categories other than 0 only exist in the PC version, and are not known yet.
+        Op::ItemGive { actor, item, category } if *category != 0 => format!("give item {:?}, category {} to {:?}", item, category, actor),
+        Op::ItemTake { actor, item, category } if *category != 0 => format!("take item {:?}, category {} from {:?}", item, category, actor),
+        Op::ItemGetAmount { item, category, dest } if *category != 0 => format!("get item {}, category {} amount, to {:?}", item, category, dest),
+        Op::ActorHeal { actor, hp, mp } => format!("restore {} HP, {} MP to {:?}", hp, mp, actor),
+
+        Op::PaletteSetImmediate { sub_palette: SubPalette::Index(sub_palette), color_index, data, length } => {
+            return Some((format!("set subpalette {:?}, color {} to {:?}", sub_palette, color_index, data[0..*length].to_vec()), OpResult::COMPLETE));
+        },
+        Op::PaletteSetImmediateIndex { sub_palette, color_index, palette_index } => format!("set palette immediate index {:?}, color {} to {}", sub_palette, color_index, palette_index),
+
+        Op::PartyExploreMode { value } => format!("party explore mode {}", value),
+        Op::PartyFollow => "party follow".to_string(),
+        Op::Equip { pc, item, category } => format!("party member {} equip item {} category {}", pc, item, category),
+
+        Op::SoundPlay { sound, panning } => format!("play sound {}, pan {:.2}", sound, panning),
+        Op::SoundVolumeSlide { left, right, duration } => format!("sound volume slide left {:.2}, right {:.2}, in {} seconds", left, right, duration),
+        Op::SoundWaitEnd => "wait for sound to end".to_string(),
+        Op::MusicPlay { music, interrupt } => format!("play music {}, interrupt? {}", music, interrupt),
+        Op::MusicTempoSlide { tempo, duration } => format!("music tempo slide to {:.2} in {} seconds", tempo, duration),
+        Op::MusicVolumeSlide { volume, duration } => format!("music volume slide to {:.2} in {} seconds", volume, duration),
+        Op::MusicWaitEnd => "wait for music to end".to_string(),
+
+        Op::SpecialScene { scene, flags } => format!("special scene {} with flags {:?}", scene, flags),
+        Op::SpecialOpenPortal { value1, value2, value3 } => format!("open portal: {} {} {}", value1, value2, value3),
+        Op::SpecialEffect { effect } if !matches!(effect,
+            SpecialEffect::ScreenCloseLeftRight | SpecialEffect::ScreenCloseRightLeft |
+            SpecialEffect::ScreenOpenLeftRight | SpecialEffect::ScreenOpenRightLeft
+        ) => format!("special effect {:?}", effect),
+
+        // Ops that are decoded, but have no implementation at all.
+        Op::ActorJump { .. } |
+        Op::ActorJumpUnknown { .. } |
+        Op::JumpConditionalBattleRange { .. } |
+        Op::PlayMovie { .. } |
+        Op::Unknown { .. } => {
+            return Some((format!("{:?}", op), OpResult::COMPLETE));
+        },
+
+        _ => return None,
+    };
+
+    Some((description, OpResult::YIELD | OpResult::COMPLETE))
+}
+
+/// Returns false if executing this op ends up in an unimplemented or dummied path of op_execute.
+pub fn op_is_implemented(op: &Op) -> bool {
+    op_unimplemented(op).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::DataSource;
+    use crate::scene_script::scene_script_decoder::ActorRef;
+
+    #[test]
+    fn op_implemented_follows_execution() {
+        assert!(op_is_implemented(&Op::NOP));
+        assert!(!op_is_implemented(&Op::PartyFollow));
+        assert!(!op_is_implemented(&Op::SpecialEffect { effect: SpecialEffect::Reset }));
+        assert!(op_is_implemented(&Op::SpecialEffect { effect: SpecialEffect::ScreenOpenLeftRight }));
+        assert!(op_is_implemented(&Op::ItemGive { actor: ActorRef::This, item: DataSource::Immediate(1), category: 0 }));
+        assert!(!op_is_implemented(&Op::ItemGive { actor: ActorRef::This, item: DataSource::Immediate(1), category: 2 }));
+        assert!(op_is_implemented(&Op::GoldTake { actor: ActorRef::This, amount: DataSource::Immediate(100) }));
+
+        let (_, result) = op_unimplemented(&Op::Unknown { code: 0xFF, data: [0; 4] }).unwrap();
+        assert_eq!(result, OpResult::COMPLETE);
+    }
+}
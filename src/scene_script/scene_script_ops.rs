@@ -309,7 +309,7 @@ pub enum Op {
     ItemGetAmount {
         item: usize,
         category: usize,
-        dest: DataSource,
+        dest: DataDest,
     },
     ActorHeal {
         actor: ActorRef,
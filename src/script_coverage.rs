@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{Cursor, ErrorKind, Write};
+use std::path::Path;
+use byteorder::ReadBytesExt;
+use crate::filesystem::filesystem::FileSystem;
+use crate::GameMode;
+use crate::scene::scene::SCENE_COUNT;
+use crate::scene_script::scene_script_decoder;
+use crate::scene_script::scene_script_exec;
+use crate::util::json::JsonValue;
+use crate::world::world::WORLD_COUNT;
+use crate::world_script::tasks::run_script;
+use crate::world_script::world_script_decoder;
+
+/// How often an opcode is used, and where.
+#[derive(Default)]
+struct OpcodeCoverage {
+    names: BTreeSet<String>,
+    count: usize,
+    unimplemented_count: usize,
+    locations: BTreeSet<usize>,
+}
+
+/// Opcode usage for a set of scripts of one type.
+#[derive(Default)]
+struct ScriptCoverage {
+    script_count: usize,
+    op_count: usize,
+    opcodes: BTreeMap<u8, OpcodeCoverage>,
+    unknown_opcodes: BTreeMap<u8, OpcodeCoverage>,
+}
+
+impl ScriptCoverage {
+    fn add_op(&mut self, op_byte: u8, name: String, implemented: bool, locations: &[usize]) {
+        let coverage = self.opcodes.entry(op_byte).or_default();
+        coverage.names.insert(name);
+        coverage.count += 1;
+        if !implemented {
+            coverage.unimplemented_count += 1;
+        }
+        coverage.locations.extend(locations.iter());
+        self.op_count += 1;
+    }
+
+    fn add_unknown(&mut self, op_byte: u8, locations: &[usize]) {
+        let coverage = self.unknown_opcodes.entry(op_byte).or_default();
+        coverage.count += 1;
+        coverage.locations.extend(locations.iter());
+        self.op_count += 1;
+    }
+
+    fn to_json(&self, location_key: &str) -> JsonValue {
+        let opcodes: Vec<JsonValue> = self.opcodes.iter().map(|(op_byte, coverage)| {
+            JsonValue::object()
+                .with("opcode", format!("0x{:02X}", op_byte))
+                .with("names", coverage.names.iter().cloned().collect::<Vec<String>>())
+                .with("count", coverage.count)
+                .with("unimplemented", coverage.unimplemented_count)
+                .with(location_key, coverage.locations.iter().copied().collect::<Vec<usize>>())
+        }).collect();
+
+        let unknown_opcodes: Vec<JsonValue> = self.unknown_opcodes.iter().map(|(op_byte, coverage)| {
+            JsonValue::object()
+                .with("opcode", format!("0x{:02X}", op_byte))
+                .with("count", coverage.count)
+                .with(location_key, coverage.locations.iter().copied().collect::<Vec<usize>>())
+        }).collect();
+
+        let unimplemented_op_count: usize = self.opcodes.values().map(|x| x.unimplemented_count).sum();
+        let unknown_op_count: usize = self.unknown_opcodes.values().map(|x| x.count).sum();
+
+        JsonValue::object()
+            .with("scripts", self.script_count)
+            .with("ops", self.op_count)
+            .with("unimplemented_ops", unimplemented_op_count)
+            .with("unknown_ops", unknown_op_count)
+            .with("opcodes", opcodes)
+            .with("unknown_opcodes", unknown_opcodes)
+    }
+
+    fn print_summary(&self, title: &str, location_name: &str) {
+        let unimplemented_op_count: usize = self.opcodes.values().map(|x| x.unimplemented_count).sum();
+        let unknown_op_count: usize = self.unknown_opcodes.values().map(|x| x.count).sum();
+
+        println!("{}: {} scripts, {} ops, {} opcodes used.", title, self.script_count, self.op_count, self.opcodes.len());
+        println!("  Unimplemented: {} ops.", unimplemented_op_count);
+        for (op_byte, coverage) in self.opcodes.iter() {
+            if coverage.unimplemented_count == 0 {
+                continue;
+            }
+            let names: Vec<String> = coverage.names.iter().cloned().collect();
+            println!("    0x{:02X} {:<32} {:>6}x in {} {}s", op_byte, names.join(", "), coverage.unimplemented_count, coverage.locations.len(), location_name);
+        }
+
+        println!("  Unknown: {} ops.", unknown_op_count);
+        for (op_byte, coverage) in self.unknown_opcodes.iter() {
+            println!("    0x{:02X} {:>6}x in {} {}s", op_byte, coverage.count, coverage.locations.len(), location_name);
+        }
+        println!();
+    }
+}
+
+/// Decodes every scene and world script, and writes a report of which opcodes are used, which of
+/// them are not implemented yet and where they are used.
+pub fn script_coverage_report(fs: &FileSystem, output_path: &Path) -> std::io::Result<()> {
+    let scene_coverage = scene_script_coverage(fs);
+    let world_coverage = world_script_coverage(fs);
+
+    scene_coverage.print_summary("Scene scripts", "scene");
+    world_coverage.print_summary("World scripts", "world");
+
+    let mode = match fs.mode {
+        GameMode::Snes => "snes",
+        GameMode::Pc => "pc",
+    };
+    let report = JsonValue::object()
+        .with("version", env!("CARGO_PKG_VERSION"))
+        .with("mode", mode)
+        .with("scene_scripts", scene_coverage.to_json("scenes"))
+        .with("world_scripts", world_coverage.to_json("worlds"));
+
+    println!("Writing {}...", output_path.display());
+    let mut file = File::create(output_path)?;
+    file.write_all(report.to_string_pretty().as_bytes())?;
+
+    Ok(())
+}
+
+fn scene_script_coverage(fs: &FileSystem) -> ScriptCoverage {
+
+    // Scenes can share scripts, so decode each script only once.
+    let mut script_scenes: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for scene_index in 0..SCENE_COUNT {
+        match fs.read_scene_script_index(scene_index) {
+            Ok(script_index) => script_scenes.entry(script_index).or_default().push(scene_index),
+            Err(error) => println!("Skipping scene {}: {}", scene_index, error),
+        }
+    }
+
+    let mut coverage = ScriptCoverage::default();
+    for (script_index, scenes) in script_scenes.iter() {
+        let script = match fs.read_scene_script(*script_index) {
+            Ok(script) => script,
+            Err(error) => {
+                println!("Skipping scene script {}: {}", script_index, error);
+                continue;
+            },
+        };
+        let mut data = Cursor::new(script.get_data().clone());
+        let data_len = data.get_ref().len() as u64;
+
+        while data.position() < data_len {
+            let op_byte = data.read_u8().unwrap();
+            match scene_script_decoder::op_decode_opcode(op_byte, &mut data, fs.mode) {
+                Ok(op) => coverage.add_op(op_byte, op_name(&op), scene_script_exec::op_is_implemented(&op), scenes),
+                Err(error) => {
+                    if error.kind() != ErrorKind::Unsupported {
+                        break;
+                    }
+                    coverage.add_unknown(op_byte, scenes);
+                },
+            }
+        }
+        coverage.script_count += 1;
+    }
+
+    coverage
+}
+
+fn world_script_coverage(fs: &FileSystem) -> ScriptCoverage {
+    let mut script_worlds: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for world_index in 0..WORLD_COUNT {
+        match fs.read_world_script_index(world_index) {
+            Ok(script_index) => script_worlds.entry(script_index).or_default().push(world_index),
+            Err(error) => println!("Skipping world {}: {}", world_index, error),
+        }
+    }
+
+    let mut coverage = ScriptCoverage::default();
+    for (script_index, worlds) in script_worlds.iter() {
+        let mut data = match fs.backend.get_world_script_data(*script_index) {
+            Ok(data) => Cursor::new(data),
+            Err(error) => {
+                println!("Skipping world script {}: {}", script_index, error);
+                continue;
+            },
+        };
+        let data_len = data.get_ref().len() as u64;
+
+        while data.position() < data_len {
+            let op_byte = data.read_u8().unwrap();
+            match world_script_decoder::op_decode_opcode(op_byte, &mut data, fs.mode) {
+                Ok(op) => coverage.add_op(op_byte, op_name(&op), run_script::op_is_implemented(&op), worlds),
+                Err(error) => {
+                    if error.kind() != ErrorKind::Unsupported {
+                        break;
+                    }
+                    coverage.add_unknown(op_byte, worlds);
+                },
+            }
+        }
+        coverage.script_count += 1;
+    }
+
+    coverage
+}
+
+// The name of an op's enum variant.
+fn op_name(op: &impl std::fmt::Debug) -> String {
+    let text = format!("{:?}", op);
+    text.split([' ', '{', '(']).next().unwrap_or_default().to_string()
+}
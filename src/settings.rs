@@ -0,0 +1,126 @@
+/// Number of text speed settings, as in the game's configuration menu.
+pub const TEXT_SPEED_COUNT: usize = 8;
+
+/// Auto-advance delays in seconds that the settings menu cycles through.
+pub const AUTO_ADVANCE_DELAYS: [f64; 6] = [0.5, 1.0, 1.5, 2.0, 3.0, 5.0];
+
+/// Textbox window animation speeds that the settings menu cycles through, in full openings or
+/// closings per second.
+pub const TEXTBOX_ANIMATION_SPEEDS: [f64; 5] = [3.0, 6.0, 12.0, 24.0, 60.0];
+
+/// Textbox window animation speed when it is not set.
+pub const TEXTBOX_ANIMATION_SPEED_DEFAULT: f64 = 6.0;
+
+/// User settings that affect how the game plays.
+pub struct Settings {
+
+    /// Text speed from 0 (fastest) to TEXT_SPEED_COUNT - 1 (slowest).
+    pub text_speed: usize,
+
+    /// Seconds to wait before continuing after a textbox page is complete, if enabled.
+    pub auto_advance_delay: Option<f64>,
+
+    /// Full openings or closings of the textbox window per second.
+    pub textbox_animation_speed: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            text_speed: 0,
+            auto_advance_delay: None,
+            textbox_animation_speed: TEXTBOX_ANIMATION_SPEED_DEFAULT,
+        }
+    }
+}
+
+impl Settings {
+    pub fn new() -> Settings {
+        Self::default()
+    }
+
+    pub fn text_speed_faster(&mut self) {
+        self.text_speed = self.text_speed.saturating_sub(1);
+    }
+
+    pub fn text_speed_slower(&mut self) {
+        self.text_speed = (self.text_speed + 1).min(TEXT_SPEED_COUNT - 1);
+    }
+
+    /// Cycles from disabled through increasingly longer delays.
+    pub fn auto_advance_longer(&mut self) {
+        self.auto_advance_delay = match self.auto_advance_delay {
+            None => Some(AUTO_ADVANCE_DELAYS[0]),
+            Some(delay) => AUTO_ADVANCE_DELAYS.iter().copied().find(|preset| *preset > delay).or(Some(delay)),
+        };
+    }
+
+    /// Cycles to shorter delays, and disables auto-advance after the shortest one.
+    pub fn auto_advance_shorter(&mut self) {
+        self.auto_advance_delay = match self.auto_advance_delay {
+            None => None,
+            Some(delay) => AUTO_ADVANCE_DELAYS.iter().rev().copied().find(|preset| *preset < delay),
+        };
+    }
+
+    pub fn textbox_animation_faster(&mut self) {
+        let speed = self.textbox_animation_speed;
+        self.textbox_animation_speed = TEXTBOX_ANIMATION_SPEEDS.iter().copied().find(|preset| *preset > speed).unwrap_or(speed);
+    }
+
+    pub fn textbox_animation_slower(&mut self) {
+        let speed = self.textbox_animation_speed;
+        self.textbox_animation_speed = TEXTBOX_ANIMATION_SPEEDS.iter().rev().copied().find(|preset| *preset < speed).unwrap_or(speed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_cycle() {
+        let mut settings = Settings::new();
+        settings.text_speed_faster();
+        assert_eq!(settings.text_speed, 0);
+        for _ in 0..10 {
+            settings.text_speed_slower();
+        }
+        assert_eq!(settings.text_speed, TEXT_SPEED_COUNT - 1);
+
+        settings.auto_advance_shorter();
+        assert_eq!(settings.auto_advance_delay, None);
+        settings.auto_advance_longer();
+        assert_eq!(settings.auto_advance_delay, Some(0.5));
+
+        // Delays from the command line move to the nearest preset.
+        settings.auto_advance_delay = Some(2.5);
+        settings.auto_advance_longer();
+        assert_eq!(settings.auto_advance_delay, Some(3.0));
+        settings.auto_advance_delay = Some(2.5);
+        settings.auto_advance_shorter();
+        assert_eq!(settings.auto_advance_delay, Some(2.0));
+
+        settings.auto_advance_delay = Some(10.0);
+        settings.auto_advance_longer();
+        assert_eq!(settings.auto_advance_delay, Some(10.0));
+        settings.auto_advance_delay = Some(0.5);
+        settings.auto_advance_shorter();
+        assert_eq!(settings.auto_advance_delay, None);
+
+        assert_eq!(settings.textbox_animation_speed, TEXTBOX_ANIMATION_SPEED_DEFAULT);
+        settings.textbox_animation_faster();
+        assert_eq!(settings.textbox_animation_speed, 12.0);
+        for _ in 0..10 {
+            settings.textbox_animation_slower();
+        }
+        assert_eq!(settings.textbox_animation_speed, TEXTBOX_ANIMATION_SPEEDS[0]);
+
+        // Speeds from the command line move to the nearest preset.
+        settings.textbox_animation_speed = 100.0;
+        settings.textbox_animation_faster();
+        assert_eq!(settings.textbox_animation_speed, 100.0);
+        settings.textbox_animation_slower();
+        assert_eq!(settings.textbox_animation_speed, 60.0);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::Context;
+use crate::input::InputAction;
+use crate::renderer::{TextFlags, TextFont, TextRenderable};
+use crate::settings::Settings;
+use crate::software_renderer::blit::{blit_bitmap_to_surface, BitmapBlitFlags};
+use crate::software_renderer::text::TextDrawFlags;
+
+const SETTINGS_MENU_CHIP_WIDTH: i32 = 24;
+const SETTINGS_MENU_LINE_HEIGHT: i32 = 16;
+const SETTINGS_MENU_TEXT_COLOR: [u8; 4] = [231, 231, 231, 255];
+
+#[derive(Clone, Copy, PartialEq)]
+enum SettingsMenuItem {
+    TextSpeed,
+    AutoAdvance,
+    TextBoxAnimation,
+}
+
+static SETTINGS_MENU_ITEMS: [SettingsMenuItem; 3] = [
+    SettingsMenuItem::TextSpeed,
+    SettingsMenuItem::AutoAdvance,
+    SettingsMenuItem::TextBoxAnimation,
+];
+
+/// An in-game window to change settings. Up and down select a setting, left and right change it.
+#[derive(Default)]
+pub struct SettingsMenu {
+    visible: bool,
+    selected: usize,
+}
+
+impl SettingsMenu {
+    pub fn new() -> SettingsMenu {
+        SettingsMenu {
+            visible: false,
+            selected: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn toggle_visible(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn tick(&mut self, ctx: &mut Context) {
+        if ctx.input.was_pressed(InputAction::MenuUp) {
+            self.selected = (self.selected + SETTINGS_MENU_ITEMS.len() - 1) % SETTINGS_MENU_ITEMS.len();
+        } else if ctx.input.was_pressed(InputAction::MenuDown) {
+            self.selected = (self.selected + 1) % SETTINGS_MENU_ITEMS.len();
+        }
+
+        let settings = &mut ctx.settings;
+        let item = SETTINGS_MENU_ITEMS[self.selected];
+        if ctx.input.was_pressed(InputAction::MenuLeft) {
+            match item {
+                SettingsMenuItem::TextSpeed => settings.text_speed_faster(),
+                SettingsMenuItem::AutoAdvance => settings.auto_advance_shorter(),
+                SettingsMenuItem::TextBoxAnimation => settings.textbox_animation_slower(),
+            }
+        } else if ctx.input.was_pressed(InputAction::MenuRight) {
+            match item {
+                SettingsMenuItem::TextSpeed => settings.text_speed_slower(),
+                SettingsMenuItem::AutoAdvance => settings.auto_advance_longer(),
+                SettingsMenuItem::TextBoxAnimation => settings.textbox_animation_faster(),
+            }
+        }
+    }
+
+    pub fn render(&mut self, ctx: &mut Context) {
+        if !self.visible {
+            return;
+        }
+
+        let chip_height = SETTINGS_MENU_ITEMS.len() as i32 * 2 + 2;
+        let x = ctx.render.target.width as i32 / 2 - SETTINGS_MENU_CHIP_WIDTH * 4;
+        let y = ctx.render.target.height as i32 / 2 - chip_height * 4;
+        ctx.ui_theme.render_window(&mut ctx.render.target, x, y, SETTINGS_MENU_CHIP_WIDTH, chip_height);
+
+        for (index, item) in SETTINGS_MENU_ITEMS.iter().enumerate() {
+            let item_y = y + 8 + index as i32 * SETTINGS_MENU_LINE_HEIGHT;
+            let (label, value) = item_text(*item, &ctx.settings);
+
+            let mut label = TextRenderable::new(label.to_string(), TextFont::Regular, SETTINGS_MENU_TEXT_COLOR, TextDrawFlags::SHADOW, 0);
+            ctx.render.render_text(&mut label, x + 24, item_y, TextFlags::empty());
+            let mut value = TextRenderable::new(value, TextFont::Regular, SETTINGS_MENU_TEXT_COLOR, TextDrawFlags::SHADOW, 0);
+            ctx.render.render_text(&mut value, x + SETTINGS_MENU_CHIP_WIDTH * 8 - 16, item_y, TextFlags::AlignHEnd);
+
+            if index == self.selected {
+                blit_bitmap_to_surface(&ctx.ui_theme.cursor_bitmap, &mut ctx.render.target, 0, 0, 16, 16, x + 5, item_y + 1, &ctx.ui_theme.cursor_palette, 0, BitmapBlitFlags::SKIP_0);
+            }
+        }
+    }
+}
+
+fn item_text(item: SettingsMenuItem, settings: &Settings) -> (&'static str, String) {
+    match item {
+        SettingsMenuItem::TextSpeed => ("Text speed", (settings.text_speed + 1).to_string()),
+        SettingsMenuItem::AutoAdvance => ("Auto-advance", match settings.auto_advance_delay {
+            Some(delay) => format!("{:.1} s", delay),
+            None => "Off".to_string(),
+        }),
+        SettingsMenuItem::TextBoxAnimation => ("Textbox animation", format!("{:.0}/s", settings.textbox_animation_speed)),
+    }
+}
@@ -13,19 +13,17 @@ pub enum CompareOp {
 }
 
 impl CompareOp {
-    pub fn from_value(value: usize) -> CompareOp {
+    pub fn from_value(value: usize) -> Option<CompareOp> {
         match value {
-            0 => CompareOp::Eq,
-            1 => CompareOp::NotEq,
-            2 => CompareOp::Gt,
-            3 => CompareOp::Lt,
-            4 => CompareOp::GtEq,
-            5 => CompareOp::LtEq,
-            6 => CompareOp::And,
-            7 => CompareOp::Or,
-            other => {
-                panic!("Unknown conditional op {:?}", other);
-            },
+            0 => Some(CompareOp::Eq),
+            1 => Some(CompareOp::NotEq),
+            2 => Some(CompareOp::Gt),
+            3 => Some(CompareOp::Lt),
+            4 => Some(CompareOp::GtEq),
+            5 => Some(CompareOp::LtEq),
+            6 => Some(CompareOp::And),
+            7 => Some(CompareOp::Or),
+            _ => None,
         }
     }
 }
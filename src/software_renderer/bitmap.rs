@@ -1,5 +1,6 @@
 use std::fs::File;
-use std::io::BufReader;
+use std::io;
+use std::io::{BufReader, ErrorKind};
 use std::path::Path;
 use std::ptr::write_bytes;
 use png::{ColorType, Decoder};
@@ -34,28 +35,48 @@ impl Bitmap {
     }
 
     pub fn from_png(path: &Path) -> (Bitmap, Palette) {
-        let buf = BufReader::new(File::open(path).unwrap());
+        let (bitmap, mut palette) = Bitmap::read_png(path).unwrap();
+        palette.colors.resize(256, [0, 0, 0, 0xFF]);
+
+        (bitmap, palette)
+    }
+
+    // Reads an indexed PNG image with a bit depth of 1, 2, 4 or 8. The returned palette has as many
+    // colors as the PNG palette.
+    pub fn read_png(path: &Path) -> io::Result<(Bitmap, Palette)> {
+        let buf = BufReader::new(File::open(path)?);
         let decoder = Decoder::new(buf);
-        let mut reader = decoder.read_info().unwrap();
-        let mut buf = vec![0; reader.output_buffer_size().unwrap()];
+        let mut reader = decoder.read_info().map_err(io::Error::other)?;
+        let size = reader.output_buffer_size().ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "PNG image is too large"))?;
+        let mut buf = vec![0; size];
 
-        let info = reader.next_frame(&mut buf).unwrap();
+        let info = reader.next_frame(&mut buf).map_err(io::Error::other)?;
         if info.color_type != ColorType::Indexed {
-            panic!("Bitmap only supports reading indexed PNG images.");
+            return Err(io::Error::new(ErrorKind::InvalidData, "Bitmap only supports reading indexed PNG images."));
         }
 
+        // Unpack pixels from images with less than 8 bits per pixel.
+        let bits = info.bit_depth as usize;
+        let mask = ((1u16 << bits) - 1) as u8;
         let mut bitmap = Bitmap::new(info.width, info.height);
-        bitmap.data.copy_from_slice(&buf[..info.buffer_size()]);
+        for y in 0..info.height as usize {
+            let row = &buf[y * info.line_size..(y + 1) * info.line_size];
+            for x in 0..info.width as usize {
+                let bit = x * bits;
+                let shift = 8 - bits - (bit % 8);
+                bitmap.data[y * info.width as usize + x] = (row[bit / 8] >> shift) & mask;
+            }
+        }
 
-        let mut palette = Palette::new(256);
-        let data = reader.info().palette.as_ref().unwrap();
+        let data = reader.info().palette.as_ref().ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "PNG image has no palette"))?;
+        let mut palette = Palette::new(data.len() / 3);
         for i in 0..data.len() / 3 {
             palette.colors[i][0] = data[i * 3 + 0];
             palette.colors[i][1] = data[i * 3 + 1];
             palette.colors[i][2] = data[i * 3 + 2];
         }
 
-        (bitmap, palette)
+        Ok((bitmap, palette))
     }
 
     pub fn clear(&mut self) {
@@ -72,8 +93,8 @@ impl Bitmap {
 
         for y in 0..height {
             for x in 0..width {
-                let scale_x = x as f64 / factor;
-                let scale_y = y as f64 / factor;
+                let scale_x = (x as f64 / factor).floor();
+                let scale_y = (y as f64 / factor).floor();
                 let src = ((scale_y * self.width as f64) + scale_x) as usize;
                 let dest = (y * width + x) as usize;
 
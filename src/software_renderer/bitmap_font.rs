@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use crate::software_renderer::bitmap::Bitmap;
+use crate::software_renderer::palette::Color;
+use crate::software_renderer::surface::Surface;
+use crate::software_renderer::text::TextDrawFlags;
+
+/// The game's own fonts, used instead of the TTF fonts when available.
+pub struct NativeFonts {
+    pub regular: BitmapFont,
+    pub small: BitmapFont,
+}
+
+/// A font drawn from 2 bit indexed glyph graphics. Color 1 is the glyph itself, colors 2 and 3 are
+/// its shadow.
+pub struct BitmapFont {
+    bitmap: Bitmap,
+    glyph_width: u32,
+    glyph_height: u32,
+
+    // Horizontal advance of each glyph.
+    widths: Vec<u8>,
+
+    // Text of a glyph, either a single character or a tag like "<HAND1>", to its glyph index.
+    glyph_map: HashMap<String, usize>,
+}
+
+impl BitmapFont {
+
+    /// Glyphs are read from left to right, top to bottom in the bitmap.
+    pub fn new(bitmap: Bitmap, glyph_width: u32, glyph_height: u32, widths: Vec<u8>, glyph_map: HashMap<String, usize>) -> BitmapFont {
+        BitmapFont {
+            bitmap,
+            glyph_width,
+            glyph_height,
+            widths,
+            glyph_map,
+        }
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.widths.len()
+    }
+
+    pub fn get_glyph_width(&self, glyph: usize) -> u32 {
+        self.widths.get(glyph).map_or(0, |width| *width as u32)
+    }
+
+    pub fn get_glyph_index(&self, text: &str) -> Option<usize> {
+        self.glyph_map.get(text).copied()
+    }
+
+    /// Returns true if the glyph has any pixels set.
+    pub fn glyph_has_pixels(&self, glyph: usize) -> bool {
+        let (glyph_x, glyph_y) = self.glyph_position(glyph);
+        for y in 0..self.glyph_height {
+            let src = ((glyph_y + y) * self.bitmap.width + glyph_x) as usize;
+            if self.bitmap.data[src..src + self.glyph_width as usize].iter().any(|pixel| *pixel != 0) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn glyph_position(&self, glyph: usize) -> (u32, u32) {
+        let glyphs_per_row = (self.bitmap.width / self.glyph_width) as usize;
+        (
+            (glyph % glyphs_per_row) as u32 * self.glyph_width,
+            (glyph / glyphs_per_row) as u32 * self.glyph_height,
+        )
+    }
+
+    // Splits a line of text into glyphs. Tags are matched as a whole, and text without a glyph is
+    // skipped.
+    fn line_glyphs(&self, text: &str) -> Vec<usize> {
+        let mut glyphs = Vec::new();
+        let mut rest = text;
+        while let Some(character) = rest.chars().next() {
+            if character == '<' {
+                let Some(end) = rest.find('>') else {
+                    break;
+                };
+                if let Some(glyph) = self.get_glyph_index(&rest[..=end]) {
+                    glyphs.push(glyph);
+                }
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            if let Some(glyph) = self.get_glyph_index(&rest[..character.len_utf8()]) {
+                glyphs.push(glyph);
+            }
+            rest = &rest[character.len_utf8()..];
+        }
+
+        glyphs
+    }
+
+    fn line_width(&self, text: &str) -> u32 {
+        self.line_glyphs(text).iter().map(|glyph| self.get_glyph_width(*glyph)).sum()
+    }
+
+    pub fn measure(&self, text: &str) -> (u32, u32) {
+        let lines: Vec<&str> = text.split('\n').collect();
+        let width = lines.iter().map(|line| self.line_width(line)).max().unwrap_or(0);
+
+        (width, lines.len() as u32 * self.glyph_height)
+    }
+
+    // Wraps text on spaces so that lines fit in the wrap width, if possible.
+    fn wrap(&self, text: &str, wrap_width: i32) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+                if !line.is_empty() && self.line_width(&candidate) as i32 > wrap_width {
+                    lines.push(line);
+                    line = word.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    pub fn draw_to_surface(&self, text: &str, color: Color, flags: TextDrawFlags, wrap_width: i32) -> Surface {
+        let lines = if wrap_width > 0 {
+            self.wrap(text, wrap_width)
+        } else {
+            text.split('\n').map(|line| line.to_string()).collect()
+        };
+
+        // Simulate the Chrono Trigger SNES character shadow colors, as the TTF text does.
+        let colors = [
+            None,
+            Some(color),
+            if flags.contains(TextDrawFlags::SHADOW) { Some(shade(color, 0.214)) } else { None },
+            if flags.contains(TextDrawFlags::SHADOW) { Some(shade(color, 0.107)) } else { None },
+        ];
+
+        let width = lines.iter().map(|line| self.line_width(line)).max().unwrap_or(0).max(1);
+        let mut surface = Surface::new(width, lines.len() as u32 * self.glyph_height);
+        for (line_index, line) in lines.iter().enumerate() {
+            let mut x = 0;
+            let y = line_index as u32 * self.glyph_height;
+            for glyph in self.line_glyphs(line) {
+                self.draw_glyph(&mut surface, glyph, x, y, &colors);
+                x += self.get_glyph_width(glyph);
+            }
+        }
+
+        surface
+    }
+
+    fn draw_glyph(&self, surface: &mut Surface, glyph: usize, x: u32, y: u32, colors: &[Option<Color>; 4]) {
+        let (glyph_x, glyph_y) = self.glyph_position(glyph);
+        for glyph_row in 0..self.glyph_height {
+            for glyph_column in 0..self.glyph_width {
+                let dest_x = x + glyph_column;
+                if dest_x >= surface.width {
+                    break;
+                }
+
+                let src = ((glyph_y + glyph_row) * self.bitmap.width + glyph_x + glyph_column) as usize;
+                let Some(color) = colors[(self.bitmap.data[src] & 3) as usize] else {
+                    continue;
+                };
+
+                let dest = (((y + glyph_row) * surface.width + dest_x) * 4) as usize;
+                surface.data[dest..dest + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+fn shade(color: Color, factor: f64) -> Color {
+    [
+        (color[0] as f64 * factor) as u8,
+        (color[1] as f64 * factor) as u8,
+        (color[2] as f64 * factor) as u8,
+        color[3],
+    ]
+}
@@ -0,0 +1,102 @@
+use std::env;
+use std::fs::create_dir_all;
+use std::path::Path;
+use super::palette::Color;
+use super::surface::Surface;
+
+const DIFF_COLOR: Color = [255, 0, 0, 255];
+
+// Compares a rendered surface against a checked-in PNG in tests/golden. Set UPDATE_GOLDEN=1 to
+// write new images, or to rewrite all of them after an intended change. A missing image fails
+// the test otherwise. On a mismatch, an image with the expected, actual and differing pixels
+// side by side is written to target/golden_diff.
+pub fn assert_golden(name: &str, surface: &Surface) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let golden_path = root.join("tests/golden").join(format!("{}.png", name));
+
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        write_png(&golden_path, surface);
+        println!("Wrote golden image {}.", golden_path.display());
+        return;
+    }
+
+    if !golden_path.exists() {
+        panic!("{}: golden image {} does not exist, run with UPDATE_GOLDEN=1 to create it.", name, golden_path.display());
+    }
+
+    let expected = Surface::from_png(&golden_path);
+    let differences = count_differences(&expected, surface);
+    if differences == 0 {
+        return;
+    }
+
+    let diff_path = root.join("target/golden_diff").join(format!("{}.png", name));
+    write_png(&diff_path, &render_diff(&expected, surface));
+
+    panic!(
+        "{}: {} pixels differ from {}, diff image written to {}.",
+        name, differences, golden_path.display(), diff_path.display(),
+    );
+}
+
+fn write_png(path: &Path, surface: &Surface) {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).unwrap();
+    }
+    surface.write_to_png(path);
+}
+
+fn count_differences(expected: &Surface, actual: &Surface) -> usize {
+    if expected.width != actual.width || expected.height != actual.height {
+        return (expected.width * expected.height).max(actual.width * actual.height) as usize;
+    }
+
+    expected.data.chunks_exact(4)
+        .zip(actual.data.chunks_exact(4))
+        .filter(|(a, b)| a != b)
+        .count()
+}
+
+// Expected, actual and a dimmed copy of the actual image with differing pixels marked.
+fn render_diff(expected: &Surface, actual: &Surface) -> Surface {
+    let width = expected.width.max(actual.width);
+    let height = expected.height.max(actual.height);
+    let mut diff = Surface::new(width * 3, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let expected_color = get_pixel(expected, x, y);
+            let actual_color = get_pixel(actual, x, y);
+
+            let marked = if expected_color != actual_color {
+                DIFF_COLOR
+            } else {
+                let color = actual_color.unwrap_or([0, 0, 0, 0]);
+                [color[0] / 4, color[1] / 4, color[2] / 4, 255]
+            };
+
+            put_pixel(&mut diff, x, y, expected_color.unwrap_or([0, 0, 0, 0]));
+            put_pixel(&mut diff, x + width, y, actual_color.unwrap_or([0, 0, 0, 0]));
+            put_pixel(&mut diff, x + width * 2, y, marked);
+        }
+    }
+
+    diff
+}
+
+fn get_pixel(surface: &Surface, x: u32, y: u32) -> Option<Color> {
+    if x >= surface.width || y >= surface.height {
+        return None;
+    }
+
+    let src = ((y * surface.width + x) * 4) as usize;
+    let mut color: Color = [0; 4];
+    color.copy_from_slice(&surface.data[src..src + 4]);
+
+    Some(color)
+}
+
+fn put_pixel(surface: &mut Surface, x: u32, y: u32, color: Color) {
+    let dest = ((y * surface.width + x) * 4) as usize;
+    surface.data[dest..dest + 4].copy_from_slice(&color);
+}
@@ -1,6 +1,12 @@
 pub mod bitmap;
+pub mod bitmap_font;
 pub mod blit;
 pub mod surface;
 pub mod palette;
 pub mod draw;
 pub mod text;
+
+#[cfg(test)]
+pub mod golden;
+#[cfg(test)]
+mod tests;
@@ -7,6 +7,7 @@ use byteorder::LittleEndian;
 use byteorder::WriteBytesExt;
 use png::ColorType;
 use png::Decoder;
+use png::Encoder;
 use crate::util::rect::Rect;
 use super::palette::Color;
 
@@ -58,6 +59,16 @@ impl Surface {
         surface
     }
 
+    pub fn write_to_png(&self, path: &Path) {
+        let file = File::create(path).expect("Could not open PNG file for writing");
+        let mut encoder = Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&self.data).unwrap();
+    }
+
     pub fn fill(&mut self, color: Color) {
         let row_len = (self.width * 4) as usize;
         unsafe {
@@ -0,0 +1,352 @@
+use std::path::Path;
+use crate::sprites::sprite_assembly::{SpriteAssemblyChip, SpriteAssemblyChipFlags, SpriteAssemblyFrame};
+use crate::sprites::sprite_renderer::render_sprite;
+use crate::util::rect::Rect;
+use super::bitmap::Bitmap;
+use super::bitmap_font::BitmapFont;
+use super::blit::{blit_bitmap_to_bitmap, blit_bitmap_to_surface, blit_bitmap_to_surface_and_source, blit_surface_to_surface, BitmapBlitFlags, SurfaceBlendOps};
+use super::draw::{draw_box_filled, draw_box_gradient_vertical, draw_line};
+use super::golden::assert_golden;
+use super::palette::{render_palette, Color, Palette};
+use super::surface::Surface;
+use super::text::{text_draw_to_surface, text_draw_to_surface_wrapped, TextDrawFlags};
+
+const BACKGROUND: Color = [48, 56, 64, 255];
+
+const BLEND_OPS: [SurfaceBlendOps; 6] = [
+    SurfaceBlendOps::Copy,
+    SurfaceBlendOps::CopyAlpha,
+    SurfaceBlendOps::Blend,
+    SurfaceBlendOps::CopyAlphaGreyscale,
+    SurfaceBlendOps::Add,
+    SurfaceBlendOps::Subtract,
+];
+
+// 16 distinct colors, followed by a second bank of 16 inverted colors for palette offsets.
+fn test_palette() -> Palette {
+    let mut palette = Palette::new(32);
+    for index in 0..16 {
+        let color = [
+            (index * 17) as u8,
+            ((index * 97) % 256) as u8,
+            (255 - index * 13) as u8,
+            255,
+        ];
+        palette.colors[index] = color;
+        palette.colors[index + 16] = [255 - color[0], 255 - color[1], 255 - color[2], 255];
+    }
+
+    palette
+}
+
+// An asymmetric 8x8 chip so that flips are visible. Color 0 is transparent when skipped.
+fn test_chip() -> Bitmap {
+    const CHIP: [&str; 8] = [
+        "11111100",
+        "12222100",
+        "12000000",
+        "12333100",
+        "12000000",
+        "12000040",
+        "12000444",
+        "00000040",
+    ];
+
+    let mut data = Vec::new();
+    for row in CHIP {
+        data.extend(row.bytes().map(|x| x - b'0'));
+    }
+
+    Bitmap::from_raw_data(8, 8, data)
+}
+
+// A 16x16 sprite sheet built from four differently colored chips.
+fn test_sprite_sheet() -> Bitmap {
+    let chip = test_chip();
+    let mut sheet = Bitmap::new(16, 16);
+    for (index, offset) in [0u8, 3, 6, 9].iter().enumerate() {
+        let mut colored = chip.clone();
+        for pixel in colored.data.iter_mut() {
+            if *pixel != 0 {
+                *pixel += offset;
+            }
+        }
+        blit_bitmap_to_bitmap(&colored, &mut sheet, 0, 0, 8, 8, (index as i32 % 2) * 8, (index as i32 / 2) * 8, BitmapBlitFlags::empty());
+    }
+
+    sheet
+}
+
+fn test_sprite_frame(flip_x: bool) -> SpriteAssemblyFrame {
+    let mut frame = SpriteAssemblyFrame::new();
+    for index in 0..4 {
+        let mut flags = if index < 2 { SpriteAssemblyChipFlags::IS_TOP } else { SpriteAssemblyChipFlags::IS_BOTTOM };
+        if flip_x {
+            flags |= SpriteAssemblyChipFlags::FLIP_X;
+        }
+
+        // Mirror the chip positions along with the chip contents.
+        let x = (index % 2) * 8;
+        frame.chips.push(SpriteAssemblyChip {
+            x: if flip_x { 8 - x } else { x },
+            y: (index / 2) * 8,
+            width: 8,
+            height: 8,
+            src_index: 0,
+            src_x: (index % 2) * 8,
+            src_y: (index / 2) * 8,
+            flags,
+        });
+    }
+
+    frame
+}
+
+// Horizontal color ramp, to make blending results visible.
+fn gradient_surface(width: u32, height: u32) -> Surface {
+    let mut surface = Surface::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let dest = ((y * width + x) * 4) as usize;
+            surface.data[dest..dest + 4].copy_from_slice(&[
+                (x * 255 / (width - 1)) as u8,
+                (y * 255 / (height - 1)) as u8,
+                128,
+                255,
+            ]);
+        }
+    }
+
+    surface
+}
+
+// Renders an indexed bitmap with a greyscale ramp, for inspecting pixel source data.
+fn bitmap_to_surface(bitmap: &Bitmap) -> Surface {
+    let mut palette = Palette::new(256);
+    for (index, color) in palette.colors.iter_mut().enumerate() {
+        let value = (index * 16).min(255) as u8;
+        *color = [value, value, value, 255];
+    }
+
+    let mut surface = Surface::new(bitmap.width, bitmap.height);
+    blit_bitmap_to_surface(bitmap, &mut surface, 0, 0, bitmap.width as i32, bitmap.height as i32, 0, 0, &palette, 0, BitmapBlitFlags::empty());
+
+    surface
+}
+
+#[test]
+fn tileset_chips() {
+    let chip = test_chip();
+    let palette = test_palette();
+    let mut surface = Surface::new(48, 32);
+    surface.fill(BACKGROUND);
+
+    // All flip combinations, for both palette banks.
+    let flips = [
+        BitmapBlitFlags::empty(),
+        BitmapBlitFlags::FLIP_X,
+        BitmapBlitFlags::FLIP_Y,
+        BitmapBlitFlags::FLIP_X | BitmapBlitFlags::FLIP_Y,
+    ];
+    for (index, flip) in flips.iter().enumerate() {
+        let x = 2 + index as i32 * 10;
+        blit_bitmap_to_surface(&chip, &mut surface, 0, 0, 8, 8, x, 2, &palette, 0, *flip | BitmapBlitFlags::SKIP_0);
+        blit_bitmap_to_surface(&chip, &mut surface, 0, 0, 8, 8, x, 12, &palette, 16, *flip | BitmapBlitFlags::SKIP_0);
+    }
+
+    // Opaque color 0, and chips clipped by the surface edges.
+    blit_bitmap_to_surface(&chip, &mut surface, 0, 0, 8, 8, 2, 22, &palette, 0, BitmapBlitFlags::empty());
+    blit_bitmap_to_surface(&chip, &mut surface, 0, 0, 8, 8, -4, 26, &palette, 0, BitmapBlitFlags::SKIP_0);
+    blit_bitmap_to_surface(&chip, &mut surface, 0, 0, 8, 8, 44, 26, &palette, 16, BitmapBlitFlags::SKIP_0);
+
+    assert_golden("software_renderer/tileset_chips", &surface);
+}
+
+#[test]
+fn sprite_flips_and_priorities() {
+    let sheet = test_sprite_sheet();
+    let palette = test_palette();
+    let chip = test_chip();
+    let mut surface = Surface::new(96, 24);
+    let mut source = Bitmap::new(96, 24);
+    surface.fill(BACKGROUND);
+
+    let frame = test_sprite_frame(false);
+    let frame_flipped = test_sprite_frame(true);
+
+    // Complete sprites, top and bottom halves only, and a mirrored sprite.
+    render_sprite(&mut surface, &mut source, 1, true, true, &frame, &sheet, 0, 0, 2, 4, &palette, 0);
+    render_sprite(&mut surface, &mut source, 2, true, false, &frame, &sheet, 0, 0, 20, 4, &palette, 0);
+    render_sprite(&mut surface, &mut source, 3, false, true, &frame, &sheet, 0, 0, 38, 4, &palette, 0);
+    render_sprite(&mut surface, &mut source, 4, true, true, &frame_flipped, &sheet, 0, 0, 56, 4, &palette, 16);
+
+    // Split priorities, the bottom half is drawn below a layer chip and the top half above it.
+    render_sprite(&mut surface, &mut source, 5, false, true, &frame, &sheet, 0, 0, 76, 2, &palette, 0);
+    blit_bitmap_to_surface_and_source(&chip, &mut surface, &mut source, 0, 0, 8, 8, 80, 10, &palette, 16, 6, BitmapBlitFlags::SKIP_0);
+    render_sprite(&mut surface, &mut source, 7, true, false, &frame, &sheet, 0, 0, 76, 2, &palette, 0);
+
+    assert_golden("software_renderer/sprites", &surface);
+    assert_golden("software_renderer/sprites_source", &bitmap_to_surface(&source));
+}
+
+#[test]
+fn surface_blend_ops() {
+    let mut surface = gradient_surface(96, 40);
+
+    // Filled boxes with a translucent color, one column per blend op.
+    for (index, blend_op) in BLEND_OPS.iter().enumerate() {
+        let x = index as i32 * 16;
+        draw_box_filled(&mut surface, Rect::new(x + 2, 2, x + 14, 18), [200, 100, 50, 128], *blend_op);
+    }
+
+    // The same for surface blits, from a source with an alpha ramp.
+    let mut source = Surface::new(12, 16);
+    for y in 0..16 {
+        for x in 0..12 {
+            let dest = ((y * 12 + x) * 4) as usize;
+            source.data[dest..dest + 4].copy_from_slice(&[60, 180, 240, (x * 255 / 11) as u8]);
+        }
+    }
+    for (index, blend_op) in BLEND_OPS.iter().enumerate() {
+        let x = index as i32 * 16;
+        blit_surface_to_surface(&source, &mut surface, 0, 0, 12, 16, x + 2, 22, *blend_op);
+    }
+
+    // Blits clipped by every edge of the destination.
+    blit_surface_to_surface(&source, &mut surface, 0, 0, 12, 16, -6, -8, SurfaceBlendOps::Blend);
+    blit_surface_to_surface(&source, &mut surface, 0, 0, 12, 16, 90, 32, SurfaceBlendOps::Add);
+
+    assert_golden("software_renderer/blend_ops", &surface);
+}
+
+#[test]
+fn draw_primitives() {
+    let mut surface = Surface::new(64, 48);
+    surface.fill(BACKGROUND);
+
+    draw_box_gradient_vertical(&mut surface, Rect::new(2, 2, 30, 46), [231, 66, 8, 255], [24, 33, 140, 255], 8, SurfaceBlendOps::Copy);
+    draw_box_gradient_vertical(&mut surface, Rect::new(8, 8, 24, 40), [0, 0, 0, 255], [255, 255, 255, 255], 4, SurfaceBlendOps::Add);
+
+    // Lines in every octant from the center, and lines that need clipping.
+    let (cx, cy) = (46, 24);
+    for (dx, dy) in [(14, 4), (4, 14), (-4, 14), (-14, 4), (-14, -4), (-4, -14), (4, -14), (14, -4)] {
+        draw_line(&mut surface, cx, cy, cx + dx, cy + dy, [255, 255, 0, 255], SurfaceBlendOps::Copy);
+    }
+    draw_line(&mut surface, -10, 40, 80, 44, [0, 255, 255, 255], SurfaceBlendOps::Copy);
+    draw_line(&mut surface, 60, -5, 70, 60, [255, 0, 255, 160], SurfaceBlendOps::Blend);
+    draw_line(&mut surface, -20, -20, -5, -5, [255, 255, 255, 255], SurfaceBlendOps::Copy);
+
+    draw_box_filled(&mut surface, Rect::new(-4, 44, 70, 60), [255, 255, 255, 64], SurfaceBlendOps::Blend);
+
+    assert_golden("software_renderer/draw", &surface);
+}
+
+#[test]
+fn palette_rendering() {
+    let mut palette = test_palette();
+
+    // Append SNES BGR555 colors.
+    for value in [0x0000, 0x001F, 0x03E0, 0x7C00, 0x7FFF, 0x4210, 0x2D7F, 0x56B5] {
+        palette.colors.push(Palette::decode_snes_color(value));
+    }
+    palette.extend_to(48);
+
+    let mut surface = Surface::new(128, 24);
+    render_palette(&palette, &mut surface, 8);
+
+    assert_golden("software_renderer/palette", &surface);
+}
+
+#[test]
+fn bitmap_operations() {
+    let chip = test_chip();
+    let sheet = test_sprite_sheet();
+    let palette = test_palette();
+
+    let mut bitmap = Bitmap::new(64, 32);
+    blit_bitmap_to_bitmap(&sheet, &mut bitmap, 0, 0, 16, 16, 0, 0, BitmapBlitFlags::empty());
+    blit_bitmap_to_bitmap(&sheet, &mut bitmap, 0, 0, 16, 16, 16, 0, BitmapBlitFlags::FLIP_X | BitmapBlitFlags::FLIP_Y);
+    blit_bitmap_to_bitmap(&chip, &mut bitmap, 0, 0, 8, 8, 12, 4, BitmapBlitFlags::SKIP_0);
+
+    // Partial source rectangle and destination clipping.
+    blit_bitmap_to_bitmap(&sheet, &mut bitmap, 8, 8, 8, 8, 60, 28, BitmapBlitFlags::empty());
+
+    let scaled = chip.scale_linear(2.5);
+    blit_bitmap_to_bitmap(&scaled, &mut bitmap, 0, 0, scaled.width as i32, scaled.height as i32, 36, 2, BitmapBlitFlags::empty());
+
+    let mut surface = Surface::new(64, 32);
+    surface.fill(BACKGROUND);
+    blit_bitmap_to_surface(&bitmap, &mut surface, 0, 0, 64, 32, 0, 0, &palette, 0, BitmapBlitFlags::SKIP_0);
+
+    assert_golden("software_renderer/bitmap", &surface);
+}
+
+#[test]
+fn text() {
+    let ttf = sdl3::ttf::init().unwrap();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let font = ttf.load_font(root.join("data/chronotype/ChronoType.ttf"), 16.0).unwrap();
+    let font_small = ttf.load_font(root.join("data/bm_mini/BMmini.TTF"), 8.0).unwrap();
+
+    let mut surface = Surface::new(128, 64);
+    surface.fill([0, 0, 96, 255]);
+
+    let text = text_draw_to_surface("Chrono Trigger", &font, [231, 231, 231, 255], TextDrawFlags::SHADOW);
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 4, 2, SurfaceBlendOps::Blend);
+
+    let text = text_draw_to_surface("No shadow", &font_small, [255, 206, 0, 255], TextDrawFlags::empty());
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 4, 22, SurfaceBlendOps::Blend);
+
+    let text = text_draw_to_surface_wrapped("Wrapped text that spans several lines.", &font_small, [231, 231, 231, 255], TextDrawFlags::SHADOW, 60);
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 4, 32, SurfaceBlendOps::Blend);
+
+    assert_golden("software_renderer/text", &surface);
+}
+
+// Four 8x16 glyphs: two letters of different widths, an empty space and a tag glyph.
+fn test_bitmap_font() -> BitmapFont {
+    let mut bitmap = Bitmap::new(128, 16);
+    for glyph in [0, 1, 3] {
+        let width = [6, 5, 0, 8][glyph];
+        for y in 2..14 {
+            for x in 0..width - 1 {
+                let pixel = if x == 0 || y == 2 || y == 13 || (glyph == 3 && x == y % 7) { 1 } else { 0 };
+                bitmap.data[y * 128 + glyph * 8 + x] = pixel;
+                if pixel == 1 {
+                    bitmap.data[(y + 1).min(15) * 128 + glyph * 8 + x + 1] |= 2;
+                }
+            }
+        }
+    }
+
+    let glyph_map = [("A", 0), ("B", 1), (" ", 2), ("<HAND1>", 3)]
+        .iter()
+        .map(|(text, glyph)| (text.to_string(), *glyph))
+        .collect();
+
+    BitmapFont::new(bitmap, 8, 16, vec![6, 5, 3, 8], glyph_map)
+}
+
+#[test]
+fn bitmap_font() {
+    let font = test_bitmap_font();
+    assert_eq!(font.measure("AB A"), (20, 16));
+    assert_eq!(font.measure("A<HAND1>\nBB"), (14, 32));
+    assert!(!font.glyph_has_pixels(2));
+
+    let mut surface = Surface::new(64, 64);
+    surface.fill([0, 0, 96, 255]);
+
+    let text = font.draw_to_surface("AB<HAND1>A", [231, 231, 231, 255], TextDrawFlags::SHADOW, 0);
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 2, 2, SurfaceBlendOps::Blend);
+
+    // Cut off tags and characters without a glyph are skipped.
+    let text = font.draw_to_surface("BxA<HAN", [255, 206, 0, 255], TextDrawFlags::empty(), 0);
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 2, 20, SurfaceBlendOps::Blend);
+
+    let text = font.draw_to_surface("AB BA AB", [231, 231, 231, 255], TextDrawFlags::SHADOW, 26);
+    assert_eq!(text.height, 32);
+    blit_surface_to_surface(&text, &mut surface, 0, 0, text.width as i32, text.height as i32, 36, 20, SurfaceBlendOps::Blend);
+
+    assert_golden("software_renderer/bitmap_font", &surface);
+}
@@ -1,5 +1,6 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use regex::Regex;
+use crate::filesystem::text_decoder::font_8_glyph_map;
 use crate::party::party::Party;
 
 #[derive(Debug, PartialEq)]
@@ -19,11 +20,15 @@ pub enum TextPart {
 
     /// Center this line.
     CenterNextLine,
+
+    /// Continue to the next page without waiting for input.
+    AutoAdvance,
 }
 
 #[derive(Debug, PartialEq)]
 enum PartType {
     TagWait,
+    TagTech,
     TagOpen,
     Text,
 }
@@ -33,6 +38,10 @@ pub type TextPage = Vec<TextPart>;
 
 pub struct TextProcessor {
     replacements: HashMap<String, String>,
+    tech_names: Vec<String>,
+
+    // Tags that are font glyphs, and are drawn as text.
+    glyph_tags: HashSet<String>,
 
     regex_match_parts: Vec<Regex>,
 
@@ -42,39 +51,77 @@ pub struct TextProcessor {
 
 impl TextProcessor {
     pub fn new() -> TextProcessor {
+        let glyph_tags = font_8_glyph_map().into_keys()
+            .filter(|text| text.starts_with('<'))
+            .collect();
+
         TextProcessor {
             replacements: HashMap::new(),
+            tech_names: Vec::new(),
+            glyph_tags,
 
             regex_match_parts: [
-                Regex::new(r"^<WAIT>(.+?)</WAIT>").unwrap(),
+                Regex::new(r"^<WAIT>(.*?)</WAIT>").unwrap(),
+                Regex::new(r"^<NAME_TEC>(.*?)</NAME_TEC>").unwrap(),
                 Regex::new(r"^<(.+?)>").unwrap(),
                 Regex::new(r"^([^<]+)").unwrap(),
             ].to_vec(),
 
             regex_tag_variable: Regex::new(r"<(.+?)>").unwrap(),
-            regex_tag_number_bits: Regex::new(r"NUMBER (\d+)").unwrap(),
+            regex_tag_number_bits: Regex::new(r"^NUMBER (\d+)$").unwrap(),
         }
     }
 
     pub fn update_party_names(&mut self, party: &Party) {
 
-        // Add names for active party members.
-        for (index, character) in party.get_characters_iter().enumerate() {
+        // Add names for all characters.
+        for character in party.get_characters_iter() {
             self.replacements.insert(character.text_key.clone(), character.name.clone());
-            self.replacements.insert(format!("NAME_PT{}", index + 1), character.name.clone());
+        }
+
+        // Add names for active party members, in party order.
+        for (index, slot) in party.get_active_party_slots().enumerate() {
+            if let Some(character) = party.get_character(slot.character_id) {
+                self.replacements.insert(format!("NAME_PT{}", index + 1), character.name.clone());
+            }
         }
 
         // Crono nickname.
         self.replacements.insert("NICK_CRO".into(), party.get_character(0).unwrap().name.clone());
 
         // Epoch.
-        self.replacements.insert("NAME_SIL".into(), "Epoch".into());
+        self.replacements.entry("NAME_SIL".into()).or_insert_with(|| "Epoch".into());
 
         // Unused Queen Leene name.
         self.replacements.insert("NAME_LEENE".into(), "Leene".into());
     }
 
+    pub fn update_epoch_name(&mut self, name: &str) {
+        self.replacements.insert("NAME_SIL".into(), name.into());
+    }
+
+    pub fn get_epoch_name(&self) -> String {
+        self.replacements.get("NAME_SIL").cloned().unwrap_or_default()
+    }
+
+    /// Sets tech names, ordered by tech index.
+    pub fn set_tech_names(&mut self, names: Vec<String>) {
+        self.tech_names = names;
+    }
+
+    /// Splits text into pages, and prints a warning for any tags that could not be processed.
     pub fn process_dialog_text(&self, text: &str, result_value: u32, result_item: String) -> Vec<TextPage> {
+        let (pages, warnings) = self.process_dialog_text_checked(text, result_value, result_item);
+        for warning in warnings.iter() {
+            println!("Text warning: {}", warning);
+        }
+
+        pages
+    }
+
+    /// Splits text into pages, and returns them together with a list of warnings for any tags
+    /// that could not be processed.
+    pub fn process_dialog_text_checked(&self, text: &str, result_value: u32, result_item: String) -> (Vec<TextPage>, Vec<String>) {
 
         // Change some PC line break handling to something more sane.
         let text = text.replace("<CT>\\", "<BR><CT>");
@@ -90,13 +137,13 @@ impl TextProcessor {
         let mut index = 0;
         let mut pages: Vec<TextPage> = Vec::new();
         let mut page = TextPage::new();
-        loop {
-            let result = self.match_part(&text, index);
-            if result.is_none() {
+        let mut warnings: Vec<String> = Vec::new();
+        while index < text.len() {
+            let Some((match_type, match_contents, match_len)) = self.match_part(&text, index) else {
+                warnings.push(format!("Unterminated tag in \"{}\".", &text[index..]));
+                page.push(TextPart::Text { text: text[index..].to_string() });
                 break;
-            }
-
-            let (match_type, match_contents, match_len) = result.unwrap();
+            };
             index += match_len;
 
             match match_type {
@@ -109,18 +156,20 @@ impl TextProcessor {
                     if match_contents == "BR" {
                         page.push(TextPart::LineBreak);
 
-                    // Indentation.
-                    } else if match_contents == "INDENT" {
+                    // Indentation, and PC whitespace.
+                    } else if match_contents == "INDENT" || match_contents == "S10" {
                         page.push(TextPart::Text { text: "   ".into() });
 
-                    // New page, auto-progress is actually done by <WAIT>.
-                    } else if match_contents == "PAGE" || match_contents == "AUTO_PAGE" || match_contents == "AUTO_END" {
+                    // New page.
+                    } else if match_contents == "PAGE" {
                         pages.push(page);
                         page = TextPage::new();
 
-                    // Whitespace?
-                    } else if match_contents == "S10" {
-                        page.push(TextPart::Text { text: "   ".into() });
+                    // New page that is shown without waiting for input, after any <WAIT>.
+                    } else if match_contents == "AUTO_PAGE" || match_contents == "AUTO_END" {
+                        page.push(TextPart::AutoAdvance);
+                        pages.push(page);
+                        page = TextPage::new();
 
                     // Center this line.
                     } else if match_contents == "CT" {
@@ -136,21 +185,46 @@ impl TextProcessor {
 
                     // Match a sized number result.
                     } else if let Some(captures) = self.regex_tag_number_bits.captures(&match_contents) {
-                        let bits: usize = captures[1].parse::<usize>().unwrap();
-                        let value = match bits {
-                            8 => result_value & 0xFF,
-                            16 => result_value & 0xFFFF,
-                            24 => result_value & 0xFFFFFF,
-                            _ => panic!("Number result bits is {} but must be 8, 16 or 24.", bits),
+                        let value = match captures[1].parse::<usize>() {
+                            Ok(8) => result_value & 0xFF,
+                            Ok(16) => result_value & 0xFFFF,
+                            Ok(24) => result_value & 0xFFFFFF,
+                            _ => {
+                                warnings.push(format!("Number result bits in <{}> must be 8, 16 or 24.", match_contents));
+                                result_value
+                            },
                         };
                         page.push(TextPart::Text { text: value.to_string() });
+
+                    // Symbols that the font has a glyph for.
+                    } else if self.glyph_tags.contains(&format!("<{}>", match_contents)) {
+                        page.push(TextPart::Text { text: format!("<{}>", match_contents) });
+
+                    } else {
+                        warnings.push(format!("Unknown tag <{}>.", match_contents));
                     }
                 },
 
                 // Wait a number of ticks.
                 PartType::TagWait => {
-                    let ticks: u32 = u32::from_str_radix(&match_contents, 16).unwrap();
-                    page.push(TextPart::Delay { ticks: (ticks + 1) * 16 });
+                    match u32::from_str_radix(&match_contents, 16) {
+                        Ok(ticks) => page.push(TextPart::Delay { ticks: (ticks + 1) * 16 }),
+                        Err(_) => warnings.push(format!("Invalid wait time \"{}\".", match_contents)),
+                    }
+                },
+
+                // A tech name by index.
+                PartType::TagTech => {
+                    match usize::from_str_radix(&match_contents, 16) {
+                        Ok(tech) => {
+                            let name = match self.tech_names.get(tech) {
+                                Some(name) => name.clone(),
+                                None => format!("Tech {}", tech),
+                            };
+                            page.push(TextPart::Text { text: name });
+                        },
+                        Err(_) => warnings.push(format!("Invalid tech index \"{}\".", match_contents)),
+                    }
                 },
             };
         }
@@ -159,9 +233,7 @@ impl TextProcessor {
             pages.push(page);
         }
 
-        self.dump_pages(&pages);
-
-        pages
+        (pages, warnings)
     }
 
     pub fn replace_variables(&self, text: String) -> String {
@@ -196,8 +268,9 @@ impl TextProcessor {
                 let contents = captures[1].to_string();
                 let part = match regex_index {
                     0 => PartType::TagWait,
-                    1 => PartType::TagOpen,
-                    2 => PartType::Text,
+                    1 => PartType::TagTech,
+                    2 => PartType::TagOpen,
+                    3 => PartType::Text,
                     _ => panic!("Unknown match type."),
                 };
                 return Some((part, contents, len));
@@ -206,19 +279,179 @@ impl TextProcessor {
 
         None
     }
+}
 
-    fn dump_pages(&self, pages: &Vec<TextPage>) {
-        for page in pages.iter() {
-            println!("-------------------------------------");
-            for part in page.iter() {
-                match part {
-                    TextPart::Text { text } => print!("{}", text),
-                    TextPart::LineBreak => println!(),
-                    _ => {},
-                }
-            }
-            println!();
+/// Joins the text of a page into lines, without the timing and layout parts.
+pub fn page_lines(page: &TextPage) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for part in page.iter() {
+        match part {
+            TextPart::Text { text } => lines.last_mut().unwrap().push_str(text),
+            TextPart::LineBreak => lines.push(String::new()),
+            TextPart::Delay { .. } | TextPart::CenterNextLine | TextPart::AutoAdvance => {},
         }
-        println!("-------------------------------------");
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> TextPart {
+        TextPart::Text { text: text.to_string() }
+    }
+
+    fn process(processor: &TextProcessor, source: &str) -> (Vec<TextPage>, Vec<String>) {
+        processor.process_dialog_text_checked(source, 0x123456, "Tonic".to_string())
+    }
+
+    fn party_processor() -> TextProcessor {
+        let mut processor = TextProcessor::new();
+        processor.update_party_names(&Party::new());
+        processor
+    }
+
+    #[test]
+    fn tag_br() {
+        let (pages, warnings) = process(&TextProcessor::new(), "A<BR>B");
+        assert_eq!(pages, vec![vec![text("A"), TextPart::LineBreak, text("B")]]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn tag_pc_line_breaks() {
+        let (pages, _) = process(&TextProcessor::new(), "A\\B<CT>\\C<PAGE>\\D");
+        assert_eq!(pages, vec![
+            vec![text("A"), TextPart::LineBreak, text("B"), TextPart::LineBreak, TextPart::CenterNextLine, text("C")],
+            vec![text("D")],
+        ]);
+    }
+
+    #[test]
+    fn tag_indent() {
+        let (pages, _) = process(&TextProcessor::new(), "<INDENT>A<S10>B");
+        assert_eq!(pages, vec![vec![text("   "), text("A"), text("   "), text("B")]]);
+    }
+
+    #[test]
+    fn tag_page() {
+        let (pages, _) = process(&TextProcessor::new(), "A<PAGE>B");
+        assert_eq!(pages, vec![vec![text("A")], vec![text("B")]]);
+    }
+
+    #[test]
+    fn tag_auto_page() {
+        let (pages, warnings) = process(&TextProcessor::new(), "A<WAIT>03</WAIT><AUTO_PAGE>B<AUTO_END>");
+        assert_eq!(pages, vec![
+            vec![text("A"), TextPart::Delay { ticks: 64 }, TextPart::AutoAdvance],
+            vec![text("B"), TextPart::AutoAdvance],
+        ]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn tag_wait() {
+        let (pages, warnings) = process(&TextProcessor::new(), "A<WAIT>0f</WAIT><BR>B<WAIT>zz</WAIT>");
+        assert_eq!(pages, vec![vec![text("A"), TextPart::Delay { ticks: 256 }, TextPart::LineBreak, text("B")]]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn tag_ct() {
+        let (pages, _) = process(&TextProcessor::new(), "<CT>A");
+        assert_eq!(pages, vec![vec![TextPart::CenterNextLine, text("A")]]);
+    }
+
+    #[test]
+    fn tag_number() {
+        let (pages, warnings) = process(&TextProcessor::new(), "<NUMBER>/<NUMBER 8>/<NUMBER 16>/<NUMBER 24>");
+        assert_eq!(pages, vec![vec![
+            text("1193046"), text("/"), text("86"), text("/"), text("13398"), text("/"), text("1193046"),
+        ]]);
+        assert!(warnings.is_empty());
+
+        // Unsupported sizes use the whole value.
+        let (pages, warnings) = process(&TextProcessor::new(), "<NUMBER 12><NUMBER 99999999999999999999>");
+        assert_eq!(pages, vec![vec![text("1193046"), text("1193046")]]);
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn tag_name_itm() {
+        let (pages, _) = process(&TextProcessor::new(), "Got <NAME_ITM>!");
+        assert_eq!(pages, vec![vec![text("Got "), text("Tonic"), text("!")]]);
+    }
+
+    #[test]
+    fn tag_name_tec() {
+        let mut processor = TextProcessor::new();
+        let (pages, _) = process(&processor, "<NAME_TEC>02</NAME_TEC>");
+        assert_eq!(pages, vec![vec![text("Tech 2")]]);
+
+        processor.set_tech_names(vec!["Cyclone".to_string(), "Slash".to_string(), "Lightning".to_string()]);
+        let (pages, warnings) = process(&processor, "<NAME_TEC>02</NAME_TEC>!<NAME_TEC>x</NAME_TEC>");
+        assert_eq!(pages, vec![vec![text("Lightning"), text("!")]]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn tag_character_names() {
+        let (pages, warnings) = process(&party_processor(), "<NAME_CRO><NAME_MAR><NAME_LUC><NAME_ROB><NAME_FRO><NAME_AYL><NAME_MAG>");
+        assert_eq!(pages, vec![vec![text("CronoMarleLuccaRoboFrogAylaMagus")]]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn tag_party_names() {
+        let mut party = Party::new();
+        party.add_character_to_party(4);
+        let mut processor = TextProcessor::new();
+        processor.update_party_names(&party);
+
+        let (pages, _) = process(&processor, "<NAME_PT1>,<NAME_PT2>,<NAME_PT3>");
+        let names: Vec<String> = party.get_active_party_slots()
+            .map(|slot| party.get_character(slot.character_id).unwrap().name.clone())
+            .collect();
+        assert_eq!(pages, vec![vec![text(&names.join(","))]]);
+    }
+
+    #[test]
+    fn tag_other_names() {
+        let mut processor = party_processor();
+        let (pages, _) = process(&processor, "<NICK_CRO> <NAME_SIL> <NAME_LEENE>");
+        assert_eq!(pages, vec![vec![text("Crono Epoch Leene")]]);
+
+        // A renamed Epoch keeps its name when party names are updated.
+        processor.update_epoch_name("Wings");
+        processor.update_party_names(&Party::new());
+        let (pages, _) = process(&processor, "<NAME_SIL>");
+        assert_eq!(pages, vec![vec![text("Wings")]]);
+    }
+
+    #[test]
+    fn tag_glyphs() {
+        let (pages, warnings) = process(&TextProcessor::new(), "<HAND1>Go");
+        assert_eq!(pages, vec![vec![text("<HAND1>"), text("Go")]]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn tag_unknown() {
+        let (pages, warnings) = process(&TextProcessor::new(), "A<UNKNOWN04><SPCH 11>B<NAME_CRO>");
+        assert_eq!(pages, vec![vec![text("A"), text("B")]]);
+        assert_eq!(warnings, vec![
+            "Unknown tag <UNKNOWN04>.".to_string(),
+            "Unknown tag <SPCH 11>.".to_string(),
+            "Unknown tag <NAME_CRO>.".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn tag_unterminated() {
+        let (pages, warnings) = process(&TextProcessor::new(), "A<BR");
+        assert_eq!(pages, vec![vec![text("A"), text("<BR")]]);
+        assert_eq!(warnings.len(), 1);
     }
 }
@@ -1,29 +1,30 @@
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Error, ErrorKind, Read};
 use byteorder::{LittleEndian, ReadBytesExt};
 
-pub fn read_script_blob(data: &mut Cursor<Vec<u8>>) -> ([u8; 64], usize) {
-    let data_len = data.read_u16::<LittleEndian>().unwrap() as usize - 2;
+pub fn read_script_blob(data: &mut Cursor<Vec<u8>>) -> Result<([u8; 64], usize), Error> {
+    let data_len = (data.read_u16::<LittleEndian>()? as usize).checked_sub(2)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Blob data length is smaller than its header"))?;
     if data_len > 64 {
-        panic!("Blob data of {} bytes is larger than the supported 64 bytes.", data_len);
+        return Err(Error::new(ErrorKind::InvalidData, format!("Blob data of {} bytes is larger than the supported 64 bytes", data_len)));
     }
 
     let mut blob = vec![0u8; data_len];
-    data.read_exact(&mut blob).unwrap();
+    data.read_exact(&mut blob)?;
 
     let mut blob_out = [0u8; 64];
     for i in 0..data_len {
         blob_out[i] = blob[i];
     }
-    (blob_out, data_len)
+    Ok((blob_out, data_len))
 }
 
-pub fn read_24_bit_address(data: &mut Cursor<Vec<u8>>) -> usize {
-    data.read_u8().unwrap() as usize |
-        (data.read_u8().unwrap() as usize) << 8 |
-        (data.read_u8().unwrap() as usize) << 16
+pub fn read_24_bit_address(data: &mut Cursor<Vec<u8>>) -> Result<usize, Error> {
+    Ok(data.read_u8()? as usize |
+        (data.read_u8()? as usize) << 8 |
+        (data.read_u8()? as usize) << 16)
 }
 
-pub fn read_segmented_address(data: &mut Cursor<Vec<u8>>) -> usize {
-    data.read_u8().unwrap() as usize |
-        (data.read_u8().unwrap() as usize) << 8
+pub fn read_segmented_address(data: &mut Cursor<Vec<u8>>) -> Result<usize, Error> {
+    Ok(data.read_u8()? as usize |
+        (data.read_u8()? as usize) << 8)
 }
@@ -0,0 +1,87 @@
+/// Matches a path against a glob pattern. `?` matches a single character and `*` any number of
+/// characters, both within a single path component. `**` also matches across path separators.
+/// Matching is ASCII case-insensitive, like the Windows filesystem the game data comes from.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let path: Vec<char> = path.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+    match_from(&pattern, &path)
+}
+
+fn match_from(pattern: &[char], path: &[char]) -> bool {
+    if pattern.is_empty() {
+        return path.is_empty();
+    }
+
+    match pattern[0] {
+        '*' if pattern.get(1) == Some(&'*') => {
+            let rest = &pattern[2..];
+
+            // "**/" matches zero or more whole directories.
+            if rest.first() == Some(&'/') {
+                let rest = &rest[1..];
+                return match_from(rest, path) ||
+                    path.iter().enumerate().any(|(index, c)| *c == '/' && match_from(rest, &path[index + 1..]));
+            }
+
+            (0..=path.len()).any(|index| match_from(rest, &path[index..]))
+        },
+        '*' => {
+            for index in 0..=path.len() {
+                if match_from(&pattern[1..], &path[index..]) {
+                    return true;
+                }
+                if index < path.len() && path[index] == '/' {
+                    return false;
+                }
+            }
+
+            false
+        },
+        '?' => !path.is_empty() && path[0] != '/' && match_from(&pattern[1..], &path[1..]),
+        c => !path.is_empty() && path[0] == c && match_from(&pattern[1..], &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob_match("Game/field/*.dat", "Game/field/empty.dat"));
+        assert!(!glob_match("Game/field/*.dat", "Game/field/Mapinfo/mapinfo_0.dat"));
+        assert!(glob_match("Game/*/empty.dat", "Game/field/empty.dat"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("*", "Game/field"));
+
+        assert!(glob_match("mapinfo_?.dat", "mapinfo_0.dat"));
+        assert!(!glob_match("mapinfo_?.dat", "mapinfo_10.dat"));
+        assert!(!glob_match("Game?field", "Game/field"));
+
+        assert!(glob_match("exact.txt", "exact.txt"));
+        assert!(!glob_match("exact.txt", "exact.txt2"));
+        assert!(!glob_match("exact.txt", "exact"));
+    }
+
+    #[test]
+    fn glob_directories() {
+        // "**/" matches zero or more whole directories.
+        assert!(glob_match("**/w_map.txt", "w_map.txt"));
+        assert!(glob_match("**/w_map.txt", "Localize/it/msg/w_map.txt"));
+        assert!(!glob_match("**/w_map.txt", "Localize/it/msg/xw_map.txt"));
+        assert!(glob_match("Localize/**/msg/*.txt", "Localize/msg/w_map.txt"));
+        assert!(glob_match("Localize/**/msg/*.txt", "Localize/it/msg/w_map.txt"));
+
+        // "**" without a separator matches anything, including separators.
+        assert!(glob_match("Game/**", "Game/field/Mapinfo/mapinfo_0.dat"));
+        assert!(glob_match("Game/**.dat", "Game/field/Mapinfo/mapinfo_0.dat"));
+        assert!(!glob_match("Game/**", "Localize/it/msg/w_map.txt"));
+    }
+
+    #[test]
+    fn glob_case_insensitive() {
+        assert!(glob_match("game/FIELD/*.DAT", "Game/field/empty.dat"));
+        assert!(glob_match("**/MAPINFO_?.dat", "Game/field/Mapinfo/mapinfo_0.dat"));
+    }
+}
@@ -0,0 +1,241 @@
+use std::io::{Error, ErrorKind};
+
+const MIN_COPY_LENGTH: usize = 3;
+
+// Size in bits of each kind of item, including its flag bit.
+const LITERAL_COST: usize = 9;
+const COPY_COST: usize = 17;
+
+#[derive(Clone, Copy)]
+enum Item {
+    Literal(u8),
+    Copy { length: usize, offset: usize },
+}
+
+/// Compresses data into the format read by lz_decompress. The small bit width variant allows
+/// longer copies from a smaller window than the large one. Data that compresses to more than
+/// 64 KiB cannot be stored in this format.
+pub fn lz_compress(data: &[u8], small_bit_width: bool) -> Result<Vec<u8>, Error> {
+    let items = find_items(data, small_bit_width);
+
+    // All groups of 8 items go in the first part. It is followed by a control byte that also
+    // holds the bit width, and if there are remaining items a pointer past a last group of them.
+    let full_count = items.len() - items.len() % 8;
+    let mut packed = Vec::new();
+    for group in items[..full_count].chunks(8) {
+        write_group(&mut packed, group, small_bit_width);
+    }
+
+    let mut last_group = Vec::new();
+    if full_count < items.len() {
+        write_group(&mut last_group, &items[full_count..], small_bit_width);
+    }
+
+    let total_size = 2 + packed.len() + 1 + if last_group.is_empty() { 0 } else { 2 + last_group.len() + 1 };
+    if total_size > 0xFFFF {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Compressed data is too large ({} bytes)", total_size)));
+    }
+
+    let mut output = Vec::with_capacity(total_size);
+    output.extend_from_slice(&(packed.len() as u16).to_le_bytes());
+    output.extend_from_slice(&packed);
+
+    let bit_width_flag = if small_bit_width { 0x40 } else { 0x00 };
+    if last_group.is_empty() {
+        output.push(bit_width_flag);
+    } else {
+        let end = (output.len() + 3 + last_group.len()) as u16;
+        output.push(bit_width_flag | (items.len() - full_count) as u8);
+        output.extend_from_slice(&end.to_le_bytes());
+        output.extend_from_slice(&last_group);
+        output.push(0);
+    }
+
+    Ok(output)
+}
+
+fn write_group(output: &mut Vec<u8>, items: &[Item], small_bit_width: bool) {
+    let flag_index = output.len();
+    output.push(0);
+
+    let mut flags = 0u8;
+    for (index, item) in items.iter().enumerate() {
+        match *item {
+            Item::Literal(value) => output.push(value),
+            Item::Copy { length, offset } => {
+                flags |= 1 << index;
+
+                let encoded_length = if small_bit_width {
+                    ((length - MIN_COPY_LENGTH) << 3) | (offset >> 8)
+                } else {
+                    ((length - MIN_COPY_LENGTH) << 4) | (offset >> 8)
+                };
+                output.push(offset as u8);
+                output.push(encoded_length as u8);
+            },
+        }
+    }
+
+    // A zero flag byte is read as 8 literals, so mark an unused item in shorter groups.
+    if flags == 0 && items.len() < 8 {
+        flags = 0x80;
+    }
+    output[flag_index] = flags;
+}
+
+// Finds the sequence of literals and copies with the smallest encoded size.
+fn find_items(data: &[u8], small_bit_width: bool) -> Vec<Item> {
+    let (max_length, max_offset) = if small_bit_width {
+        (MIN_COPY_LENGTH + 0x1F, 0x7FF)
+    } else {
+        (MIN_COPY_LENGTH + 0x0F, 0xFFF)
+    };
+
+    // Chains of earlier positions starting with the same 3 bytes.
+    let mut head = vec![usize::MAX; 0x10000];
+    let mut previous = vec![usize::MAX; data.len()];
+    let mut matches = vec![(0usize, 0usize); data.len()];
+    for pos in 0..data.len() {
+        if pos + MIN_COPY_LENGTH > data.len() {
+            break;
+        }
+
+        let hash = hash_at(data, pos);
+        let mut candidate = head[hash];
+        while candidate != usize::MAX && pos - candidate <= max_offset {
+            let limit = max_length.min(data.len() - pos);
+            let mut length = 0;
+            while length < limit && data[candidate + length] == data[pos + length] {
+                length += 1;
+            }
+            if length > matches[pos].0 {
+                matches[pos] = (length, pos - candidate);
+                if length == limit {
+                    break;
+                }
+            }
+            candidate = previous[candidate];
+        }
+
+        previous[pos] = head[hash];
+        head[hash] = pos;
+    }
+
+    // Cheapest encoding of the data from each position to the end.
+    let mut cost = vec![0usize; data.len() + 1];
+    let mut choice = vec![0usize; data.len()];
+    for pos in (0..data.len()).rev() {
+        cost[pos] = cost[pos + 1] + LITERAL_COST;
+        choice[pos] = 1;
+
+        let (length, _) = matches[pos];
+        for copy_length in MIN_COPY_LENGTH..=length {
+            let copy_cost = cost[pos + copy_length] + COPY_COST;
+            if copy_cost < cost[pos] {
+                cost[pos] = copy_cost;
+                choice[pos] = copy_length;
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        if choice[pos] == 1 {
+            items.push(Item::Literal(data[pos]));
+        } else {
+            items.push(Item::Copy { length: choice[pos], offset: matches[pos].1 });
+        }
+        pos += choice[pos];
+    }
+
+    items
+}
+
+fn hash_at(data: &[u8], pos: usize) -> usize {
+    ((data[pos] as usize) << 8 ^ (data[pos + 1] as usize) << 4 ^ data[pos + 2] as usize) & 0xFFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::lz_decompress::lz_decompress;
+
+    struct TestRandom {
+        state: u64,
+    }
+
+    impl TestRandom {
+        fn next(&mut self) -> u64 {
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            self.state
+        }
+    }
+
+    // Mixes random bytes, runs and repeats of earlier data, like graphics and map data.
+    fn test_buffer(random: &mut TestRandom, length: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(length);
+        while data.len() < length {
+            let count = (random.next() % 40) as usize + 1;
+            match random.next() % 4 {
+                0 => (0..count).for_each(|_| data.push(random.next() as u8)),
+                1 => data.extend(std::iter::repeat_n(random.next() as u8, count)),
+                _ if !data.is_empty() => {
+                    let start = (random.next() as usize) % data.len();
+                    for index in 0..count {
+                        data.push(data[start + index % (data.len() - start)]);
+                    }
+                },
+                _ => {},
+            }
+        }
+        data.truncate(length);
+
+        data
+    }
+
+    fn assert_round_trip(data: &[u8], small_bit_width: bool) {
+        let compressed = lz_compress(data, small_bit_width).unwrap();
+
+        // Decompress from a non-zero start, the pointer to the last group is relative to it.
+        let mut source = vec![0xEE; 3];
+        source.extend_from_slice(&compressed);
+        let mut output = vec![0u8; 0x10000];
+        let length = lz_decompress(&source, &mut output, 3);
+
+        assert_eq!(length, data.len());
+        assert_eq!(&output[..length], data);
+    }
+
+    #[test]
+    fn round_trip_edge_cases() {
+        for small_bit_width in [false, true] {
+            assert_round_trip(&[], small_bit_width);
+            for length in 1..=20 {
+                assert_round_trip(&vec![0; length], small_bit_width);
+                assert_round_trip(&(0..length as u8).collect::<Vec<u8>>(), small_bit_width);
+            }
+            assert_round_trip(&vec![0xAB; 0x8000], small_bit_width);
+        }
+    }
+
+    #[test]
+    fn round_trip_random_buffers() {
+        let mut random = TestRandom { state: 0x9E3779B97F4A7C15 };
+        for _ in 0..200 {
+            let length = (random.next() % 0x2000) as usize;
+            let data = test_buffer(&mut random, length);
+            assert_round_trip(&data, false);
+            assert_round_trip(&data, true);
+        }
+    }
+
+    #[test]
+    fn incompressible_data_is_too_large() {
+        let mut random = TestRandom { state: 0x2545F4914F6CDD1D };
+        let data: Vec<u8> = (0..0xFFFF).map(|_| random.next() as u8).collect();
+        assert!(lz_compress(&data, false).is_err());
+    }
+}
@@ -8,7 +8,7 @@ pub fn lz_decompress(data: &Vec<u8>, data_out: &mut Vec<u8>, start: usize) -> us
     let mut bits_count: u8;
     let mut cur_byte: u8;
     let mut work_pos: usize = 0;
-    let small_bit_width: bool = data[byte_after] & 0xC0 != 0;
+    let small_bit_width: bool = lz_small_bit_width(data, start);
 
     bits_count = 8;
     loop {
@@ -93,6 +93,26 @@ pub fn lz_decompress(data: &Vec<u8>, data_out: &mut Vec<u8>, start: usize) -> us
     }
 }
 
+/// Returns if the compressed data at start uses the small bit width variant, with longer copies
+/// from a smaller window.
+pub fn lz_small_bit_width(data: &[u8], start: usize) -> bool {
+    let compressed_size = ((data[start + 1] as u16 * 256) | data[start] as u16) as usize;
+    data[start + 2 + compressed_size] & 0xC0 != 0
+}
+
+/// Returns the size of the compressed data at start, including its size and control bytes.
+pub fn lz_compressed_size(data: &[u8], start: usize) -> usize {
+    let compressed_size = ((data[start + 1] as u16 * 256) | data[start] as u16) as usize;
+
+    // Each control byte either ends the data or points to the next one.
+    let mut control = start + 2 + compressed_size;
+    while data[control] & 0x3F != 0 {
+        control = start + ((data[control + 2] as u16 * 256) | data[control + 1] as u16) as usize;
+    }
+
+    control + 1 - start
+}
+
 fn copy_bytes(data: &Vec<u8>, data_out: &mut Vec<u8>, byte_pos: &mut usize, work_pos: &mut usize, small_bit_width: bool) {
     let mut byte_copy_count: u16;
     let mut byte_copy_off: u16;
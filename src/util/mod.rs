@@ -1,4 +1,5 @@
 pub mod lz_decompress;
+pub mod lz_compress;
 pub mod bmp_reader;
 pub mod timer;
 pub mod random;
@@ -6,3 +7,5 @@ pub mod vec2df64;
 pub mod vec2di32;
 pub mod rect;
 pub mod data_read;
+pub mod json;
+pub mod glob;
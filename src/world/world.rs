@@ -6,6 +6,9 @@ use crate::tileset::TileSet;
 use crate::world::world_exit::{WorldTrigger, WorldExit};
 use crate::world::world_map::WorldMap;
 
+// Number of worlds in the game.
+pub const WORLD_COUNT: usize = 8;
+
 pub struct World {
     pub index: usize,
 
@@ -1,6 +1,7 @@
 use std::path::Path;
 use crate::assets::Assets;
 use crate::Context;
+use crate::filesystem::error::FileSystemResult;
 use crate::party::character::CharacterId;
 use crate::software_renderer::blit::{blit_bitmap_to_surface, BitmapBlitFlags};
 use crate::software_renderer::palette::Palette;
@@ -13,15 +14,15 @@ pub struct WorldSprites {
 
 impl WorldSprites {
 
-    pub fn new(ctx: &mut Context, world_index: usize, sprite_graphics: [usize; 4]) -> WorldSprites {
-        let bitmap = ctx.fs.read_world_sprite_tiles_all(world_index, sprite_graphics);
+    pub fn new(ctx: &mut Context, world_index: usize, sprite_graphics: [usize; 4]) -> FileSystemResult<WorldSprites> {
+        let bitmap = ctx.fs.read_world_sprite_tiles_all(world_index, sprite_graphics)?;
         let bitmap_key = Assets::asset_key_bitmap_sprite_tiles_world();
         ctx.assets.add_bitmap(bitmap_key, bitmap);
 
-        WorldSprites {
+        Ok(WorldSprites {
             world_index,
             bitmap_key,
-        }
+        })
     }
 
     pub fn get_bitmap_key(&self) -> u64 {
@@ -29,15 +30,15 @@ impl WorldSprites {
     }
 
     // Replace part of the world sprite tile graphics with new data.
-    pub fn replace(&mut self, ctx: &mut Context, tiles_index: usize, offset: usize) {
+    pub fn replace(&mut self, ctx: &mut Context, tiles_index: usize, offset: usize) -> FileSystemResult<()> {
         let bitmap = ctx.assets.get_bitmap_mut(self.bitmap_key);
-        ctx.fs.read_world_sprite_tiles(self.world_index, tiles_index, offset, &mut bitmap.data);
+        ctx.fs.read_world_sprite_tiles(self.world_index, tiles_index, offset, &mut bitmap.data)
     }
 
     // Read player character sprites.
-    pub fn load_player_sprites(&mut self, ctx: &mut Context, party_index: usize, character: CharacterId) {
+    pub fn load_player_sprites(&mut self, ctx: &mut Context, party_index: usize, character: CharacterId) -> FileSystemResult<()> {
         let bitmap = ctx.assets.get_bitmap_mut(self.bitmap_key);
-        let src_pixels = ctx.fs.read_world_player_sprite_tiles();
+        let src_pixels = ctx.fs.read_world_player_sprite_tiles()?;
 
         // Copy player world sprites from external bitmap data into sprite bitmap data.
 
@@ -53,17 +54,21 @@ impl WorldSprites {
             let dest_offset = 0x5800 + party_index * 0x10 + (row * 0x80);
             bitmap.data[dest_offset..dest_offset + 0x10].copy_from_slice(&src_pixels[src_offset..src_offset + 0x10]);
         }
+
+        Ok(())
     }
 
     // Read epoch sprites.
-    pub fn load_epoch_sprites(&mut self, ctx: &mut Context, mode: usize) {
+    pub fn load_epoch_sprites(&mut self, ctx: &mut Context, mode: usize) -> FileSystemResult<()> {
         let bitmap = ctx.assets.get_bitmap_mut(self.bitmap_key);
-        let src_pixels = ctx.fs.read_world_epoch_sprite_tiles();
+        let src_pixels = ctx.fs.read_world_epoch_sprite_tiles()?;
 
         // Copy walking sprites to 8192 + start of player character.
         let src_offset = mode * 0x800;
         let dest_offset = 0x6000 + (mode * 0x1000);
         bitmap.data[dest_offset..dest_offset + 0x1000].copy_from_slice(&src_pixels[src_offset..src_offset + 0x1000]);
+
+        Ok(())
     }
 
     // Dump world sprite tiles to disk.
@@ -1,11 +1,12 @@
 pub mod world_script;
 pub mod world_script_ops;
-mod world_script_decoder;
+pub mod world_script_decoder;
 mod world_script_disassembler;
+pub mod world_script_export;
 pub mod world_animation_script;
 mod exec;
 mod functions;
-mod tasks;
+pub mod tasks;
 pub mod task_dispatch;
 pub mod world_actor;
 mod function_dispatch;
@@ -24,6 +24,10 @@ pub fn task_run_script(ctx: &mut Context, world_state: &mut WorldState, actor: &
         let result;
         if let Some(op) = op_decode(&mut world_state.script_data, ctx.mode) {
             result = match op {
+                _ if op_unimplemented(&op) => {
+                    println!("Unimplemented world script op {:?}", op);
+                    OpResult::Continue
+                }
                 Op::InitMemory => {
                     actor.memory.clear();
                     actor.script_current_address = 0;
@@ -346,3 +350,29 @@ pub fn task_run_script(ctx: &mut Context, world_state: &mut WorldState, actor: &
         }
     }
 }
+
+/// Returns true for ops that task_run_script skips because they are not implemented yet.
+pub fn op_unimplemented(op: &Op) -> bool {
+    matches!(op,
+        Op::Bind { .. } |
+        Op::CopyToVram { .. } |
+        Op::MosaicIn { .. } |
+        Op::MosaicOut { .. } |
+        Op::MoveExtended { .. } |
+        Op::MoveToX { .. } |
+        Op::MoveToY { .. } |
+        Op::MusicCommand { .. } |
+        Op::PaletteExtended { .. } |
+        Op::PlayMusic { .. } |
+        Op::PlayMusicS { .. } |
+        Op::PlaySound1 { .. } |
+        Op::PlaySound2 { .. } |
+        Op::SetTileR { .. } |
+        Op::Timer { .. }
+    )
+}
+
+/// Returns false if executing this op ends up in the unimplemented path of task_run_script.
+pub fn op_is_implemented(op: &Op) -> bool {
+    !op_unimplemented(op)
+}
@@ -1,4 +1,4 @@
-use std::io::Cursor;
+use std::io::{stdout, Cursor, Write};
 use byteorder::{LittleEndian, ReadBytesExt};
 use crate::assets::Assets;
 use crate::Context;
@@ -181,53 +181,59 @@ impl WorldAnimationScript {
     }
 
     pub fn disassemble(&mut self) {
+        self.write_disassembly(&mut stdout()).unwrap();
+    }
+
+    pub fn write_disassembly(&mut self, out: &mut dyn Write) -> std::io::Result<()> {
         let offsets = self.offsets.clone();
         for (index, offset) in offsets.iter().enumerate() {
             let mut op_address = *offset;
 
-            println!("Anim {} @ {:04X}", index, offset);
+            writeln!(out, "Anim {} @ {:04X}    // {}", index, offset, get_animation_description(index))?;
             loop {
                 self.data.set_position(op_address);
                 let op = self.decode(op_address);
 
                 match op {
                     WorldAnimationOp::Reset { address } => {
-                        println!("  {:04X} reset 0x{:02X}", op_address, address);
+                        writeln!(out, "  {:04X} reset 0x{:02X}", op_address, address)?;
                     }
                     WorldAnimationOp::Increment { address } => {
-                        println!("  {:04X} inc 0x{:02X}", op_address, address);
+                        writeln!(out, "  {:04X} inc 0x{:02X}", op_address, address)?;
                     }
                     WorldAnimationOp::Decrement { address } => {
-                        println!("  {:04X} dec 0x{:02X}", op_address, address);
+                        writeln!(out, "  {:04X} dec 0x{:02X}", op_address, address)?;
                     }
                     WorldAnimationOp::Goto { offset } => {
-                        println!("  {:04X} goto 0x{:04X}", op_address, op_address as i64 + offset);
+                        writeln!(out, "  {:04X} goto 0x{:04X}", op_address, op_address as i64 + offset)?;
                         break;
                     }
                     WorldAnimationOp::Wait { duration } => {
-                        println!("  {:04X} wait {}", op_address, duration);
+                        writeln!(out, "  {:04X} wait {}", op_address, duration)?;
                         if duration == 0 {
                             break;
                         }
                     }
                     WorldAnimationOp::Animate { duration, assembly_address } => {
-                        println!("  {:04X} animate 0x{:02X} {}", op_address, assembly_address - 0xE000, duration);
+                        writeln!(out, "  {:04X} animate 0x{:02X} {}", op_address, assembly_address - 0xE000, duration)?;
                         if duration == 0 {
                             break;
                         }
                     }
                     WorldAnimationOp::CopyToVram { source_address, vram_dest_address, byte_count } => {
-                        println!("  {:04X} copy_to_vram 0x{:06X} 0x{:04X} {}", op_address, source_address, vram_dest_address, byte_count);
+                        writeln!(out, "  {:04X} copy_to_vram 0x{:06X} 0x{:04X} {}", op_address, source_address, vram_dest_address, byte_count)?;
                     }
                     WorldAnimationOp::Nop => {
-                        println!("  {:04X} unknown07", op_address);
+                        writeln!(out, "  {:04X} unknown07", op_address)?;
                     }
                 }
 
                 op_address = self.data.position()
             }
-            println!();
+            writeln!(out)?;
         }
+
+        Ok(())
     }
 
     fn read_sprite_assembly(&mut self, ctx: &mut Context, assembly_address: u64) -> u64 {
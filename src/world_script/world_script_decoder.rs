@@ -18,10 +18,18 @@ pub fn op_decode(data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Option<Op> {
         }
     };
 
-    op_decode_internal(op_byte, data, mode).ok()
+    match op_decode_opcode(op_byte, data, mode) {
+        Ok(op) => Some(op),
+        Err(error) => {
+            println!("{} at 0x{:04X}.", error, data.position());
+            None
+        },
+    }
 }
 
-fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
+/// Decodes the op for an already read opcode byte. Unknown opcodes result in an unsupported
+/// error, truncated data in an unexpected end of file error.
+pub fn op_decode_opcode(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -> Result<Op, Error> {
     let op = match op_byte {
 
         // Misc. ops.
@@ -41,13 +49,13 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
         // "pal", source data address, number of colors, mode
         0x04 => Op::PaletteLoad {
-            address: read_24_bit_address(data),
+            address: read_24_bit_address(data)?,
             palette_index: data.read_u8()?,
             mode: data.read_u8()?,
         },
         // "mapjump"
         0x05 => Op::ChangeLocation {
-            destination: Destination::from_cursor(data, mode),
+            destination: Destination::from_cursor(data, mode)?,
         },
         // "initscreen"
         0x3E => Op::InitBackgroundLayer {
@@ -224,7 +232,7 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         // Jumps.
         // "jp"
         0x1A => Op::GoTo {
-            address: data.read_u16::<LittleEndian>()? as u64 - 0x400 + 1,
+            address: read_address(data)? + 1,
         },
         // "jdjnz"
         0x1B => {
@@ -373,7 +381,7 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         // Map changes.
         // "putmap"
         0x07 => Op::SetTile {
-            layer: data.read_u8()? as usize - 1,
+            layer: read_layer(data)? as usize,
             x: data.read_u8()? as usize,
             y: data.read_u8()? as usize,
             tile_index: data.read_u8()? as usize,
@@ -390,10 +398,10 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
         // "copymap"
         0x4F => Op::CopyTiles {
-            source_layer: data.read_u8()? as usize - 1,
+            source_layer: read_layer(data)? as usize,
             source_x: data.read_u8()? as usize,
             source_y: data.read_u8()? as usize,
-            dest_layer: data.read_u8()? as usize - 1,
+            dest_layer: read_layer(data)? as usize,
             dest_x: data.read_u8()? as usize,
             dest_y: data.read_u8()? as usize,
             width: data.read_u8()? as usize,
@@ -401,7 +409,7 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
         // "putmapr"
         0x50 => Op::SetTileR {
-            layer: data.read_u8()? - 1,
+            layer: read_layer(data)?,
             x: data.read_u8()?,
             y: data.read_u8()?,
             tile_index: data.read_u8()?,
@@ -431,7 +439,7 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
         // "scrollr
         0x51 => Op::ScrollLayer {
-            layer: (data.read_u8()? - 1) as usize,
+            layer: read_layer(data)? as usize,
             steps: data.read_u8()?,
         },
         // "tpxmove"
@@ -450,12 +458,12 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         // Function calls/new objects.
         // "bind"
         0x08 => Op::Bind {
-            address: data.read_u16::<LittleEndian>()? as u64 - 0x400,
+            address: read_address(data)?,
             pc: data.read_u8()?,
         },
         // "newevent"
         0x09 => Op::AddActor {
-            address: data.read_u16::<LittleEndian>()? as u64 - 0x400,
+            address: read_address(data)?,
             unused: data.read_u8()?,
         },
         // "func"
@@ -476,7 +484,7 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         },
         // "call"
         0x36 => Op::GoSub {
-            address: data.read_u16::<LittleEndian>()? as u64 - 0x400,
+            address: read_address(data)?,
         },
         // "return"
         0x37 => Op::Return,
@@ -491,13 +499,13 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
         // "s_newevent"
         0x43 => {
             Op::AddActorSpecial {
-                address: data.read_u16::<LittleEndian>()? as u64 - 0x400,
+                address: read_address(data)?,
                 i0: data.read_u8()?,
             }
         },
         // "func2"
         0x4E => {
-            let address = read_24_bit_address(data) as u32;
+            let address = read_24_bit_address(data)? as u32;
             Op::CallFunctionFar {
                 function: WorldActorFunction::from_address(address, mode),
                 address,
@@ -568,11 +576,22 @@ fn op_decode_internal(op_byte: u8, data: &mut Cursor<Vec<u8>>, mode: GameMode) -
             i3: data.read_u8()?,
         },
 
-        _ => {
-            println!("Unimplemented opcode 0x{:02X}", op_byte);
-            return Err(Error::new(ErrorKind::Other, format!("Unimplemented opcode 0x{:02X}", op_byte)));
-        },
+        _ => return Err(Error::new(ErrorKind::Unsupported, format!("Unimplemented opcode 0x{:02X}", op_byte))),
     };
 
     Ok(op)
 }
+
+// Script addresses are relative to the start of the script data at 0x400.
+fn read_address(data: &mut Cursor<Vec<u8>>) -> Result<u64, Error> {
+    let address = data.read_u16::<LittleEndian>()? as u64;
+    address.checked_sub(0x400)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Script address 0x{:04X} is before the script data", address)))
+}
+
+// Layers are 1-based in script data.
+fn read_layer(data: &mut Cursor<Vec<u8>>) -> Result<u8, Error> {
+    let layer = data.read_u8()?;
+    layer.checked_sub(1)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Layer 0 is invalid"))
+}
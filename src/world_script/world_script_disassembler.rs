@@ -1,9 +1,11 @@
-use std::collections::{HashMap, HashSet};
-use std::io::Cursor;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{stdout, Cursor, Write};
 use crate::GameMode;
 use crate::music_list::get_music_title;
 use crate::sound_list::get_sound_name;
 use crate::world::world_exit::WorldTrigger;
+use crate::world_script::function_dispatch::WorldActorFunction;
+use crate::world_script::task_dispatch::WorldActorTask;
 use crate::world_script::world_script_decoder::op_decode;
 use crate::world_script::world_script_ops::Op;
 use crate::world_script::world_animation_script::get_animation_description;
@@ -14,6 +16,10 @@ pub struct WorldScriptDisassembler {
     mode: GameMode,
     scripted_exit_map: HashMap<u64, usize>,
     labels: HashMap<u64, HashSet<String>>,
+
+    // Addresses of unknown tasks and functions, with the script addresses that refer to them.
+    pub unknown_tasks: BTreeMap<u32, Vec<u64>>,
+    pub unknown_functions: BTreeMap<u32, Vec<u64>>,
 }
 
 impl WorldScriptDisassembler {
@@ -30,6 +36,8 @@ impl WorldScriptDisassembler {
             mode,
             scripted_exit_map,
             labels: HashMap::new(),
+            unknown_tasks: BTreeMap::new(),
+            unknown_functions: BTreeMap::new(),
         }
     }
 
@@ -57,6 +65,19 @@ impl WorldScriptDisassembler {
                     _ => {}
                 };
 
+                // Track unknown task and function targets.
+                match op {
+                    Op::Link { task: WorldActorTask::Unknown { address }, .. } |
+                    Op::LinkSpecial { task: WorldActorTask::Unknown { address }, .. } => {
+                        self.unknown_tasks.entry(address).or_default().push(op_address);
+                    },
+                    Op::CallFunction { function: WorldActorFunction::Unknown { address }, .. } |
+                    Op::CallFunctionFar { function: WorldActorFunction::Unknown { address }, .. } => {
+                        self.unknown_functions.entry(address).or_default().push(op_address);
+                    },
+                    _ => {},
+                };
+
                 if let Some(script_index) = self.scripted_exit_map.get(&op_address) {
                     self.add_label(op_address, format!("exit_{:02}", script_index));
                 }
@@ -74,6 +95,10 @@ impl WorldScriptDisassembler {
     }
 
     pub fn dump(&mut self) {
+        self.write(&mut stdout()).unwrap();
+    }
+
+    pub fn write(&mut self, out: &mut dyn Write) -> std::io::Result<()> {
         let data_len = self.data.get_ref().len() as u64;
         let mut op_address = 0;
 
@@ -82,9 +107,11 @@ impl WorldScriptDisassembler {
 
             // Output generated labels.
             if self.labels.contains_key(&op_address) {
-                println!();
-                for label in self.labels[&op_address].iter() {
-                    println!("{:<48}    // {:04X}", format!("{}:", label), op_address);
+                writeln!(out)?;
+                let mut labels: Vec<&String> = self.labels[&op_address].iter().collect();
+                labels.sort();
+                for label in labels {
+                    writeln!(out, "{:<48}    // {:04X}", format!("{}:", label), op_address)?;
                 }
             }
 
@@ -118,8 +145,8 @@ impl WorldScriptDisassembler {
                     Op::JumpConditional { lhs, cmp, rhs, offset } => {
                         format!("if {} {} {} goto jp_{:04X}", lhs.as_string(), cmp.as_string(), rhs.as_string(), op_address as i64 + offset)
                     },
-                    Op::Link { task, .. } => format!("link {}", task.as_string()),
-                    Op::LinkSpecial { task, .. } => format!("link_special {}", task.as_string()),
+                    Op::Link { task, address } => format!("link {}    // Task: 0x{:04X}", task.as_string(), address),
+                    Op::LinkSpecial { task, address } => format!("link_special {}    // Task: 0x{:04X}", task.as_string(), address),
                     Op::MosaicIn { mode } => format!("mosaic_in {}", mode),
                     Op::MosaicOut { mode } => format!("mosaic_out {}", mode),
                     Op::Move { steps } => format!("move {}", steps),
@@ -160,10 +187,12 @@ impl WorldScriptDisassembler {
                     Op::ExitOpen { exit_type, exit_index } => format!("exit_open {} {}", exit_type, exit_index),
                 };
 
-                println!("  {:<48}  // {:04X}", statement, op_address);
+                writeln!(out, "  {:<48}  // {:04X}", statement, op_address)?;
             }
 
             op_address = self.data.position();
         }
+
+        Ok(())
     }
 }
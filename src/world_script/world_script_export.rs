@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::filesystem::filesystem::FileSystem;
+use crate::l10n::{IndexedType, L10n};
+use crate::world::world::WORLD_COUNT;
+use crate::world::world_exit::WorldExitType;
+use crate::world_script::function_dispatch::WorldActorFunction;
+use crate::world_script::task_dispatch::WorldActorTask;
+use crate::world_script::world_script_disassembler::WorldScriptDisassembler;
+
+// Where an unknown task or function address is referenced from.
+struct UnknownReference {
+    world_index: usize,
+    script_address: u64,
+}
+
+/// Writes an annotated world script disassembly for every world to the output path, together
+/// with a summary of all unknown task and function addresses found in them.
+pub fn world_script_export(fs: &FileSystem, l10n: &L10n, output_path: &Path) -> std::io::Result<()> {
+    create_dir_all(output_path)?;
+
+    let mut unknown_tasks: BTreeMap<u32, Vec<UnknownReference>> = BTreeMap::new();
+    let mut unknown_functions: BTreeMap<u32, Vec<UnknownReference>> = BTreeMap::new();
+
+    for world_index in 0..WORLD_COUNT {
+        let world = fs.read_world(world_index)?;
+        let mut animations = fs.read_world_animation_script()?;
+
+        let mut disassembler = WorldScriptDisassembler::new(&world.script_data, &world.triggers, &world.script_offsets, fs.mode);
+        disassembler.disassemble();
+
+        let filename = output_path.join(format!("world_{:02}.txt", world_index));
+        println!("Writing {}...", filename.display());
+        let mut out = BufWriter::new(File::create(&filename)?);
+
+        writeln!(out, "// World {} - {}", world_index, l10n.get_indexed(IndexedType::World, world_index))?;
+        writeln!(out)?;
+
+        // Scripted exits and triggers, with the script addresses they start at.
+        writeln!(out, "// Scripted exits")?;
+        for exit in world.exits.iter() {
+            if let WorldExitType::Scripted { pointer_index } = exit.exit_type {
+                let address = world.script_offsets.get(pointer_index).copied().unwrap_or(0);
+                writeln!(out, "//   exit {:02} at {} - '{}', script address {} @ {:04X}", exit.index, exit.pos, l10n.get_indexed(IndexedType::WorldExit, exit.name_index), pointer_index, address)?;
+            }
+        }
+        writeln!(out)?;
+
+        writeln!(out, "// Triggers")?;
+        for trigger in world.triggers.iter() {
+            let address = world.script_offsets.get(trigger.script_address_index).copied().unwrap_or(0);
+            writeln!(out, "//   exit_{:02} at {}, script address {} @ {:04X}", trigger.index, trigger.pos, trigger.script_address_index, address)?;
+        }
+        writeln!(out)?;
+
+        // Unknown tasks and functions in this world.
+        if !disassembler.unknown_tasks.is_empty() || !disassembler.unknown_functions.is_empty() {
+            writeln!(out, "// Unknown targets")?;
+            for (address, references) in disassembler.unknown_tasks.iter() {
+                writeln!(out, "//   task {}, {} reference(s)", WorldActorTask::Unknown { address: *address }.as_string(), references.len())?;
+            }
+            for (address, references) in disassembler.unknown_functions.iter() {
+                writeln!(out, "//   function {}, {} reference(s)", WorldActorFunction::Unknown { address: *address }.as_string(), references.len())?;
+            }
+            writeln!(out)?;
+        }
+
+        writeln!(out, "// Script")?;
+        disassembler.write(&mut out)?;
+        writeln!(out)?;
+
+        writeln!(out, "// Animation script")?;
+        animations.write_disassembly(&mut out)?;
+
+        // Collect unknowns for the summary.
+        for (address, references) in disassembler.unknown_tasks.iter() {
+            let entry = unknown_tasks.entry(*address).or_default();
+            entry.extend(references.iter().map(|script_address| UnknownReference { world_index, script_address: *script_address }));
+        }
+        for (address, references) in disassembler.unknown_functions.iter() {
+            let entry = unknown_functions.entry(*address).or_default();
+            entry.extend(references.iter().map(|script_address| UnknownReference { world_index, script_address: *script_address }));
+        }
+    }
+
+    let filename = output_path.join("unknown.txt");
+    println!("Writing {}...", filename.display());
+    let mut out = BufWriter::new(File::create(&filename)?);
+
+    writeln!(out, "// Unknown world actor tasks, most referenced first")?;
+    write_unknown_summary(&mut out, &unknown_tasks, |address| WorldActorTask::Unknown { address }.as_string())?;
+    writeln!(out)?;
+
+    writeln!(out, "// Unknown world actor functions, most referenced first")?;
+    write_unknown_summary(&mut out, &unknown_functions, |address| WorldActorFunction::Unknown { address }.as_string())?;
+
+    Ok(())
+}
+
+fn write_unknown_summary(out: &mut dyn Write, unknowns: &BTreeMap<u32, Vec<UnknownReference>>, name: fn(u32) -> String) -> std::io::Result<()> {
+    let mut sorted: Vec<(&u32, &Vec<UnknownReference>)> = unknowns.iter().collect();
+    sorted.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+
+    for (address, references) in sorted {
+        let locations: Vec<String> = references.iter().map(|x| format!("world {} @ {:04X}", x.world_index, x.script_address)).collect();
+        writeln!(out, "{:<32} {:>4}    // {}", name(*address), references.len(), locations.join(", "))?;
+    }
+
+    Ok(())
+}